@@ -0,0 +1,80 @@
+//! 线程局部的异常检测模式。
+//!
+//! 开启后，每个运算在前向计算时记录创建结果的调用栈，反向传播时检查每个运算输出的梯度，
+//! 出现 NaN 或无穷时 panic，并指出产生它的运算及该运算在前向计算中被调用的位置。
+//! 记录调用栈的开销较大，只应在调试时开启。
+
+use crate::ops::Op;
+use ndarray::ArrayD;
+use std::backtrace::Backtrace;
+use std::cell::Cell;
+
+thread_local! {
+    static ANOMALY_ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 当前线程是否开启了异常检测
+pub fn is_anomaly_enabled() -> bool {
+    ANOMALY_ENABLED.with(Cell::get)
+}
+
+/// 异常检测守卫，析构时恢复进入前的模式
+#[must_use = "异常检测只在守卫存活期间生效"]
+#[derive(Debug)]
+pub struct AnomalyModeGuard {
+    prev: bool,
+}
+
+impl Drop for AnomalyModeGuard {
+    fn drop(&mut self) {
+        ANOMALY_ENABLED.with(|a| a.set(self.prev));
+    }
+}
+
+/// 在守卫存活期间按 `enabled` 开启或关闭异常检测
+pub fn set_detect_anomaly(enabled: bool) -> AnomalyModeGuard {
+    let prev = ANOMALY_ENABLED.with(|a| a.replace(enabled));
+    AnomalyModeGuard { prev }
+}
+
+/// 在守卫存活期间开启异常检测，前向计算和反向传播都需要在守卫内进行，例如：
+///
+/// ```
+/// let _guard = torch_rs::autograd::detect_anomaly();
+/// ```
+pub fn detect_anomaly() -> AnomalyModeGuard {
+    set_detect_anomaly(true)
+}
+
+/// 开启异常检测时记录当前调用栈，作为运算结果的创建位置
+pub(crate) fn capture_trace() -> Option<Backtrace> {
+    is_anomaly_enabled().then(Backtrace::force_capture)
+}
+
+/// 检查运算 `op` 在反向传播中输出的梯度，出现 NaN 或无穷时 panic
+pub(crate) fn check_gradients<'a>(
+    op: &dyn Op,
+    trace: Option<&Backtrace>,
+    grads: impl IntoIterator<Item = &'a ArrayD<f32>>,
+) {
+    for (i, grad) in grads.into_iter().enumerate() {
+        let kind = if grad.iter().any(|g| g.is_nan()) {
+            "nan"
+        } else if grad.iter().any(|g| g.is_infinite()) {
+            "inf"
+        } else {
+            continue;
+        };
+        let trace = match trace {
+            Some(trace) => format!("Traceback of forward call that caused the error:\n{}", trace),
+            None => "No forward traceback was recorded, enable detect_anomaly() before the forward pass to get one".to_string(),
+        };
+        panic!(
+            "Function {} returned {} values in its gradient for input {}.\n{}",
+            op.name(),
+            kind,
+            i,
+            trace
+        );
+    }
+}
@@ -0,0 +1,165 @@
+//! 激活检查点：前向计算时不保存一段计算的中间结果，反向传播时重新计算这一段。
+//!
+//! 被包裹的一段计算在计算图中只留下一个节点，它保存输入而不保存中间激活，
+//! 用一次额外的前向计算换取内存，适合很深的 `Sequential` 等结构。
+
+use super::forward_ad::{make_dual, unpack_dual};
+use super::{accumulates_leaf_grads, enable_grad, grad, grad_to_tensor, is_grad_enabled, no_grad};
+use crate::ops::{Op, needs_grad, propagate_tangent};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::ArrayD;
+use std::fmt;
+use std::sync::Arc;
+
+/// 被检查点包裹的一段计算
+pub(crate) type Segment = dyn Fn(&[Tensor]) -> Tensor + Send + Sync;
+
+/// 计算图中代表整段计算的节点，反向传播时重新计算这一段
+pub struct Checkpoint {
+    segment: Arc<Segment>,
+    inputs: Vec<SavedTensor>,
+    needs_input_grad: Vec<bool>,
+}
+
+impl fmt::Debug for Checkpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Checkpoint")
+            .field("needs_input_grad", &self.needs_input_grad)
+            .finish()
+    }
+}
+
+/// 对 `inputs` 计算 `segment`，不保存中间结果，反向传播时重新计算。
+///
+/// 经 `backward` 反向传播时，`segment` 用到的参数等叶子张量在重新计算后的反向传播中直接累加梯度，
+/// 但只有至少一个输入需要梯度时才会记录计算图；参数需要梯度而输入不需要时请使用 `nn::Checkpoint`。
+/// 经 `autograd::grad` 求导时，梯度只传给 `inputs`，不会写入 `segment` 捕获的参数的 `grad`。
+pub fn checkpoint<F>(segment: F, inputs: &[&Tensor]) -> Tensor
+where
+    F: Fn(&[Tensor]) -> Tensor + Send + Sync + 'static,
+{
+    checkpoint_segment(Arc::new(segment), inputs, false)
+}
+
+/// `params_require_grad` 为 true 时，即使输入都不需要梯度也记录计算图
+pub(crate) fn checkpoint_segment(
+    segment: Arc<Segment>,
+    inputs: &[&Tensor],
+    params_require_grad: bool,
+) -> Tensor {
+    let detached: Vec<Tensor> = inputs.iter().map(|input| input.detach()).collect();
+    let result = {
+        let _guard = no_grad();
+        segment(&detached).detach()
+    };
+
+    let op = Checkpoint {
+        segment,
+        inputs: Vec::new(),
+        needs_input_grad: Vec::new(),
+    };
+    propagate_tangent(&op, inputs, &result);
+    if needs_grad(inputs) || (params_require_grad && is_grad_enabled()) {
+        let op = Checkpoint {
+            inputs: inputs
+                .iter()
+                .map(|&input| SavedTensor::new(input))
+                .collect(),
+            needs_input_grad: inputs
+                .iter()
+                .map(|input| input.0.borrow().requires_grad)
+                .collect(),
+            ..op
+        };
+        let mut result_data = result.0.borrow_mut();
+        result_data.set_creator(Arc::new(op));
+        for &input in inputs {
+            result_data.add_parent(input);
+        }
+        result_data.requires_grad = true;
+    }
+    result
+}
+
+impl Op for Checkpoint {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        checkpoint_segment(Arc::clone(&self.segment), inputs, false)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let grad_output = grad_to_tensor(grad_output, parent);
+        // 按保存时的元素类型重建输入，f64 与复数输入的重新计算与原计算一致
+        let inputs: Vec<Tensor> = self
+            .inputs
+            .iter()
+            .zip(&self.needs_input_grad)
+            .map(|(input, &needs)| input.unpack_tensor().require_grad(needs))
+            .collect();
+
+        let _guard = enable_grad();
+        let output = (self.segment)(&inputs);
+        if !output.0.borrow().requires_grad {
+            return inputs
+                .iter()
+                .map(|input| ArrayD::zeros(input.0.borrow().grad_shape()))
+                .collect();
+        }
+        if !accumulates_leaf_grads() {
+            // 经 autograd::grad 到达：只求输入的梯度，不写入参数的 grad
+            let inputs: Vec<&Tensor> = inputs.iter().collect();
+            return grad(&[&output], &inputs, Some(&[&grad_output]), false)
+                .unwrap_or_else(|e| panic!("{}", e))
+                .iter()
+                .map(|grad| grad.0.borrow().data_as_real())
+                .collect();
+        }
+        // 经 backward 到达：在重新计算的计算图上反向传播，参数的梯度在此累加
+        output
+            .backward_with(&grad_output, false)
+            .unwrap_or_else(|e| panic!("{}", e));
+        inputs
+            .iter()
+            .map(|input| {
+                let mut data = input.0.borrow_mut();
+                let grad = data.grad.take();
+                grad.unwrap_or_else(|| ArrayD::zeros(data.grad_shape()))
+            })
+            .collect()
+    }
+
+    fn backward_graph(
+        &self,
+        inputs: &[Tensor],
+        _output: &Tensor,
+        grad_output: &Tensor,
+    ) -> Vec<Tensor> {
+        // 在原输入上重新计算这一段并记录计算图，梯度本身因此可以再次求导
+        let output = (self.segment)(inputs);
+        if !output.0.borrow().requires_grad {
+            return inputs.iter().map(Tensor::zeros_like).collect();
+        }
+        let inputs: Vec<&Tensor> = inputs.iter().collect();
+        grad(&[&output], &inputs, Some(&[grad_output]), true).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // 把切线附到输入上重新计算一遍
+        let duals: Vec<Tensor> = inputs
+            .iter()
+            .zip(tangents)
+            .map(|(&input, tangent)| match tangent {
+                Some(tangent) => make_dual(input, tangent).expect("Tangent matches its input"),
+                None => input.clone(),
+            })
+            .collect();
+        let (output, tangent) = unpack_dual(&(self.segment)(&duals));
+        tangent.unwrap_or_else(|| output.zeros_like())
+    }
+}
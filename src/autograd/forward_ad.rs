@@ -0,0 +1,110 @@
+//! 前向模式自动微分：切线（方向导数）随原值一起在前向计算中传播。
+//!
+//! 在 `dual_level()` 返回的守卫存活期间，用 `make_dual` 给输入附上切线，
+//! 之后每个运算都会通过 `Op::jvp` 算出结果的切线，最后用 `unpack_dual` 取出。
+//! 一次前向计算就能得到雅可比-向量积，不需要构建计算图或反向传播。
+
+use super::grad_to_tensor;
+use crate::tensor::Tensor;
+use ndarray::ArrayD;
+use std::cell::Cell;
+
+thread_local! {
+    static CURRENT_LEVEL: Cell<Option<usize>> = const { Cell::new(None) };
+    static NEXT_LEVEL: Cell<usize> = const { Cell::new(0) };
+}
+
+/// 当前线程正在使用的对偶层级
+fn current_level() -> Option<usize> {
+    CURRENT_LEVEL.with(Cell::get)
+}
+
+/// 对偶层级守卫，析构时结束该层级，层级内附上的切线随之失效
+#[must_use = "对偶层级只在守卫存活期间有效"]
+#[derive(Debug)]
+pub struct DualLevel {
+    _private: (),
+}
+
+impl Drop for DualLevel {
+    fn drop(&mut self) {
+        CURRENT_LEVEL.with(|level| level.set(None));
+    }
+}
+
+/// 进入一个新的对偶层级，不支持嵌套，例如：
+///
+/// ```
+/// use torch_rs::autograd::forward_ad::{dual_level, make_dual, unpack_dual};
+/// use torch_rs::tensor::Tensor;
+///
+/// let _level = dual_level();
+/// let x = make_dual(&Tensor::from(vec![1.0, 2.0]), &Tensor::from(vec![1.0, 0.0])).unwrap();
+/// let (_, tangent) = unpack_dual(&(&x * &x));
+/// assert_eq!(tangent.unwrap().data().into_raw_vec_and_offset().0, vec![2.0, 0.0]);
+/// ```
+pub fn dual_level() -> DualLevel {
+    assert!(
+        current_level().is_none(),
+        "Nested forward AD levels are not supported"
+    );
+    let level = NEXT_LEVEL.with(|next| {
+        let level = next.get();
+        next.set(level + 1);
+        level
+    });
+    CURRENT_LEVEL.with(|current| current.set(Some(level)));
+    DualLevel { _private: () }
+}
+
+/// 张量在当前对偶层级下的切线，不在层级内或切线属于已结束的层级时为 `None`。
+///
+/// 切线与梯度一样按 `view_as_real` 的形状保存，复数张量的切线取出时合成为复数张量。
+pub(crate) fn tangent_of(tensor: &Tensor) -> Option<Tensor> {
+    let level = current_level()?;
+    let tangent = match &tensor.0.borrow().tangent {
+        Some((tangent_level, tangent)) if *tangent_level == level => tangent.clone(),
+        _ => return None,
+    };
+    Some(grad_to_tensor(tangent, tensor))
+}
+
+/// 给张量附上切线（结果记录在当前层级），切线必须与张量同为实数或同为复数
+pub(crate) fn set_tangent(tensor: &Tensor, tangent: &Tensor) {
+    assert_eq!(
+        tensor.is_complex(),
+        tangent.is_complex(),
+        "Tangent must be complex exactly when the tensor is complex"
+    );
+    let level = current_level().expect("No active forward AD level");
+    let tangent: ArrayD<f32> = tangent.0.borrow().data_as_real();
+    tensor.0.borrow_mut().tangent = Some((level, tangent));
+}
+
+/// 返回带切线的对偶张量。
+///
+/// 结果与 `primal` 共享存储，并保留 `primal` 在计算图中的位置，因此仍可以反向传播。
+pub fn make_dual(primal: &Tensor, tangent: &Tensor) -> Result<Tensor, &'static str> {
+    if current_level().is_none() {
+        return Err("make_dual 只能在 dual_level 内使用");
+    }
+    if primal.shape() != tangent.shape() {
+        return Err("切线的形状必须与原张量相同");
+    }
+    if primal.is_complex() != tangent.is_complex() {
+        return Err("切线与原张量必须同为实数或同为复数");
+    }
+    let dual = primal.reshape(&primal.shape())?;
+    set_tangent(&dual, tangent);
+    Ok(dual)
+}
+
+/// 拆分对偶张量，返回不带切线的原值和当前层级下的切线（没有时为 `None`）
+pub fn unpack_dual(tensor: &Tensor) -> (Tensor, Option<Tensor>) {
+    let tangent = tangent_of(tensor);
+    let primal = tensor
+        .reshape(&tensor.shape())
+        .expect("Reshaping to the same shape cannot fail");
+    primal.0.borrow_mut().tangent = None;
+    (primal, tangent)
+}
@@ -0,0 +1,292 @@
+//! 自定义可微函数：实现 `Function` 的前向与反向计算，由框架负责连接计算图。
+//!
+//! 与直接实现 `ops::Op` 不同，不需要手动调用 `set_creator`、`add_parent` 或设置
+//! `requires_grad`：`Function::apply` 按输入的顺序记录全部父节点，并遵守梯度模式。
+//! 非张量参数可以作为实现类型的字段，前向中得到的中间结果可以保存在上下文里。
+
+use super::{grad_to_tensor, is_grad_enabled, no_grad};
+use crate::ops::{Op, needs_grad, propagate_tangent};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::ArrayD;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// 前向与反向计算之间共享的上下文
+pub struct FunctionCtx {
+    /// 前向计算期间的输入，用于识别保存的张量是否为输入
+    inputs: Vec<Tensor>,
+    input_shapes: Vec<Vec<usize>>,
+    input_complex: Vec<bool>,
+    needs_input_grad: Vec<bool>,
+    /// 保存的张量及其对应的输入位置
+    saved: Vec<(Option<usize>, SavedTensor)>,
+    values: HashMap<&'static str, Arc<dyn Any + Send + Sync>>,
+    /// `create_graph` 反向传播时的输入，保存的输入会换成它们以便继续求导
+    graph_inputs: Option<Vec<Tensor>>,
+}
+
+impl FunctionCtx {
+    fn new(inputs: &[&Tensor]) -> Self {
+        FunctionCtx {
+            inputs: inputs.iter().map(|&input| input.clone()).collect(),
+            input_shapes: inputs.iter().map(|input| input.shape()).collect(),
+            input_complex: inputs.iter().map(|input| input.is_complex()).collect(),
+            needs_input_grad: inputs
+                .iter()
+                .map(|input| is_grad_enabled() && input.0.borrow().requires_grad)
+                .collect(),
+            saved: Vec::new(),
+            values: HashMap::new(),
+            graph_inputs: None,
+        }
+    }
+
+    /// `create_graph` 反向传播使用的上下文：共享保存的数据，保存的输入换成 `inputs`。
+    ///
+    /// 每次反向传播各自构造，同一节点在多个线程中求导时互不影响。
+    fn with_graph_inputs(&self, inputs: &[Tensor]) -> Self {
+        FunctionCtx {
+            inputs: Vec::new(),
+            input_shapes: self.input_shapes.clone(),
+            input_complex: self.input_complex.clone(),
+            needs_input_grad: self.needs_input_grad.clone(),
+            saved: self.saved.clone(),
+            values: self.values.clone(),
+            graph_inputs: Some(inputs.to_vec()),
+        }
+    }
+
+    /// 第 `i` 个输入是否需要梯度，不需要时反向传播可以跳过它
+    pub fn needs_input_grad(&self, i: usize) -> bool {
+        self.needs_input_grad[i]
+    }
+
+    /// 保存反向传播需要的张量，之后被原地修改时取出会 panic
+    pub fn save_for_backward(&mut self, tensors: &[&Tensor]) {
+        self.saved = tensors
+            .iter()
+            .map(|&tensor| {
+                let input = self
+                    .inputs
+                    .iter()
+                    .position(|input| Arc::ptr_eq(&input.0, &tensor.0));
+                (input, SavedTensor::new(tensor))
+            })
+            .collect();
+    }
+
+    /// 取出 `save_for_backward` 保存的张量。
+    ///
+    /// `create_graph` 时保存的输入会连回计算图，其余张量视为常量，并保持保存时的元素类型。
+    pub fn saved_tensors(&self) -> Vec<Tensor> {
+        self.saved
+            .iter()
+            .map(|(input, saved)| {
+                let tensor = saved.unpack_tensor();
+                match (input, &self.graph_inputs) {
+                    (Some(i), Some(graph_inputs)) => graph_inputs[*i].clone(),
+                    _ => tensor,
+                }
+            })
+            .collect()
+    }
+
+    /// 保存非张量的值，例如前向中计算出的下标或标量
+    pub fn save_value<T: Any + Send + Sync>(&mut self, key: &'static str, value: T) {
+        self.values.insert(key, Arc::new(value));
+    }
+
+    /// 取出 `save_value` 保存的值，键不存在或类型不符时为 `None`
+    pub fn saved_value<T: Any>(&self, key: &str) -> Option<&T> {
+        self.values.get(key)?.downcast_ref()
+    }
+}
+
+impl fmt::Debug for FunctionCtx {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FunctionCtx")
+            .field("input_shapes", &self.input_shapes)
+            .field("needs_input_grad", &self.needs_input_grad)
+            .field("saved", &self.saved.len())
+            .field("values", &self.values.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+/// 用户自定义的可微函数，例如：
+///
+/// ```
+/// use torch_rs::autograd::{Function, FunctionCtx};
+/// use torch_rs::tensor::Tensor;
+///
+/// /// y = scale · x³
+/// struct ScaledCube {
+///     scale: f32,
+/// }
+///
+/// impl Function for ScaledCube {
+///     fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+///         ctx.save_for_backward(&[inputs[0]]);
+///         &inputs[0].powf(3.0) * self.scale
+///     }
+///
+///     fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+///         let x = ctx.saved_tensors().remove(0);
+///         vec![Some(&(grad_output * &(&x * &x)) * (3.0 * self.scale))]
+///     }
+/// }
+///
+/// let x = Tensor::from(vec![1.0, 2.0]).require_grad(true);
+/// ScaledCube { scale: 2.0 }.apply(&[&x]).sum().backward();
+/// assert_eq!(x.0.borrow().grad.clone().unwrap().into_raw_vec_and_offset().0, vec![6.0, 24.0]);
+/// ```
+pub trait Function: Send + Sync + 'static {
+    /// 前向计算，执行时梯度记录已关闭
+    fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor;
+
+    /// 由输出梯度计算各输入的梯度，与输入一一对应，不需要梯度的输入可以返回 `None`。
+    ///
+    /// 用可微运算实现时，`autograd::grad` 的 `create_graph` 也可以对它继续求导。
+    fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>>;
+
+    /// 前向模式求导：由输入的切线计算输出的切线，没有切线的输入为 `None`
+    fn jvp(&self, ctx: &FunctionCtx, tangents: &[Option<Tensor>]) -> Tensor {
+        let _ = (ctx, tangents);
+        panic!(
+            "{} does not support forward AD",
+            std::any::type_name::<Self>()
+        );
+    }
+
+    /// 对输入应用函数，需要时记录计算图
+    fn apply(self, inputs: &[&Tensor]) -> Tensor
+    where
+        Self: Sized,
+    {
+        apply(Arc::new(self), inputs)
+    }
+}
+
+/// 计算图中代表一次 `Function` 调用的节点
+struct FunctionNode<F> {
+    function: Arc<F>,
+    ctx: Option<FunctionCtx>,
+}
+
+impl<F> fmt::Debug for FunctionNode<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct(std::any::type_name::<F>())
+            .field("ctx", &self.ctx)
+            .finish()
+    }
+}
+
+fn apply<F: Function>(function: Arc<F>, inputs: &[&Tensor]) -> Tensor {
+    let mut ctx = FunctionCtx::new(inputs);
+    let result = {
+        let _guard = no_grad();
+        function.forward(&mut ctx, inputs).detach()
+    };
+    ctx.inputs.clear();
+
+    let node = FunctionNode {
+        function,
+        ctx: Some(ctx),
+    };
+    propagate_tangent(&node, inputs, &result);
+    if needs_grad(inputs) {
+        let mut result_data = result.0.borrow_mut();
+        result_data.set_creator(Arc::new(node));
+        for &input in inputs {
+            result_data.add_parent(input);
+        }
+        result_data.requires_grad = true;
+    }
+    result
+}
+
+impl<F: Function> FunctionNode<F> {
+    fn ctx(&self) -> &FunctionCtx {
+        self.ctx.as_ref().expect("ctx is None in backward")
+    }
+
+    /// 检查用户返回的梯度，把 `None` 补成全 0；复数输入的梯度是同形状的复数张量
+    fn input_grads(&self, grads: Vec<Option<Tensor>>) -> Vec<Tensor> {
+        let shapes = &self.ctx().input_shapes;
+        let complex = &self.ctx().input_complex;
+        assert_eq!(
+            grads.len(),
+            shapes.len(),
+            "{} returned {} gradients for {} inputs",
+            std::any::type_name::<F>(),
+            grads.len(),
+            shapes.len()
+        );
+        grads
+            .into_iter()
+            .zip(shapes.iter().zip(complex))
+            .map(|(grad, (shape, &complex))| match grad {
+                Some(grad) => {
+                    assert_eq!(
+                        &grad.shape(),
+                        shape,
+                        "{} returned a gradient whose shape does not match its input",
+                        std::any::type_name::<F>()
+                    );
+                    assert_eq!(
+                        grad.is_complex(),
+                        complex,
+                        "{} returned a gradient that must be complex exactly when its input is",
+                        std::any::type_name::<F>()
+                    );
+                    grad
+                }
+                None if complex => {
+                    let zeros = Tensor::zeros(shape);
+                    Tensor::complex(&zeros, &zeros).expect("Zero parts have the same shape")
+                }
+                None => Tensor::zeros(shape),
+            })
+            .collect()
+    }
+}
+
+impl<F: Function> Op for FunctionNode<F> {
+    fn name(&self) -> &'static str {
+        std::any::type_name::<F>()
+    }
+
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        apply(Arc::clone(&self.function), inputs)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        // 复数输出的梯度按 view_as_real 的形状保存，交给用户前合成为复数张量
+        let grad_output = grad_to_tensor(grad_output, parent);
+        let _guard = no_grad();
+        let grads = self.function.backward(self.ctx(), &grad_output);
+        self.input_grads(grads)
+            .iter()
+            .map(|grad| grad.0.borrow().data_as_real())
+            .collect()
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let ctx = self.ctx().with_graph_inputs(inputs);
+        let grads = self.function.backward(&ctx, grad);
+        self.input_grads(grads)
+    }
+
+    fn jvp(&self, _inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        self.function.jvp(self.ctx(), tangents)
+    }
+}
@@ -0,0 +1,241 @@
+//! 基于 `autograd::grad` 的高阶求导工具：雅可比矩阵、Hessian 矩阵以及各种向量积。
+//!
+//! 所有函数都接收一个 `Fn(&Tensor) -> Tensor` 闭包和求导点 `input`。
+//! `create_graph` 为 false 时结果与计算图断开；为 true 时结果可以继续求导，
+//! 若 `input` 本身需要梯度，结果对它也是可微的。
+
+use super::{enable_grad, grad, no_grad};
+use crate::tensor::Tensor;
+use ndarray::{Array2, ArrayD, IxDyn};
+
+/// 雅可比矩阵与 Hessian 矩阵的计算策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    /// 逐行做一次向量-雅可比积，对函数没有额外要求
+    #[default]
+    Loop,
+    /// 把输入复制成一批，只做一次前向与反向传播。
+    ///
+    /// 要求函数把新增的第 0 维当作批量维度，对每个样本独立计算并保留该维度，
+    /// 即 `func(stack([x; m]))` 的第 i 个切片等于 `func(x)`。
+    Vectorized,
+}
+
+/// 准备求导的输入：需要保留对原输入的计算图时直接使用原张量，否则断开成新的叶子
+fn prepare_input(input: &Tensor, create_graph: bool) -> Tensor {
+    if create_graph && input.0.borrow().requires_grad {
+        input.clone()
+    } else {
+        input.detach().require_grad(true)
+    }
+}
+
+/// 不需要继续求导时断开结果与计算图的联系
+fn finish(tensor: Tensor, create_graph: bool) -> Tensor {
+    if create_graph {
+        tensor
+    } else {
+        tensor.detach()
+    }
+}
+
+/// 计算 `grad_outputᵀ · ∂output/∂input`，输出与输入无关时为全 0
+fn vector_grad(
+    output: &Tensor,
+    input: &Tensor,
+    grad_output: &Tensor,
+    create_graph: bool,
+) -> Result<Tensor, &'static str> {
+    if !output.0.borrow().requires_grad {
+        return Ok(input.zeros_like());
+    }
+    Ok(grad(&[output], &[input], Some(&[grad_output]), create_graph)?.remove(0))
+}
+
+/// 形状为 `[n, *shape]` 的单位张量：第 i 个切片展平后是第 i 个单位向量
+fn batched_eye(shape: &[usize]) -> Tensor {
+    let n: usize = shape.iter().product();
+    let mut full_shape = vec![n];
+    full_shape.extend_from_slice(shape);
+    let eye = Array2::<f32>::eye(n)
+        .into_shape_with_order(IxDyn(&full_shape))
+        .expect("Failed to reshape identity");
+    Tensor::new(eye)
+}
+
+/// 把输入沿新增的第 0 维复制 `n` 份（共享存储的视图）
+fn batched_input(x: &Tensor, n: usize) -> Result<Tensor, &'static str> {
+    let mut shape = vec![n];
+    shape.extend(x.shape());
+    x.unsqueeze(0)?.expand(&shape)
+}
+
+/// 把形状为 `[n, *in]` 的逐行结果整理成 `[*out, *in]`
+fn rows_to_matrix(rows: &Tensor, out_shape: &[usize], in_shape: &[usize]) -> Tensor {
+    let shape: Vec<usize> = out_shape.iter().chain(in_shape).copied().collect();
+    rows.reshape(&shape)
+        .expect("Failed to reshape jacobian rows")
+}
+
+/// 向量-雅可比积：返回 `func(input)` 与 `vᵀ · J`，`v` 的形状与输出相同
+pub fn vjp<F>(
+    func: F,
+    input: &Tensor,
+    v: &Tensor,
+    create_graph: bool,
+) -> Result<(Tensor, Tensor), &'static str>
+where
+    F: Fn(&Tensor) -> Tensor,
+{
+    let _guard = enable_grad();
+    let x = prepare_input(input, create_graph);
+    let output = func(&x);
+    if v.shape() != output.shape() {
+        return Err("向量的形状必须与函数输出相同");
+    }
+    let product = vector_grad(&output, &x, v, create_graph)?;
+    Ok((finish(output, create_graph), finish(product, create_graph)))
+}
+
+/// 雅可比-向量积：返回 `func(input)` 与 `J · v`，`v` 的形状与输入相同。
+///
+/// 通过两次反向传播计算：`g(u) = uᵀ · J` 对 `u` 是线性的，它对 `u` 的向量-雅可比积就是 `J · v`。
+pub fn jvp<F>(
+    func: F,
+    input: &Tensor,
+    v: &Tensor,
+    create_graph: bool,
+) -> Result<(Tensor, Tensor), &'static str>
+where
+    F: Fn(&Tensor) -> Tensor,
+{
+    if v.shape() != input.shape() {
+        return Err("向量的形状必须与输入相同");
+    }
+    let _guard = enable_grad();
+    let x = prepare_input(input, create_graph);
+    let output = func(&x);
+    let u = output.zeros_like().require_grad(true);
+    let g = vector_grad(&output, &x, &u, true)?;
+    let product = vector_grad(&g, &u, v, create_graph)?;
+    Ok((finish(output, create_graph), finish(product, create_graph)))
+}
+
+/// Hessian-向量积：返回 `func(input)` 与 `H · v`，`func` 的输出必须是只有一个元素的张量
+pub fn hvp<F>(
+    func: F,
+    input: &Tensor,
+    v: &Tensor,
+    create_graph: bool,
+) -> Result<(Tensor, Tensor), &'static str>
+where
+    F: Fn(&Tensor) -> Tensor,
+{
+    if v.shape() != input.shape() {
+        return Err("向量的形状必须与输入相同");
+    }
+    let _guard = enable_grad();
+    let x = prepare_input(input, create_graph);
+    let output = func(&x);
+    if output.numel() != 1 {
+        return Err("函数的输出必须只有一个元素");
+    }
+    let g = vector_grad(&output, &x, &output.ones_like(), true)?;
+    let product = vector_grad(&g, &x, v, create_graph)?;
+    Ok((finish(output, create_graph), finish(product, create_graph)))
+}
+
+/// 雅可比矩阵，形状为 `[*输出形状, *输入形状]`
+pub fn jacobian<F>(
+    func: F,
+    input: &Tensor,
+    create_graph: bool,
+    strategy: Strategy,
+) -> Result<Tensor, &'static str>
+where
+    F: Fn(&Tensor) -> Tensor,
+{
+    let _guard = enable_grad();
+    let x = prepare_input(input, create_graph);
+    let in_shape = x.shape();
+    let jacobian = match strategy {
+        Strategy::Loop => {
+            let output = func(&x);
+            let rows = jacobian_rows(&output, &x, create_graph)?;
+            rows_to_matrix(&rows, &output.shape(), &in_shape)
+        }
+        Strategy::Vectorized => {
+            // 先在不记录计算图的情况下确定输出形状，即批量的大小
+            let out_shape = {
+                let _no_grad = no_grad();
+                func(&x).shape()
+            };
+            let n = out_shape.iter().product();
+            let batch = batched_input(&x, n)?;
+            let output = func(&batch);
+            if output.shape() != [&[n][..], &out_shape].concat() {
+                return Err("向量化策略要求函数保留第0维作为批量维度");
+            }
+            let rows = vector_grad(&output, &batch, &batched_eye(&out_shape), create_graph)?;
+            rows_to_matrix(&rows, &out_shape, &in_shape)
+        }
+    };
+    Ok(finish(jacobian, create_graph))
+}
+
+/// 逐个输出元素做向量-雅可比积，结果形状为 `[输出元素个数, *输入形状]`
+fn jacobian_rows(output: &Tensor, x: &Tensor, create_graph: bool) -> Result<Tensor, &'static str> {
+    let n = output.numel();
+    let eye = batched_eye(&output.shape());
+    let rows = (0..n)
+        .map(|i| {
+            let unit = eye.select(0, i)?;
+            vector_grad(output, x, &unit, create_graph)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if rows.is_empty() {
+        let mut shape = vec![0];
+        shape.extend(x.shape());
+        return Ok(Tensor::new(ArrayD::zeros(IxDyn(&shape))));
+    }
+    Tensor::stack(&rows)
+}
+
+/// Hessian 矩阵，形状为 `[*输入形状, *输入形状]`，`func` 的输出必须是只有一个元素的张量
+pub fn hessian<F>(
+    func: F,
+    input: &Tensor,
+    create_graph: bool,
+    strategy: Strategy,
+) -> Result<Tensor, &'static str>
+where
+    F: Fn(&Tensor) -> Tensor,
+{
+    let _guard = enable_grad();
+    let x = prepare_input(input, create_graph);
+    let in_shape = x.shape();
+    let hessian = match strategy {
+        Strategy::Loop => {
+            let output = func(&x);
+            if output.numel() != 1 {
+                return Err("函数的输出必须只有一个元素");
+            }
+            let g = vector_grad(&output, &x, &output.ones_like(), true)?;
+            let rows = jacobian_rows(&g, &x, create_graph)?;
+            rows_to_matrix(&rows, &in_shape, &in_shape)
+        }
+        Strategy::Vectorized => {
+            // 每个样本的梯度都是 ∇f(x)，再用单位向量对第 i 个样本求一次向量-雅可比积
+            let n = x.numel();
+            let batch = batched_input(&x, n)?;
+            let output = func(&batch);
+            if output.shape() != [n] {
+                return Err("向量化策略要求函数保留第0维作为批量维度");
+            }
+            let g = vector_grad(&output, &batch, &output.ones_like(), true)?;
+            let rows = vector_grad(&g, &batch, &batched_eye(&in_shape), create_graph)?;
+            rows_to_matrix(&rows, &in_shape, &in_shape)
+        }
+    };
+    Ok(finish(hessian, create_graph))
+}
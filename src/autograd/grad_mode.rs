@@ -0,0 +1,74 @@
+//! 线程局部的梯度模式。
+//!
+//! 关闭梯度时，所有运算都不记录创建者和父节点，也不保存反向传播所需的数据，
+//! 适合评估循环等不需要求导的场景。模式通过 RAII 守卫切换，守卫析构时恢复之前的模式。
+
+use std::cell::Cell;
+
+thread_local! {
+    static GRAD_ENABLED: Cell<bool> = const { Cell::new(true) };
+    static INFERENCE_MODE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// 当前线程是否记录计算图
+pub fn is_grad_enabled() -> bool {
+    GRAD_ENABLED.with(Cell::get) && !is_inference_mode_enabled()
+}
+
+/// 当前线程是否处于推理模式
+pub fn is_inference_mode_enabled() -> bool {
+    INFERENCE_MODE.with(Cell::get)
+}
+
+/// 梯度模式守卫，析构时恢复进入前的模式
+#[must_use = "梯度模式只在守卫存活期间生效"]
+#[derive(Debug)]
+pub struct GradModeGuard {
+    prev_grad: bool,
+    prev_inference: bool,
+}
+
+impl GradModeGuard {
+    fn new(grad: bool, inference: bool) -> Self {
+        let guard = GradModeGuard {
+            prev_grad: GRAD_ENABLED.with(Cell::get),
+            prev_inference: is_inference_mode_enabled(),
+        };
+        GRAD_ENABLED.with(|g| g.set(grad));
+        INFERENCE_MODE.with(|m| m.set(inference));
+        guard
+    }
+}
+
+impl Drop for GradModeGuard {
+    fn drop(&mut self) {
+        GRAD_ENABLED.with(|g| g.set(self.prev_grad));
+        INFERENCE_MODE.with(|m| m.set(self.prev_inference));
+    }
+}
+
+/// 在守卫存活期间按 `enabled` 开启或关闭梯度记录
+pub fn set_grad_enabled(enabled: bool) -> GradModeGuard {
+    GradModeGuard::new(enabled, is_inference_mode_enabled())
+}
+
+/// 在守卫存活期间关闭梯度记录，例如：
+///
+/// ```
+/// let _guard = torch_rs::autograd::no_grad();
+/// ```
+pub fn no_grad() -> GradModeGuard {
+    set_grad_enabled(false)
+}
+
+/// 在守卫存活期间重新开启梯度记录，可用于 `no_grad` 内部的局部求导
+pub fn enable_grad() -> GradModeGuard {
+    set_grad_enabled(true)
+}
+
+/// 推理模式：比 `no_grad` 更彻底，不能被 `enable_grad` 重新开启。
+///
+/// 推理模式下创建的张量是推理张量，原地修改不增加版本号，也不能被保存用于反向传播。
+pub fn inference_mode() -> GradModeGuard {
+    GradModeGuard::new(false, true)
+}
@@ -0,0 +1,227 @@
+//! 数值梯度检查：用中心差分验证反向传播给出的解析梯度。
+//!
+//! 输入是 f32 的叶子张量，输出按 f32 读出；函数内部可以转换为 f64 或构造复数张量计算，
+//! 复数运算通过 `view_as_real`、`abs` 等得到实数输出后检查。
+//! 差分的商与误差比较在 f64 中进行，并以扰动后实际存入 f32 的步长作分母，减少舍入带来的误差。
+
+use super::grad;
+use crate::tensor::Tensor;
+use ndarray::{Array2, ArrayD, IxDyn};
+use std::fmt;
+
+/// 梯度检查失败时误差最大的元素
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradcheckError {
+    /// 出错的输入在 `inputs` 中的位置
+    pub input: usize,
+    /// 输入中元素的下标
+    pub input_index: Vec<usize>,
+    /// 输出中元素的下标
+    pub output_index: Vec<usize>,
+    /// 反向传播得到的导数
+    pub analytical: f64,
+    /// 中心差分得到的导数
+    pub numerical: f64,
+}
+
+impl fmt::Display for GradcheckError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "梯度检查失败：输出{:?}对第{}个输入{:?}的导数，解析值为{}，数值为{}",
+            self.output_index, self.input, self.input_index, self.analytical, self.numerical
+        )
+    }
+}
+
+impl std::error::Error for GradcheckError {}
+
+/// 把行主序的线性下标还原为多维下标
+fn unravel(mut flat: usize, shape: &[usize]) -> Vec<usize> {
+    let mut index = vec![0; shape.len()];
+    for (i, &size) in index.iter_mut().zip(shape).rev() {
+        *i = flat % size;
+        flat /= size;
+    }
+    index
+}
+
+/// 用数据构造需要梯度的叶子张量作为函数的输入
+fn leaves(data: &[ArrayD<f32>]) -> Vec<Tensor> {
+    data.iter()
+        .map(|d| Tensor::new(d.clone()).require_grad(true))
+        .collect()
+}
+
+/// 逐个输出元素做一次反向传播，得到每个输入的雅可比矩阵 `[输出元素, 输入元素]`
+fn analytical_jacobians(output: &Tensor, inputs: &[Tensor]) -> Vec<Array2<f64>> {
+    let n_out = output.numel();
+    let mut jacobians: Vec<Array2<f64>> = inputs
+        .iter()
+        .map(|x| Array2::zeros((n_out, x.numel())))
+        .collect();
+    if !output.0.borrow().requires_grad {
+        return jacobians;
+    }
+    let inputs: Vec<&Tensor> = inputs.iter().collect();
+    for k in 0..n_out {
+        let mut unit = vec![0.0; n_out];
+        unit[k] = 1.0;
+        let unit = Tensor::new(ArrayD::from_shape_vec(IxDyn(&output.shape()), unit).unwrap());
+        let grads = grad(&[output], &inputs, Some(&[&unit]), false)
+            .expect("Failed to compute analytical gradient");
+        for (jacobian, g) in jacobians.iter_mut().zip(grads) {
+            for (value, &g) in jacobian.row_mut(k).iter_mut().zip(g.data().iter()) {
+                *value = g as f64;
+            }
+        }
+    }
+    jacobians
+}
+
+/// 把第 `which` 个输入的第 `j` 个元素加上 `delta` 后求值，返回展平的输出与实际的步长
+fn perturbed_output<F>(
+    f: &F,
+    data: &[ArrayD<f32>],
+    which: usize,
+    j: usize,
+    delta: f64,
+) -> (Vec<f32>, f64)
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let mut data = data.to_vec();
+    let value = &mut data[which]
+        .as_slice_mut()
+        .expect("Input data is contiguous")[j];
+    let original = *value as f64;
+    *value = (original + delta) as f32;
+    let step = *value as f64 - original;
+    let output = f(&leaves(&data)).data().iter().copied().collect();
+    (output, step)
+}
+
+/// 用中心差分计算第 `which` 个输入的雅可比矩阵 `[输出元素, 输入元素]`
+fn numerical_jacobian<F>(
+    f: &F,
+    data: &[ArrayD<f32>],
+    which: usize,
+    n_out: usize,
+    eps: f64,
+) -> Array2<f64>
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let n_in = data[which].len();
+    let mut jacobian = Array2::zeros((n_out, n_in));
+    for j in 0..n_in {
+        let (plus, step_plus) = perturbed_output(f, data, which, j, eps);
+        let (minus, step_minus) = perturbed_output(f, data, which, j, -eps);
+        assert!(
+            plus.len() == n_out && minus.len() == n_out,
+            "Function output changed shape during gradcheck"
+        );
+        let step = step_plus - step_minus;
+        for (k, (&p, &m)) in plus.iter().zip(&minus).enumerate() {
+            jacobian[[k, j]] = (p as f64 - m as f64) / step;
+        }
+    }
+    jacobian
+}
+
+/// 比较 `f` 在 `inputs` 处反向传播得到的梯度与中心差分，所有元素都满足
+/// `|解析值 - 数值| <= atol + rtol * |数值|` 时返回 `Ok`，否则报告超出容差最多的元素。
+///
+/// `f` 接收与 `inputs` 一一对应、需要梯度的叶子张量，可以返回任意形状的张量。
+/// 函数在扰动点附近必须可导，`eps` 为差分步长。
+pub fn gradcheck<F>(
+    f: F,
+    inputs: &[&Tensor],
+    eps: f64,
+    atol: f64,
+    rtol: f64,
+) -> Result<(), GradcheckError>
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let data: Vec<ArrayD<f32>> = inputs
+        .iter()
+        .map(|t| t.data().as_standard_layout().into_owned())
+        .collect();
+    let xs = leaves(&data);
+    let output = f(&xs);
+    let out_shape = output.shape();
+
+    let mut worst: Option<(f64, GradcheckError)> = None;
+    for (i, analytical) in analytical_jacobians(&output, &xs).iter().enumerate() {
+        let numerical = numerical_jacobian(&f, &data, i, output.numel(), eps);
+        for ((k, j), &a) in analytical.indexed_iter() {
+            let n = numerical[[k, j]];
+            let error = (a - n).abs();
+            let tolerance = atol + rtol * n.abs();
+            if error <= tolerance {
+                continue;
+            }
+            // NaN 总是视为误差最大
+            let excess = if error.is_nan() {
+                f64::INFINITY
+            } else {
+                error - tolerance
+            };
+            if worst.as_ref().is_none_or(|(w, _)| excess > *w) {
+                let failure = GradcheckError {
+                    input: i,
+                    input_index: unravel(j, data[i].shape()),
+                    output_index: unravel(k, &out_shape),
+                    analytical: a,
+                    numerical: n,
+                };
+                worst = Some((excess, failure));
+            }
+        }
+    }
+    match worst {
+        Some((_, failure)) => Err(failure),
+        None => Ok(()),
+    }
+}
+
+/// 固定的输出梯度：各元素取值不同，避免对称的误差相互抵消
+fn probe_like(output: &Tensor) -> Tensor {
+    let values = (0..output.numel())
+        .map(|k| 1.0 - 0.37 * (k % 5) as f32)
+        .collect();
+    Tensor::new(ArrayD::from_shape_vec(IxDyn(&output.shape()), values).unwrap())
+}
+
+/// 二阶梯度检查：对 `vᵀ · ∂f/∂x`（`v` 为固定的输出梯度）再做一次 `gradcheck`，
+/// 验证 `create_graph` 构建的反向传播。
+///
+/// 报告中的输出下标指向把各输入的梯度展平后依次拼接得到的向量。
+pub fn gradgradcheck<F>(
+    f: F,
+    inputs: &[&Tensor],
+    eps: f64,
+    atol: f64,
+    rtol: f64,
+) -> Result<(), GradcheckError>
+where
+    F: Fn(&[Tensor]) -> Tensor,
+{
+    let first_order = |xs: &[Tensor]| {
+        let output = f(xs);
+        let grads = if output.0.borrow().requires_grad {
+            let inputs: Vec<&Tensor> = xs.iter().collect();
+            grad(&[&output], &inputs, Some(&[&probe_like(&output)]), true)
+                .expect("Failed to compute first-order gradient")
+        } else {
+            xs.iter().map(Tensor::zeros_like).collect()
+        };
+        let flat: Vec<Tensor> = grads
+            .iter()
+            .map(|g| g.reshape(&[g.numel()]).expect("Failed to flatten gradient"))
+            .collect();
+        Tensor::cat(&flat, 0).expect("Failed to concatenate gradients")
+    };
+    gradcheck(first_order, inputs, eps, atol, rtol)
+}
@@ -0,0 +1,136 @@
+//! 张量上的梯度钩子：在 `Tensor::backward` 的拓扑遍历中查看或修改流经张量的梯度。
+
+use crate::tensor::{Tensor, TensorCell};
+use ndarray::ArrayD;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Weak};
+
+/// 梯度钩子，返回 `Some` 时替换流经张量的梯度
+pub(crate) type GradHook = Arc<dyn Fn(&ArrayD<f32>) -> Option<ArrayD<f32>> + Send + Sync>;
+
+/// 叶子节点的梯度累加完成后调用的钩子
+pub(crate) type PostAccumulateGradHook = Arc<dyn Fn(&Tensor) + Send + Sync>;
+
+/// 钩子编号在所有线程间唯一
+static NEXT_HOOK_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn next_hook_id() -> usize {
+    NEXT_HOOK_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// 注册钩子得到的句柄，调用 `remove` 注销钩子。
+///
+/// 句柄只持有张量的弱引用，丢弃句柄不会注销钩子。
+#[derive(Debug)]
+pub struct RemovableHandle {
+    tensor: Weak<TensorCell>,
+    id: usize,
+}
+
+impl RemovableHandle {
+    /// 注销钩子，张量已被释放时什么也不做
+    pub fn remove(&self) {
+        if let Some(tensor) = self.tensor.upgrade() {
+            let mut data = tensor.borrow_mut();
+            data.hooks.retain(|(id, _)| *id != self.id);
+            data.post_accumulate_hooks.retain(|(id, _)| *id != self.id);
+        }
+    }
+}
+
+impl Tensor {
+    /// 注册梯度钩子：每次反向传播算出该张量的梯度后调用，
+    /// 返回 `Some` 时用新梯度替换原梯度继续传播，多个钩子按注册顺序依次调用。
+    pub fn register_hook<F>(&self, hook: F) -> Result<RemovableHandle, &'static str>
+    where
+        F: Fn(&ArrayD<f32>) -> Option<ArrayD<f32>> + Send + Sync + 'static,
+    {
+        if !self.0.borrow().requires_grad {
+            return Err("不需要梯度的张量不能注册梯度钩子");
+        }
+        let id = next_hook_id();
+        self.0.borrow_mut().hooks.push((id, Arc::new(hook)));
+        Ok(RemovableHandle {
+            tensor: Arc::downgrade(&self.0),
+            id,
+        })
+    }
+
+    /// 注册叶子节点的梯度累加钩子：反向传播把梯度累加到 `grad` 之后调用，
+    /// 可以在钩子中读取或修改张量的梯度
+    pub fn register_post_accumulate_grad_hook<F>(
+        &self,
+        hook: F,
+    ) -> Result<RemovableHandle, &'static str>
+    where
+        F: Fn(&Tensor) + Send + Sync + 'static,
+    {
+        if !self.is_leaf() {
+            return Err("只有叶子节点可以注册梯度累加钩子");
+        }
+        if !self.0.borrow().requires_grad {
+            return Err("不需要梯度的张量不能注册梯度钩子");
+        }
+        let id = next_hook_id();
+        self.0
+            .borrow_mut()
+            .post_accumulate_hooks
+            .push((id, Arc::new(hook)));
+        Ok(RemovableHandle {
+            tensor: Arc::downgrade(&self.0),
+            id,
+        })
+    }
+
+    /// 反向传播后保留非叶子节点的梯度，多次反向传播时与叶子节点一样累加；叶子节点的梯度总是保留
+    pub fn retain_grad(&self) -> Result<(), &'static str> {
+        let mut data = self.0.borrow_mut();
+        if !data.requires_grad {
+            return Err("不需要梯度的张量不能保留梯度");
+        }
+        data.retains_grad = true;
+        Ok(())
+    }
+
+    /// 反向传播后是否保留该张量的梯度
+    pub fn retains_grad(&self) -> bool {
+        self.0.borrow().retains_grad
+    }
+}
+
+/// 依次调用张量上的梯度钩子，返回最终的梯度
+pub(crate) fn run_hooks(tensor: &Tensor, mut grad: ArrayD<f32>) -> ArrayD<f32> {
+    // 先复制钩子列表再调用，钩子中可以访问张量
+    let hooks: Vec<GradHook> = tensor
+        .0
+        .borrow()
+        .hooks
+        .iter()
+        .map(|(_, hook)| Arc::clone(hook))
+        .collect();
+    for hook in hooks {
+        if let Some(new_grad) = hook(&grad) {
+            assert_eq!(
+                new_grad.shape(),
+                grad.shape(),
+                "Hook changed the shape of the gradient"
+            );
+            grad = new_grad;
+        }
+    }
+    grad
+}
+
+/// 调用叶子节点的梯度累加钩子
+pub(crate) fn run_post_accumulate_hooks(tensor: &Tensor) {
+    let hooks: Vec<PostAccumulateGradHook> = tensor
+        .0
+        .borrow()
+        .post_accumulate_hooks
+        .iter()
+        .map(|(_, hook)| Arc::clone(hook))
+        .collect();
+    for hook in hooks {
+        hook(tensor);
+    }
+}
@@ -1,57 +1,360 @@
+pub mod anomaly_mode;
+pub mod checkpoint;
+pub mod forward_ad;
+pub mod function;
+pub mod functional;
+pub mod grad_mode;
+pub mod gradcheck;
+pub mod hooks;
+pub mod viz;
+
+pub use anomaly_mode::{AnomalyModeGuard, detect_anomaly, is_anomaly_enabled, set_detect_anomaly};
+pub use checkpoint::checkpoint;
+pub use function::{Function, FunctionCtx};
+pub use grad_mode::{
+    GradModeGuard, enable_grad, inference_mode, is_grad_enabled, is_inference_mode_enabled,
+    no_grad, set_grad_enabled,
+};
+pub use gradcheck::{GradcheckError, gradcheck, gradgradcheck};
+pub use hooks::RemovableHandle;
+
+use super::dtype::pairs_to_complex;
+use super::ops::Op;
 use super::tensor::Tensor;
+use anomaly_mode::check_gradients;
+use hooks::{run_hooks, run_post_accumulate_hooks};
 use ndarray::ArrayD;
-use std::{collections::HashSet, rc::Rc};
+use std::{
+    cell::Cell,
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
+
+thread_local! {
+    static ACCUMULATE_LEAF_GRADS: Cell<bool> = const { Cell::new(true) };
+}
+
+/// 当前的反向传播是否把梯度累加到叶子张量的 `grad` 上：
+/// `backward` 会累加，`grad` 复用运算的反向传播时不会。
+/// 反向传播中自己求导的运算（如检查点）据此决定是否写入它捕获的参数的梯度
+pub(crate) fn accumulates_leaf_grads() -> bool {
+    ACCUMULATE_LEAF_GRADS.with(Cell::get)
+}
+
+/// 守卫存活期间按 `enabled` 设置 `accumulates_leaf_grads`，析构时恢复
+struct AccumulateLeafGradsGuard {
+    prev: bool,
+}
+
+impl AccumulateLeafGradsGuard {
+    fn new(enabled: bool) -> Self {
+        let prev = ACCUMULATE_LEAF_GRADS.with(|a| a.replace(enabled));
+        AccumulateLeafGradsGuard { prev }
+    }
+}
+
+impl Drop for AccumulateLeafGradsGuard {
+    fn drop(&mut self) {
+        ACCUMULATE_LEAF_GRADS.with(|a| a.set(self.prev));
+    }
+}
+
+const GRAPH_FREED: &str =
+    "计算图已在之前的反向传播中释放，需要再次反向传播时请设置retain_graph=true";
+
+/// 计算图中节点的标识
+fn node_id(tensor: &Tensor) -> *const () {
+    Arc::as_ptr(&tensor.0) as *const ()
+}
+
+/// 把按 `view_as_real` 形状保存的梯度（或切线）数组转换为张量：
+/// `tensor` 为复数时合成同类型的复数张量，否则为 f32 张量
+pub(crate) fn grad_to_tensor(grad: ArrayD<f32>, tensor: &Tensor) -> Tensor {
+    if tensor.is_complex() {
+        Tensor::from_complex(pairs_to_complex(&grad.mapv(f64::from)), tensor.dtype())
+    } else {
+        Tensor::new(grad)
+    }
+}
+
+/// 对从 `roots` 出发可达的计算图做拓扑排序，每个节点排在它的所有父节点之后。
+///
+/// 不需要梯度的父节点不会收到梯度，因此不进入排序结果。
+/// 用显式的栈做深度优先遍历，很深的计算图（长序列展开、上万步的循环）也不会栈溢出。
+fn sorted_graph(roots: &[&Tensor]) -> Result<Vec<Tensor>, &'static str> {
+    let mut visited = HashSet::new();
+    let mut order = Vec::new();
+    // 第二项表示节点的父节点是否都已入栈，再次弹出时即可输出
+    let mut stack: Vec<(Tensor, bool)> = roots
+        .iter()
+        .rev()
+        .map(|&root| (root.clone(), false))
+        .collect();
+    while let Some((tensor, expanded)) = stack.pop() {
+        if expanded {
+            order.push(tensor);
+            continue;
+        }
+        if !visited.insert(node_id(&tensor)) {
+            continue;
+        }
+        let parents: Vec<Tensor> = tensor
+            .0
+            .borrow()
+            .parents
+            .iter()
+            .filter(|parent| parent.borrow().requires_grad)
+            .cloned()
+            .map(Tensor)
+            .collect();
+        stack.push((tensor, true));
+        for parent in parents.into_iter().rev() {
+            if !visited.contains(&node_id(&parent)) {
+                stack.push((parent, false));
+            }
+        }
+    }
+    if order.iter().any(|t| t.0.borrow().graph_freed) {
+        return Err(GRAPH_FREED);
+    }
+    Ok(order)
+}
+
+/// 运算返回的梯度按位置对应父节点，个数必须相同
+fn check_parent_count(op: &dyn Op, parents: usize, grads: usize) {
+    assert_eq!(
+        parents,
+        grads,
+        "{} returned {} gradients for {} parents",
+        op.name(),
+        grads,
+        parents
+    );
+}
+
+/// 把梯度累加到节点 `id` 已有的梯度上
+fn accumulate(grads: &mut HashMap<*const (), Tensor>, id: *const (), grad: Tensor) {
+    let sum = match grads.remove(&id) {
+        Some(existing) => &existing + &grad,
+        None => grad,
+    };
+    grads.insert(id, sum);
+}
+
+/// 计算 `outputs` 对 `inputs` 的梯度并直接返回，不写入任何张量的 `grad`，也不释放计算图。
+///
+/// `grad_outputs` 为各输出的梯度，None 时取全 1（复数输出必须给出）；结果与 `inputs` 一一对应，
+/// 与输出无关的输入得到全 0 梯度。复数张量的梯度是复数张量 `∂L/∂x + i·∂L/∂y`。
+///
+/// `create_graph` 为 true 时，反向传播本身由可微运算构成并记录计算图，
+/// 返回的梯度可以再次求导，用于 Hessian-向量积、梯度惩罚等高阶导数。
+pub fn grad(
+    outputs: &[&Tensor],
+    inputs: &[&Tensor],
+    grad_outputs: Option<&[&Tensor]>,
+    create_graph: bool,
+) -> Result<Vec<Tensor>, &'static str> {
+    if let Some(grad_outputs) = grad_outputs {
+        if grad_outputs.len() != outputs.len() {
+            return Err("输出梯度的个数必须与输出张量相同");
+        }
+        if outputs
+            .iter()
+            .zip(grad_outputs)
+            .any(|(output, grad)| output.shape() != grad.shape())
+        {
+            return Err("输出梯度的形状必须与张量形状相同");
+        }
+        if outputs
+            .iter()
+            .zip(grad_outputs)
+            .any(|(output, grad)| output.is_complex() != grad.is_complex())
+        {
+            return Err("输出梯度与张量必须同为实数或同为复数");
+        }
+    } else if outputs.iter().any(|output| output.is_complex()) {
+        return Err("复数输出必须给出输出梯度");
+    }
+    if outputs
+        .iter()
+        .any(|output| !output.0.borrow().requires_grad)
+    {
+        return Err("输出张量不需要梯度，没有可以反向传播的计算图");
+    }
+    let topo_order = sorted_graph(outputs)?;
+
+    // 梯度按节点累加，只有 create_graph 时才记录梯度的计算图
+    let _guard = set_grad_enabled(create_graph);
+    let mut grads: HashMap<*const (), Tensor> = HashMap::new();
+    for (i, output) in outputs.iter().enumerate() {
+        let grad = match grad_outputs {
+            Some(grad_outputs) => grad_outputs[i].clone(),
+            None => output.ones_like(),
+        };
+        accumulate(&mut grads, node_id(output), grad);
+    }
+
+    for tensor in topo_order.iter().rev() {
+        let Some(grad) = grads.get(&node_id(tensor)).cloned() else {
+            continue;
+        };
+        let Some(op) = tensor.0.borrow().creator.clone() else {
+            continue;
+        };
+        let parents: Vec<Tensor> = tensor
+            .0
+            .borrow()
+            .parents
+            .iter()
+            .cloned()
+            .map(Tensor)
+            .collect();
+        let parent_grads = if create_graph {
+            op.backward_graph(&parents, tensor, &grad)
+        } else {
+            // 复用基于数组的反向传播：暂时把梯度放到节点上，结束后恢复
+            let grad = grad.0.borrow().data_as_real();
+            let previous = tensor.0.borrow_mut().grad.replace(grad);
+            let grads = {
+                let _accumulate = AccumulateLeafGradsGuard::new(false);
+                op.backward(tensor)
+            };
+            tensor.0.borrow_mut().grad = previous;
+            grads
+                .into_iter()
+                .zip(&parents)
+                .map(|(grad, parent)| grad_to_tensor(grad, parent))
+                .collect()
+        };
+        if is_anomaly_enabled() {
+            let trace = tensor.0.borrow().creation_trace.clone();
+            let data: Vec<ArrayD<f32>> = parent_grads
+                .iter()
+                .map(|grad| grad.0.borrow().data_as_real())
+                .collect();
+            check_gradients(op.as_ref(), trace.as_deref(), &data);
+        }
+        check_parent_count(op.as_ref(), parents.len(), parent_grads.len());
+        for (parent, grad) in parents.iter().zip(parent_grads) {
+            if parent.0.borrow().requires_grad {
+                accumulate(&mut grads, node_id(parent), grad);
+            }
+        }
+    }
+
+    Ok(inputs
+        .iter()
+        .map(|input| {
+            grads.get(&node_id(input)).cloned().unwrap_or_else(|| {
+                let zeros = ArrayD::zeros(input.0.borrow().data_as_real().raw_dim());
+                grad_to_tensor(zeros, input)
+            })
+        })
+        .collect())
+}
 
 impl Tensor {
     /// 反向传播：从当前张量出发，递归地计算所有依赖张量的梯度。
     ///
-    /// 通常用于loss.backward()。
+    /// 通常用于loss.backward()。以全1作为输出梯度，反向传播后释放计算图。
+    ///
+    /// # Panics
+    /// 计算图已在之前的反向传播中释放时 panic；需要以错误的形式得到这种情况时，
+    /// 改用 [`Tensor::backward_with`]，它返回同样的错误信息而不会 panic。
     pub fn backward(&self) {
-        // 1. 初始化自身的梯度为全1（通常用于标量loss）
-        {
-            let mut self_data = self.0.borrow_mut();
-            self_data.grad = Some(ArrayD::ones(self_data.data.shape()));
-        }
+        let ones = Tensor::new(ArrayD::ones(self.shape()));
+        self.backward_with(&ones, false)
+            .unwrap_or_else(|e| panic!("{}", e));
+    }
 
-        // 2. 拓扑排序，确保每个节点在所有子节点之后被处理
-        fn topo_sort(tensor: &Tensor, visited: &mut HashSet<*const ()>, order: &mut Vec<Tensor>) {
-            let ptr = Rc::as_ptr(&tensor.0) as *const ();
-            if visited.contains(&ptr) {
-                return;
-            }
-            visited.insert(ptr);
-            let parents = tensor.0.borrow().parents.clone();
-            for parent_weak in parents {
-                let parent_rc = parent_weak.clone();
-                topo_sort(&Tensor(parent_rc), visited, order);
-            }
-            order.push(tensor.clone());
+    /// 以 `grad` 作为输出梯度反向传播，即计算向量-雅可比积 `gradᵀ · J`。
+    ///
+    /// `retain_graph` 为 false 时，反向传播结束后释放计算图（创建者、父节点与保存的张量），
+    /// 之后再经过这部分图反向传播会返回错误；需要多次反向传播时传入 true。
+    pub fn backward_with(&self, grad: &Tensor, retain_graph: bool) -> Result<(), &'static str> {
+        if grad.shape() != self.shape() {
+            return Err("输出梯度的形状必须与张量形状相同");
         }
+        if grad.is_complex() != self.is_complex() {
+            return Err("输出梯度与张量必须同为实数或同为复数");
+        }
+
+        // 1. 拓扑排序，确保每个节点在所有子节点之后被处理
+        let topo_order = sorted_graph(&[self])?;
+        let _accumulate = AccumulateLeafGradsGuard::new(true);
 
-        let mut visited = HashSet::new();
-        let mut topo_order = Vec::new();
-        topo_sort(self, &mut visited, &mut topo_order);
+        // 2. 本次反向传播的梯度先按节点累加，处理到节点时才写入张量
+        let mut grads: HashMap<*const (), ArrayD<f32>> = HashMap::new();
+        grads.insert(node_id(self), grad.0.borrow().data_as_real());
 
         // 3. 反向遍历拓扑序，执行梯度传播
         for tensor in topo_order.into_iter().rev() {
+            let Some(grad) = grads.remove(&node_id(&tensor)) else {
+                continue;
+            };
+            let grad = run_hooks(&tensor, grad);
             let creator = tensor.0.borrow().creator.clone();
-            //println!("Processing tensor: {:?}", tensor);
-            if let Some(op) = creator {
-                let grads = op.backward(&tensor);
-                let parents = tensor.0.borrow().parents.clone();
-                for (parent_weak, grad) in parents.into_iter().zip(grads.into_iter()) {
-                    let parent_rc = parent_weak.clone();
-                    let mut parent_data = parent_rc.borrow_mut();
-                    match &mut parent_data.grad {
-                        Some(parent_grad) => {
-                            *parent_grad += &grad;
-                        }
-                        None => {
-                            parent_data.grad = Some(grad);
-                        }
+            let Some(op) = creator else {
+                // 叶子节点：累加到已有的梯度上
+                {
+                    let mut data = tensor.0.borrow_mut();
+                    match &mut data.grad {
+                        Some(existing) => *existing += &grad,
+                        None => data.grad = Some(grad),
                     }
                 }
+                run_post_accumulate_hooks(&tensor);
+                continue;
+            };
+
+            // 运算从节点上读取输出梯度；retain_grad 的节点先取出已累加的梯度，结束后再加回
+            let retained = {
+                let mut data = tensor.0.borrow_mut();
+                let retained = if data.retains_grad {
+                    data.grad.take()
+                } else {
+                    None
+                };
+                data.grad = Some(grad);
+                retained
+            };
+            let parent_grads = op.backward(&tensor);
+            if is_anomaly_enabled() {
+                let trace = tensor.0.borrow().creation_trace.clone();
+                check_gradients(op.as_ref(), trace.as_deref(), &parent_grads);
+            }
+            let parents = tensor.0.borrow().parents.clone();
+            check_parent_count(op.as_ref(), parents.len(), parent_grads.len());
+            for (parent, grad) in parents.iter().zip(parent_grads) {
+                if !parent.borrow().requires_grad {
+                    continue;
+                }
+                let id = Arc::as_ptr(parent) as *const ();
+                match grads.get_mut(&id) {
+                    Some(existing) => *existing += &grad,
+                    None => {
+                        grads.insert(id, grad);
+                    }
+                }
+            }
+
+            let mut data = tensor.0.borrow_mut();
+            // 中间节点的梯度只在 retain_grad 后保留，与叶子节点一样在多次反向传播间累加
+            if data.retains_grad {
+                if let (Some(existing), Some(grad)) = (retained, data.grad.as_mut()) {
+                    *grad += &existing;
+                }
+            } else {
+                data.grad = None;
+            }
+            if !retain_graph {
+                // 释放创建者（连同其保存的张量）与父节点
+                data.creator = None;
+                data.parents.clear();
+                data.creation_trace = None;
+                data.graph_freed = true;
             }
         }
+        Ok(())
     }
 }
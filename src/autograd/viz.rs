@@ -0,0 +1,107 @@
+//! 计算图可视化：把从输出张量出发的计算图导出为 Graphviz 的 DOT 格式。
+//!
+//! 每个张量是一个节点，标出创建它的运算（叶子节点标为参数名或 leaf）、形状与 `requires_grad`；
+//! 边从父节点指向结果。得到的文本可以用 `dot -Tsvg graph.dot -o graph.svg` 渲染。
+
+use crate::tensor::Tensor;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::Arc;
+
+/// 导出计算图，叶子节点不带名字
+pub fn to_dot(output: &Tensor) -> String {
+    to_dot_named(output, &[])
+}
+
+/// 导出计算图，`params` 中出现的叶子节点用对应的名字标注，
+/// 可以直接传入 `Module::named_parameters()` 的结果
+pub fn to_dot_named(output: &Tensor, params: &[(String, Tensor)]) -> String {
+    let names: HashMap<*const (), &str> = params
+        .iter()
+        .map(|(name, param)| (Arc::as_ptr(&param.0) as *const (), name.as_str()))
+        .collect();
+
+    // 按发现顺序编号，遍历用显式的栈，与反向传播一样不受图的深度限制
+    let mut ids: HashMap<*const (), usize> = HashMap::new();
+    let mut nodes = Vec::new();
+    let mut stack = vec![output.clone()];
+    ids.insert(Arc::as_ptr(&output.0) as *const (), 0);
+    while let Some(tensor) = stack.pop() {
+        for parent in &tensor.0.borrow().parents {
+            let next = ids.len();
+            ids.entry(Arc::as_ptr(parent) as *const ())
+                .or_insert_with(|| {
+                    stack.push(Tensor(Arc::clone(parent)));
+                    next
+                });
+        }
+        nodes.push(tensor);
+    }
+    nodes.sort_by_key(|tensor| ids[&(Arc::as_ptr(&tensor.0) as *const ())]);
+
+    let mut dot = String::from("digraph {\n");
+    dot.push_str("    node [shape=box, style=filled, fontname=\"monospace\"];\n");
+    for (id, tensor) in nodes.iter().enumerate() {
+        let data = tensor.0.borrow();
+        let name = names.get(&(Arc::as_ptr(&tensor.0) as *const ()));
+        let (title, color) = match (&data.creator, name) {
+            (Some(op), _) => (short_name(op.name()), "white"),
+            (None, Some(name)) => (format!("param: {}", name), "lightblue"),
+            (None, None) if data.graph_freed => ("(graph freed)".to_string(), "lightgray"),
+            (None, None) if data.requires_grad => ("leaf".to_string(), "lightblue"),
+            (None, None) => ("constant".to_string(), "lightgray"),
+        };
+        let color = if id == 0 { "darkolivegreen1" } else { color };
+        let label = format!(
+            "{}\n{:?}\nrequires_grad={}",
+            title,
+            data.shape(),
+            data.requires_grad
+        );
+        writeln!(
+            dot,
+            "    t{} [label=\"{}\", fillcolor={}];",
+            id,
+            escape(&label),
+            color
+        )
+        .unwrap();
+    }
+    for (id, tensor) in nodes.iter().enumerate() {
+        for parent in &tensor.0.borrow().parents {
+            let parent_id = ids[&(Arc::as_ptr(parent) as *const ())];
+            writeln!(dot, "    t{} -> t{};", parent_id, id).unwrap();
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// 去掉类型名中的模块路径，例如 `torch_rs::ops::mul::Multiply` 变为 `Multiply`
+fn short_name(type_name: &str) -> String {
+    let mut result = String::new();
+    let mut segment = String::new();
+    let mut chars = type_name.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == ':' && chars.peek() == Some(&':') {
+            chars.next();
+            segment.clear();
+        } else if c.is_alphanumeric() || c == '_' {
+            segment.push(c);
+        } else {
+            result.push_str(&segment);
+            segment.clear();
+            result.push(c);
+        }
+    }
+    result.push_str(&segment);
+    result
+}
+
+/// 转义 DOT 字符串中的引号、反斜杠与换行
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
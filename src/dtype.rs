@@ -0,0 +1,221 @@
+//! 张量的元素类型与类型提升规则。
+//!
+//! 存储按元素类型保存数据，`Tensor::data()` 总是返回 f32 副本，`Tensor::data_f64()` 返回 f64 副本。
+//! 运算的结果类型：
+//! - 加、减、乘、除、幂与矩阵乘法为提升后的类型，整数相除或求幂得到 f32；
+//! - exp、sin、softmax、mean 等浮点运算保持浮点输入的类型，整数与布尔输入得到 f32；
+//! - sum、prod 对整数与布尔输入得到 i64；取负、relu、max/min 以及形状变换、索引、拼接保持原类型。
+//!
+//! 结果不是 f32 时在 f64 下计算再转换。einsum 与分类损失只支持 f32，其他类型的输入直接 panic，
+//! 需要先用 `to_dtype(DType::F32)` 转换。只有浮点与复数类型的张量可以求梯度，梯度总是以 f32 保存。
+//!
+//! 复数张量把实部与虚部交替保存在 f32（complex64）或 f64（complex128）存储中，
+//! 梯度按 `view_as_real` 的形状（末尾多一维长度 2）保存为 `∂L/∂x + i·∂L/∂y`，
+//! 即 PyTorch 采用的共轭 Wirtinger 导数的两倍，可以直接用于梯度下降。
+
+use half::{bf16, f16};
+use ndarray::{ArrayD, Axis, Zip};
+use num_complex::Complex64;
+use std::fmt;
+
+/// 张量的元素类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DType {
+    F32,
+    F64,
+    F16,
+    BF16,
+    I32,
+    I64,
+    U8,
+    Bool,
+    /// 实部与虚部都是 f32 的复数
+    Complex64,
+    /// 实部与虚部都是 f64 的复数
+    Complex128,
+}
+
+/// 类型的大类，提升时大类高的一方优先
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Category {
+    Bool,
+    Integer,
+    Float,
+    Complex,
+}
+
+impl DType {
+    /// 是否为浮点类型
+    pub fn is_floating_point(self) -> bool {
+        self.category() == Category::Float
+    }
+
+    /// 是否为复数类型
+    pub fn is_complex(self) -> bool {
+        self.category() == Category::Complex
+    }
+
+    /// 复数类型实部与虚部的类型，实数类型返回自身
+    pub fn to_real(self) -> DType {
+        match self {
+            DType::Complex64 => DType::F32,
+            DType::Complex128 => DType::F64,
+            dtype => dtype,
+        }
+    }
+
+    /// 以该类型为实部与虚部的复数类型，f64 对应 complex128，其余实数类型对应 complex64
+    pub fn to_complex(self) -> DType {
+        match self {
+            DType::F64 | DType::Complex128 => DType::Complex128,
+            _ => DType::Complex64,
+        }
+    }
+
+    /// 浮点运算（exp、matmul 等）的结果类型：浮点与复数类型保持不变，整数与布尔提升为 f32
+    pub fn to_floating(self) -> DType {
+        match self.category() {
+            Category::Bool | Category::Integer => DType::F32,
+            Category::Float | Category::Complex => self,
+        }
+    }
+
+    fn category(self) -> Category {
+        match self {
+            DType::Bool => Category::Bool,
+            DType::U8 | DType::I32 | DType::I64 => Category::Integer,
+            DType::F32 | DType::F64 | DType::F16 | DType::BF16 => Category::Float,
+            DType::Complex64 | DType::Complex128 => Category::Complex,
+        }
+    }
+}
+
+/// 存储中的元素类型，与 f64 互相转换
+pub(crate) trait Element: Copy + Send + Sync + 'static {
+    fn to_f64(self) -> f64;
+
+    /// 浮点类型舍入到最近的值，整数向零截断并饱和，布尔取是否非零
+    fn from_f64(value: f64) -> Self;
+
+    fn to_f32(self) -> f32 {
+        self.to_f64() as f32
+    }
+}
+
+macro_rules! impl_element {
+    ($($t:ty),*) => {
+        $(impl Element for $t {
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+        })*
+    };
+}
+
+impl_element!(f32, f64, i32, i64, u8);
+
+impl Element for f16 {
+    fn to_f64(self) -> f64 {
+        f16::to_f64(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        f16::from_f64(value)
+    }
+}
+
+impl Element for bf16 {
+    fn to_f64(self) -> f64 {
+        bf16::to_f64(self)
+    }
+
+    fn from_f64(value: f64) -> Self {
+        bf16::from_f64(value)
+    }
+}
+
+impl Element for bool {
+    fn to_f64(self) -> f64 {
+        self as u8 as f64
+    }
+
+    fn from_f64(value: f64) -> Self {
+        value != 0.0
+    }
+}
+
+impl fmt::Display for DType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DType::F32 => "float32",
+            DType::F64 => "float64",
+            DType::F16 => "float16",
+            DType::BF16 => "bfloat16",
+            DType::I32 => "int32",
+            DType::I64 => "int64",
+            DType::U8 => "uint8",
+            DType::Bool => "bool",
+            DType::Complex64 => "complex64",
+            DType::Complex128 => "complex128",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 两个类型提升后的公共类型：大类高者优先，同一大类取能表示两者的较大类型，
+/// f16 与 bf16 提升为 f32，complex64 与 f64 提升为 complex128
+pub fn promote_types(a: DType, b: DType) -> DType {
+    use DType::*;
+    if a == b {
+        return a;
+    }
+    match a.category().cmp(&b.category()) {
+        std::cmp::Ordering::Greater if a.is_complex() && b == F64 => return Complex128,
+        std::cmp::Ordering::Less if b.is_complex() && a == F64 => return Complex128,
+        std::cmp::Ordering::Greater => return a,
+        std::cmp::Ordering::Less => return b,
+        std::cmp::Ordering::Equal => {}
+    }
+    match (a, b) {
+        (Complex128, _) | (_, Complex128) => Complex128,
+        (F64, _) | (_, F64) => F64,
+        (F32, _) | (_, F32) | (F16, BF16) | (BF16, F16) => F32,
+        (I64, _) | (_, I64) => I64,
+        (I32, _) | (_, I32) => I32,
+        _ => unreachable!("Unhandled promotion of {} and {}", a, b),
+    }
+}
+
+/// 二元运算结果的类型。
+///
+/// 与 PyTorch 一致，零维张量（包括与张量运算的 f32 标量）只在大类更高时才参与提升，
+/// 例如 f16 张量加 f32 标量仍为 f16，整数张量加 f32 标量为 f32。
+pub fn result_type(a: DType, a_scalar: bool, b: DType, b_scalar: bool) -> DType {
+    match (a_scalar, b_scalar) {
+        (true, false) if a.category() <= b.category() => b,
+        (false, true) if b.category() <= a.category() => a,
+        _ => promote_types(a, b),
+    }
+}
+
+/// 把末尾一维长度为 2 的实部、虚部对合成复数
+pub(crate) fn pairs_to_complex(pairs: &ArrayD<f64>) -> ArrayD<Complex64> {
+    let last = Axis(pairs.ndim() - 1);
+    let re = pairs.index_axis(last, 0);
+    let im = pairs.index_axis(last, 1);
+    Zip::from(&re)
+        .and(&im)
+        .map_collect(|&re, &im| Complex64::new(re, im))
+}
+
+/// 把复数拆成末尾一维长度为 2 的实部、虚部对，是 `pairs_to_complex` 的逆操作
+pub(crate) fn complex_to_pairs(data: &ArrayD<Complex64>) -> ArrayD<f64> {
+    let re = data.mapv(|z| z.re).insert_axis(Axis(data.ndim()));
+    let im = data.mapv(|z| z.im).insert_axis(Axis(data.ndim()));
+    ndarray::concatenate(Axis(data.ndim()), &[re.view(), im.view()])
+        .expect("Real and imaginary parts have the same shape")
+}
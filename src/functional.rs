@@ -1,6 +1,18 @@
 use crate::ops::Op;
 use crate::tensor::Tensor;
-use std::rc::Rc;
+use std::sync::Arc;
+
+/// 损失函数的归约方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reduction {
+    /// 不归约，返回每个样本的损失
+    None,
+    /// 对损失取平均
+    #[default]
+    Mean,
+    /// 对损失求和
+    Sum,
+}
 
 /// 计算预测值和目标值之间的均方误差（Mean Squared Error）。
 ///
@@ -11,12 +23,299 @@ use std::rc::Rc;
 /// # 返回
 /// 一个包含损失值的标量张量。
 pub fn mse_loss(prediction: &Tensor, target: &Tensor) -> Tensor {
-    let diff = prediction + &((-1.0 as f32) * target);
+    let diff = prediction - target;
     let squared_diff = &diff * &diff;
     // println!("Squared Difference: {:?}", squared_diff);
     squared_diff.mean()
 }
 
+/// 按归约方式汇总逐元素的损失。
+pub(crate) fn reduce(loss: Tensor, reduction: Reduction) -> Tensor {
+    match reduction {
+        Reduction::None => loss,
+        Reduction::Mean => loss.mean(),
+        Reduction::Sum => loss.sum(),
+    }
+}
+
+/// 由目标值构造常数掩码：`target == value` 的位置为 1，其余为 0。
+fn target_mask(target: &Tensor, value: f32) -> Tensor {
+    Tensor::new(target.data().mapv(|y| if y == value { 1.0 } else { 0.0 }))
+}
+
+/// 平均绝对误差（L1 Loss）。
+///
+/// # 参数
+/// * `prediction` - 模型的预测输出张量。
+/// * `target` - 真实的标签张量。
+/// * `reduction` - 归约方式。
+///
+/// # 返回
+/// `|prediction - target|` 按 `reduction` 归约后的损失。
+pub fn l1_loss(prediction: &Tensor, target: &Tensor, reduction: Reduction) -> Tensor {
+    reduce((prediction - target).abs(), reduction)
+}
+
+/// Huber损失：误差小于 `delta` 时为平方误差，否则为线性误差。
+///
+/// 逐元素损失为 `0.5 * d²`（`|d| < delta`）或 `delta * (|d| - 0.5 * delta)`。
+///
+/// # 参数
+/// * `prediction` - 模型的预测输出张量。
+/// * `target` - 真实的标签张量。
+/// * `delta` - 平方区与线性区的分界，必须为正。
+/// * `reduction` - 归约方式。
+pub fn huber_loss(
+    prediction: &Tensor,
+    target: &Tensor,
+    delta: f32,
+    reduction: Reduction,
+) -> Tensor {
+    if delta <= 0.0 {
+        panic!("huber_loss requires delta > 0, got {}", delta);
+    }
+    let abs_diff = (prediction - target).abs();
+    // 记 m = min(|d|, delta)，则 0.5 * m² + delta * (|d| - m) 恰好是分段的Huber损失
+    let quadratic = abs_diff.clamp(None, Some(delta));
+    let linear = &abs_diff - &quadratic;
+    let loss = &(&(&quadratic * &quadratic) * 0.5_f32) + &(&linear * delta);
+    reduce(loss, reduction)
+}
+
+/// Smooth L1损失，等价于 `huber_loss / beta`。
+///
+/// 逐元素损失为 `0.5 * d² / beta`（`|d| < beta`）或 `|d| - 0.5 * beta`。
+/// `beta` 为 0 时退化为 `l1_loss`。
+///
+/// # 参数
+/// * `prediction` - 模型的预测输出张量。
+/// * `target` - 真实的标签张量。
+/// * `beta` - 平方区与线性区的分界，不能为负。
+/// * `reduction` - 归约方式。
+pub fn smooth_l1_loss(
+    prediction: &Tensor,
+    target: &Tensor,
+    beta: f32,
+    reduction: Reduction,
+) -> Tensor {
+    if beta < 0.0 {
+        panic!("smooth_l1_loss requires beta >= 0, got {}", beta);
+    }
+    if beta == 0.0 {
+        return l1_loss(prediction, target, reduction);
+    }
+    let loss = huber_loss(prediction, target, beta, Reduction::None);
+    reduce(&loss / beta, reduction)
+}
+
+/// 二分类交叉熵损失，输入为概率。
+///
+/// 与PyTorch一致，对数值被截断到不小于 -100，避免概率为 0 或 1 时出现无穷大。
+///
+/// # 参数
+/// * `input` - 预测概率，取值范围 [0, 1]。
+/// * `target` - 目标概率，形状与 `input` 相同。
+/// * `weight` - 可选的逐元素权重，可广播到 `input` 的形状。
+/// * `reduction` - 归约方式。
+pub fn binary_cross_entropy(
+    input: &Tensor,
+    target: &Tensor,
+    weight: Option<&Tensor>,
+    reduction: Reduction,
+) -> Tensor {
+    if input.shape() != target.shape() {
+        panic!(
+            "binary_cross_entropy expects target shape {:?} to match input shape {:?}",
+            target.shape(),
+            input.shape()
+        );
+    }
+    let log_p = input.log().clamp(Some(-100.0), None);
+    let log_one_minus_p = (1.0_f32 - input).log().clamp(Some(-100.0), None);
+    let loss = -&(&(target * &log_p) + &(&(1.0_f32 - target) * &log_one_minus_p));
+    let loss = match weight {
+        Some(w) => &loss * w,
+        None => loss,
+    };
+    reduce(loss, reduction)
+}
+
+/// 带sigmoid的二分类交叉熵损失，输入为logits。
+///
+/// 使用 `(1 - y) * x + (1 + (pos_weight - 1) * y) * softplus(-x)` 计算，
+/// 比先求sigmoid再调用 `binary_cross_entropy` 更稳定。
+///
+/// # 参数
+/// * `input` - 未经sigmoid的logits。
+/// * `target` - 目标概率，形状与 `input` 相同。
+/// * `weight` - 可选的逐元素权重，可广播到 `input` 的形状。
+/// * `pos_weight` - 可选的正样本权重，沿最后一维（类别）广播。
+/// * `reduction` - 归约方式。
+pub fn binary_cross_entropy_with_logits(
+    input: &Tensor,
+    target: &Tensor,
+    weight: Option<&Tensor>,
+    pos_weight: Option<&Tensor>,
+    reduction: Reduction,
+) -> Tensor {
+    if input.shape() != target.shape() {
+        panic!(
+            "binary_cross_entropy_with_logits expects target shape {:?} to match input shape {:?}",
+            target.shape(),
+            input.shape()
+        );
+    }
+    // softplus(-x) = ln(1 + e^(-x)) = -ln(sigmoid(x))
+    let neg_log_sigmoid = (-input).softplus();
+    let log_weight = match pos_weight {
+        Some(pw) => &(&(pw - 1.0_f32) * target) + 1.0_f32,
+        None => Tensor::ones(&target.shape()),
+    };
+    let loss = &(&(1.0_f32 - target) * input) + &(&log_weight * &neg_log_sigmoid);
+    let loss = match weight {
+        Some(w) => &loss * w,
+        None => loss,
+    };
+    reduce(loss, reduction)
+}
+
+/// KL散度损失 `KL(target || exp(input))`。
+///
+/// 与PyTorch一致，`input` 为对数概率；`log_target` 为真时 `target` 也为对数概率。
+/// 数学上的KL散度应使用 `Reduction::Sum` 再除以批大小，`Reduction::Mean` 是对所有元素取平均。
+///
+/// # 参数
+/// * `input` - 对数概率。
+/// * `target` - 目标概率（或对数概率）。
+/// * `log_target` - `target` 是否为对数概率。
+/// * `reduction` - 归约方式。
+pub fn kl_div(input: &Tensor, target: &Tensor, log_target: bool, reduction: Reduction) -> Tensor {
+    let loss = if log_target {
+        &target.exp() * &(target - input)
+    } else {
+        // target 为 0 处约定 0 * ln(0) = 0：截断后 ln 为有限值，乘以 0 得 0
+        let log_target = target.clamp(Some(f32::MIN_POSITIVE), None).log();
+        target * &(&log_target - input)
+    };
+    reduce(loss, reduction)
+}
+
+/// 余弦嵌入损失，用于判断两个输入是否相似。
+///
+/// 逐样本损失为 `1 - cos(x1, x2)`（`y = 1`）或 `max(0, cos(x1, x2) - margin)`（`y = -1`）。
+///
+/// # 参数
+/// * `x1`, `x2` - 形状为 `[N, D]` 或 `[D]` 的输入。
+/// * `target` - 取值为 1 或 -1，形状为 `[N]` 或标量。
+/// * `margin` - 不相似样本的间隔，建议取 [-1, 1]。
+/// * `reduction` - 归约方式。
+pub fn cosine_embedding_loss(
+    x1: &Tensor,
+    x2: &Tensor,
+    target: &Tensor,
+    margin: f32,
+    reduction: Reduction,
+) -> Tensor {
+    const EPSILON: f32 = 1e-12;
+    let last = x1
+        .dim()
+        .checked_sub(1)
+        .expect("Input must have at least 1 dimension");
+    let dot = (x1 * x2).sum_dims(&[last], false);
+    let mag1 = &(x1 * x1).sum_dims(&[last], false) + EPSILON;
+    let mag2 = &(x2 * x2).sum_dims(&[last], false) + EPSILON;
+    let cos = &dot / &(&mag1 * &mag2).sqrt();
+
+    let positive = &target_mask(target, 1.0) * &(1.0_f32 - &cos);
+    let negative = &target_mask(target, -1.0) * &relu(&(&cos - margin));
+    reduce(&positive + &negative, reduction)
+}
+
+/// 排序间隔损失 `max(0, -y * (x1 - x2) + margin)`。
+///
+/// # 参数
+/// * `x1`, `x2` - 待比较的两组得分。
+/// * `target` - 取值为 1（x1 应更大）或 -1（x2 应更大）。
+/// * `margin` - 间隔。
+/// * `reduction` - 归约方式。
+pub fn margin_ranking_loss(
+    x1: &Tensor,
+    x2: &Tensor,
+    target: &Tensor,
+    margin: f32,
+    reduction: Reduction,
+) -> Tensor {
+    let loss = relu(&(margin - &(target * &(x1 - x2))));
+    reduce(loss, reduction)
+}
+
+/// 三元组间隔损失 `max(d(a, p) - d(a, n) + margin, 0)`，其中 `d(x, y) = ‖x - y + eps‖_p`。
+///
+/// # 参数
+/// * `anchor`, `positive`, `negative` - 形状为 `[N, D]` 或 `[D]` 的输入，沿最后一维计算距离。
+/// * `margin` - 间隔。
+/// * `p` - 范数的阶数。
+/// * `eps` - 防止距离为 0 时梯度无穷大的小常数。
+/// * `swap` - 是否使用 `min(d(a, n), d(p, n))` 作为负样本距离（distance swap）。
+/// * `reduction` - 归约方式。
+#[allow(clippy::too_many_arguments)]
+pub fn triplet_margin_loss(
+    anchor: &Tensor,
+    positive: &Tensor,
+    negative: &Tensor,
+    margin: f32,
+    p: f32,
+    eps: f32,
+    swap: bool,
+    reduction: Reduction,
+) -> Tensor {
+    if p <= 0.0 {
+        panic!("triplet_margin_loss requires p > 0, got {}", p);
+    }
+    let distance = |x: &Tensor, y: &Tensor| {
+        let last = x
+            .dim()
+            .checked_sub(1)
+            .expect("Input must have at least 1 dimension");
+        (&(x - y) + eps)
+            .abs()
+            .powf(p)
+            .sum_dims(&[last], false)
+            .powf(1.0 / p)
+    };
+    let d_pos = distance(anchor, positive);
+    let mut d_neg = distance(anchor, negative);
+    if swap {
+        // min(a, b) = a - relu(a - b)
+        let d_swap = distance(positive, negative);
+        d_neg = &d_neg - &relu(&(&d_neg - &d_swap));
+    }
+    let loss = relu(&(&(&d_pos - &d_neg) + margin));
+    reduce(loss, reduction)
+}
+
+/// Hinge嵌入损失。
+///
+/// 逐元素损失为 `x`（`y = 1`）或 `max(0, margin - x)`（`y = -1`）。
+///
+/// # 参数
+/// * `input` - 输入（通常为两个样本间的距离）。
+/// * `target` - 取值为 1 或 -1，形状与 `input` 相同。
+/// * `margin` - 间隔。
+/// * `reduction` - 归约方式。
+pub fn hinge_embedding_loss(
+    input: &Tensor,
+    target: &Tensor,
+    margin: f32,
+    reduction: Reduction,
+) -> Tensor {
+    let not_positive = Tensor::new(target.data().mapv(|y| if y != 1.0 { 1.0 } else { 0.0 }));
+    let not_negative = Tensor::new(target.data().mapv(|y| if y != -1.0 { 1.0 } else { 0.0 }));
+    let margin_term = &not_positive * &relu(&(margin - input));
+    let self_term = &not_negative * input;
+    reduce(&margin_term + &self_term, reduction)
+}
+
 /// ReLU激活函数。
 ///
 /// # 参数
@@ -25,7 +324,175 @@ pub fn mse_loss(prediction: &Tensor, target: &Tensor) -> Tensor {
 /// # 返回
 /// 应用ReLU后的张量。
 pub fn relu(input: &Tensor) -> Tensor {
-    let op = Rc::new(crate::ops::relu::ReLU::new());
-    let res = op.forward(&[input]);
-    res
+    let op = Arc::new(crate::ops::relu::ReLU::new());
+    op.forward(&[input])
+}
+
+/// 逐元素指数函数 `e^x`。
+pub fn exp(input: &Tensor) -> Tensor {
+    input.exp()
+}
+
+/// 逐元素自然对数 `ln(x)`。
+pub fn log(input: &Tensor) -> Tensor {
+    input.log()
+}
+
+/// 逐元素计算 `ln(1 + x)`，在 x 接近 0 时更精确。
+pub fn log1p(input: &Tensor) -> Tensor {
+    input.log1p()
+}
+
+/// 逐元素平方根。
+pub fn sqrt(input: &Tensor) -> Tensor {
+    input.sqrt()
+}
+
+/// 逐元素平方根倒数 `1/√x`。
+pub fn rsqrt(input: &Tensor) -> Tensor {
+    input.rsqrt()
+}
+
+/// 逐元素绝对值。
+pub fn abs(input: &Tensor) -> Tensor {
+    input.abs()
+}
+
+/// 逐元素符号函数，返回 -1、0 或 1。
+pub fn sign(input: &Tensor) -> Tensor {
+    input.sign()
+}
+
+/// 逐元素正弦函数。
+pub fn sin(input: &Tensor) -> Tensor {
+    input.sin()
+}
+
+/// 逐元素余弦函数。
+pub fn cos(input: &Tensor) -> Tensor {
+    input.cos()
+}
+
+/// Tanh激活函数。
+///
+/// # 参数
+/// * `input` - 输入张量。
+///
+/// # 返回
+/// 应用tanh后的张量。
+pub fn tanh(input: &Tensor) -> Tensor {
+    input.tanh()
+}
+
+/// Sigmoid激活函数 `1 / (1 + e^(-x))`。
+///
+/// # 参数
+/// * `input` - 输入张量。
+///
+/// # 返回
+/// 应用sigmoid后的张量，取值范围为 (0, 1)。
+pub fn sigmoid(input: &Tensor) -> Tensor {
+    input.sigmoid()
+}
+
+/// Softplus激活函数 `ln(1 + e^x)`，是ReLU的平滑近似。
+///
+/// # 参数
+/// * `input` - 输入张量。
+///
+/// # 返回
+/// 应用softplus后的张量。
+pub fn softplus(input: &Tensor) -> Tensor {
+    input.softplus()
+}
+
+/// Softmax函数，沿指定维度将输入归一化为概率分布。
+///
+/// 计算时先减去最大值，避免指数上溢。
+///
+/// # 参数
+/// * `input` - 输入张量。
+/// * `dim` - 归一化的维度。
+///
+/// # 返回
+/// 与输入形状相同的张量，沿 `dim` 的元素之和为 1。
+pub fn softmax(input: &Tensor, dim: usize) -> Tensor {
+    input.softmax(dim)
+}
+
+/// LogSoftmax函数，沿指定维度计算 `ln(softmax(x))`。
+///
+/// 比先求softmax再取对数更稳定。
+///
+/// # 参数
+/// * `input` - 输入张量。
+/// * `dim` - 归一化的维度。
+///
+/// # 返回
+/// 与输入形状相同的对数概率张量。
+pub fn log_softmax(input: &Tensor, dim: usize) -> Tensor {
+    input.log_softmax(dim)
+}
+
+/// 爱因斯坦求和约定表示的张量缩并，可以表达矩阵乘法、双线性形式、注意力分数等运算。
+///
+/// # 参数
+/// * `equation` - 形如 `"ij,jk->ik"` 的表达式，下标为小写字母；省略 `->` 时输出为只出现一次的下标（按字母序）。
+/// * `operands` - 操作数，个数与表达式中的操作数一致。
+///
+/// # 返回
+/// 缩并结果，对每个操作数都可以求导。
+pub fn einsum(equation: &str, operands: &[&Tensor]) -> Tensor {
+    crate::ops::einsum::einsum(equation, operands)
+}
+
+/// 负对数似然损失（Negative Log Likelihood）。
+///
+/// # 参数
+/// * `input` - 对数概率，形状为 `[C]`、`[N, C]` 或 `[N, C, d1, ...]`。
+/// * `target` - 类别下标，形状为输入去掉类别维（第1维）。
+/// * `weight` - 可选的类别权重，形状为 `[C]`。
+/// * `ignore_index` - 可选的忽略类别，该类样本不贡献损失和梯度。
+/// * `reduction` - 归约方式。`Mean` 时除以未忽略样本的权重之和。
+///
+/// # 返回
+/// 损失张量，`Reduction::None` 时形状与 `target` 相同，否则为标量。
+pub fn nll_loss(
+    input: &Tensor,
+    target: &Tensor,
+    weight: Option<&Tensor>,
+    ignore_index: Option<usize>,
+    reduction: Reduction,
+) -> Tensor {
+    crate::ops::cross_entropy::nll_loss(input, target, weight, ignore_index, reduction)
+}
+
+/// 交叉熵损失，等价于 `log_softmax` 后接 `nll_loss`，但梯度是融合计算的。
+///
+/// # 参数
+/// * `input` - 未归一化的logits，形状为 `[C]`、`[N, C]` 或 `[N, C, d1, ...]`。
+/// * `target` - 类别下标，形状为输入去掉类别维（第1维）。
+/// * `weight` - 可选的类别权重，形状为 `[C]`。
+/// * `ignore_index` - 可选的忽略类别，该类样本不贡献损失和梯度。
+/// * `label_smoothing` - 标签平滑系数，取值范围 [0, 1]。
+/// * `reduction` - 归约方式。`Mean` 时除以未忽略样本的权重之和。
+///
+/// # 返回
+/// 损失张量，`Reduction::None` 时形状与 `target` 相同，否则为标量。
+pub fn cross_entropy(
+    input: &Tensor,
+    target: &Tensor,
+    weight: Option<&Tensor>,
+    ignore_index: Option<usize>,
+    label_smoothing: f32,
+    reduction: Reduction,
+) -> Tensor {
+    crate::ops::cross_entropy::cross_entropy(
+        input,
+        target,
+        weight,
+        ignore_index,
+        label_smoothing,
+        reduction,
+    )
 }
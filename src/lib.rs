@@ -13,9 +13,11 @@
 //! demo目录下有一些简单的示例程序。由于数值问题暂时尚未解决，现在只能在少量数据下运行。实测可以正常收敛。
 
 pub mod autograd;
+pub mod dtype;
 pub mod functional;
 pub mod nn;
 pub mod ops;
 pub mod optimizer;
+pub mod storage;
 pub mod tensor;
 pub mod utils;
@@ -12,7 +12,7 @@ fn main() {
     let mut x = vec![];
     let mut y = vec![];
     let max_x = 2.0 * 50.0;
-    let max_y = ((max_x * max_x) * 2.0) as f32;
+    let max_y = (max_x * max_x) * 2.0;
     for i in 0..50 {
         let x_val = [i as f32, (i + 1) as f32];
         let y_val = (((i + (i + 1)) * (i + (i + 1))) as f32) * 2.0;
@@ -0,0 +1,48 @@
+use crate::autograd::checkpoint::checkpoint_segment;
+use crate::nn::Module;
+use crate::tensor::Tensor;
+use std::sync::{Arc, RwLock};
+
+/// 激活检查点包装层：前向计算时丢弃被包裹模块的中间结果，反向传播时重新计算。
+///
+/// 输入不需要梯度时，只要模块的参数需要梯度也会记录计算图，因此可以包裹网络的第一层。
+#[derive(Debug)]
+pub struct Checkpoint {
+    module: Arc<RwLock<Box<dyn Module>>>,
+}
+
+impl Checkpoint {
+    pub fn new(module: Box<dyn Module>) -> Self {
+        Checkpoint {
+            module: Arc::new(RwLock::new(module)),
+        }
+    }
+}
+
+impl Module for Checkpoint {
+    fn forward(&self, input: &Tensor) -> Tensor {
+        let params_require_grad = self
+            .parameters()
+            .iter()
+            .any(|param| param.0.borrow().requires_grad);
+        let module = Arc::clone(&self.module);
+        let segment = move |inputs: &[Tensor]| module.read().unwrap().forward(&inputs[0]);
+        checkpoint_segment(Arc::new(segment), &[input], params_require_grad)
+    }
+
+    fn parameters(&self) -> Vec<Tensor> {
+        self.module.read().unwrap().parameters()
+    }
+
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        self.module.read().unwrap().named_parameters()
+    }
+
+    fn train(&mut self) {
+        self.module.write().unwrap().train();
+    }
+
+    fn eval(&mut self) {
+        self.module.write().unwrap().eval();
+    }
+}
@@ -47,6 +47,13 @@ impl Module for Linear {
     fn parameters(&self) -> Vec<Tensor> {
         vec![self.w.clone(), self.b.clone()]
     }
+    /// 获取参数及其名字
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        vec![
+            ("w".to_string(), self.w.clone()),
+            ("b".to_string(), self.b.clone()),
+        ]
+    }
     /// 切换到训练模式
     fn train(&mut self) {
         self.training = true;
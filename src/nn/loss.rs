@@ -0,0 +1,482 @@
+use crate::functional::{self, Reduction};
+use crate::nn::Module;
+use crate::tensor::Tensor;
+use std::fmt::Debug;
+
+/// 损失函数通用trait。
+///
+/// 损失函数以结构体形式保存配置，可以通过 `Box<dyn Loss>` 选择。
+/// 输入依次为预测值与目标值；比较两个（或三个）输入的损失按文档说明的顺序传入。
+///
+/// 每个损失同时实现了 `Module`：用 `with_targets` 绑定预测值之后的其余输入，
+/// 就可以作为 `Sequential` 的最后一层，`forward` 的输入即预测值。
+pub trait Loss: Debug {
+    /// 计算损失
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor;
+}
+
+/// 检查输入个数，返回与 `N` 等长的数组
+fn expect_inputs<'a, const N: usize>(inputs: &[&'a Tensor], name: &str) -> [&'a Tensor; N] {
+    inputs
+        .try_into()
+        .unwrap_or_else(|_| panic!("{} expects {} input tensors, got {}", name, N, inputs.len()))
+}
+
+/// 为损失实现 `Module` 与 `with_targets`，损失没有可训练参数，训练/评估模式下行为相同
+macro_rules! impl_module {
+    ($($loss:ident),* $(,)?) => {
+        $(
+            impl $loss {
+                /// 绑定预测值之后的其余输入，作为 `Module` 使用时与 `forward` 的输入一起计算损失
+                pub fn with_targets(mut self, targets: &[&Tensor]) -> Self {
+                    self.targets = targets.iter().map(|&target| target.clone()).collect();
+                    self
+                }
+            }
+
+            impl Module for $loss {
+                fn forward(&self, input: &Tensor) -> Tensor {
+                    assert!(
+                        !self.targets.is_empty(),
+                        "{} used as a Module needs its targets set with with_targets",
+                        stringify!($loss)
+                    );
+                    let mut inputs = vec![input];
+                    inputs.extend(&self.targets);
+                    Loss::forward(self, &inputs)
+                }
+
+                fn parameters(&self) -> Vec<Tensor> {
+                    vec![]
+                }
+
+                fn train(&mut self) {}
+
+                fn eval(&mut self) {}
+            }
+        )*
+    };
+}
+
+impl_module!(
+    MSELoss,
+    L1Loss,
+    SmoothL1Loss,
+    HuberLoss,
+    BCELoss,
+    BCEWithLogitsLoss,
+    KLDivLoss,
+    CosineEmbeddingLoss,
+    MarginRankingLoss,
+    TripletMarginLoss,
+    HingeEmbeddingLoss,
+    CrossEntropyLoss,
+    NLLLoss,
+);
+
+/// 均方误差损失，输入为 `[prediction, target]`
+#[derive(Debug, Default)]
+pub struct MSELoss {
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl MSELoss {
+    pub fn new(reduction: Reduction) -> Self {
+        MSELoss {
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for MSELoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [prediction, target] = expect_inputs(inputs, "MSELoss");
+        let diff = prediction - target;
+        functional::reduce(&diff * &diff, self.reduction)
+    }
+}
+
+/// 平均绝对误差损失，输入为 `[prediction, target]`
+#[derive(Debug, Default)]
+pub struct L1Loss {
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl L1Loss {
+    pub fn new(reduction: Reduction) -> Self {
+        L1Loss {
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for L1Loss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [prediction, target] = expect_inputs(inputs, "L1Loss");
+        functional::l1_loss(prediction, target, self.reduction)
+    }
+}
+
+/// Smooth L1损失，输入为 `[prediction, target]`
+#[derive(Debug)]
+pub struct SmoothL1Loss {
+    pub beta: f32,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl SmoothL1Loss {
+    pub fn new(beta: f32, reduction: Reduction) -> Self {
+        SmoothL1Loss {
+            beta,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Default for SmoothL1Loss {
+    fn default() -> Self {
+        Self::new(1.0, Reduction::Mean)
+    }
+}
+
+impl Loss for SmoothL1Loss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [prediction, target] = expect_inputs(inputs, "SmoothL1Loss");
+        functional::smooth_l1_loss(prediction, target, self.beta, self.reduction)
+    }
+}
+
+/// Huber损失，输入为 `[prediction, target]`
+#[derive(Debug)]
+pub struct HuberLoss {
+    pub delta: f32,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl HuberLoss {
+    pub fn new(delta: f32, reduction: Reduction) -> Self {
+        HuberLoss {
+            delta,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Default for HuberLoss {
+    fn default() -> Self {
+        Self::new(1.0, Reduction::Mean)
+    }
+}
+
+impl Loss for HuberLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [prediction, target] = expect_inputs(inputs, "HuberLoss");
+        functional::huber_loss(prediction, target, self.delta, self.reduction)
+    }
+}
+
+/// 二分类交叉熵损失，输入为 `[probability, target]`
+#[derive(Debug, Default)]
+pub struct BCELoss {
+    pub weight: Option<Tensor>,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl BCELoss {
+    pub fn new(weight: Option<Tensor>, reduction: Reduction) -> Self {
+        BCELoss {
+            weight,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for BCELoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [input, target] = expect_inputs(inputs, "BCELoss");
+        functional::binary_cross_entropy(input, target, self.weight.as_ref(), self.reduction)
+    }
+}
+
+/// 带sigmoid的二分类交叉熵损失，输入为 `[logits, target]`
+#[derive(Debug, Default)]
+pub struct BCEWithLogitsLoss {
+    pub weight: Option<Tensor>,
+    pub pos_weight: Option<Tensor>,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl BCEWithLogitsLoss {
+    pub fn new(weight: Option<Tensor>, pos_weight: Option<Tensor>, reduction: Reduction) -> Self {
+        BCEWithLogitsLoss {
+            weight,
+            pos_weight,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for BCEWithLogitsLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [input, target] = expect_inputs(inputs, "BCEWithLogitsLoss");
+        functional::binary_cross_entropy_with_logits(
+            input,
+            target,
+            self.weight.as_ref(),
+            self.pos_weight.as_ref(),
+            self.reduction,
+        )
+    }
+}
+
+/// KL散度损失，输入为 `[log_probability, target]`
+#[derive(Debug, Default)]
+pub struct KLDivLoss {
+    pub log_target: bool,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl KLDivLoss {
+    pub fn new(log_target: bool, reduction: Reduction) -> Self {
+        KLDivLoss {
+            log_target,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for KLDivLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [input, target] = expect_inputs(inputs, "KLDivLoss");
+        functional::kl_div(input, target, self.log_target, self.reduction)
+    }
+}
+
+/// 余弦嵌入损失，输入为 `[x1, x2, target]`
+#[derive(Debug, Default)]
+pub struct CosineEmbeddingLoss {
+    pub margin: f32,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl CosineEmbeddingLoss {
+    pub fn new(margin: f32, reduction: Reduction) -> Self {
+        CosineEmbeddingLoss {
+            margin,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for CosineEmbeddingLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [x1, x2, target] = expect_inputs(inputs, "CosineEmbeddingLoss");
+        functional::cosine_embedding_loss(x1, x2, target, self.margin, self.reduction)
+    }
+}
+
+/// 排序间隔损失，输入为 `[x1, x2, target]`
+#[derive(Debug, Default)]
+pub struct MarginRankingLoss {
+    pub margin: f32,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl MarginRankingLoss {
+    pub fn new(margin: f32, reduction: Reduction) -> Self {
+        MarginRankingLoss {
+            margin,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for MarginRankingLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [x1, x2, target] = expect_inputs(inputs, "MarginRankingLoss");
+        functional::margin_ranking_loss(x1, x2, target, self.margin, self.reduction)
+    }
+}
+
+/// 三元组间隔损失，输入为 `[anchor, positive, negative]`
+#[derive(Debug)]
+pub struct TripletMarginLoss {
+    pub margin: f32,
+    pub p: f32,
+    pub eps: f32,
+    pub swap: bool,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl TripletMarginLoss {
+    /// 创建三元组间隔损失，`eps` 默认为 1e-6，不使用distance swap
+    pub fn new(margin: f32, p: f32, reduction: Reduction) -> Self {
+        TripletMarginLoss {
+            margin,
+            p,
+            eps: 1e-6,
+            swap: false,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Default for TripletMarginLoss {
+    fn default() -> Self {
+        Self::new(1.0, 2.0, Reduction::Mean)
+    }
+}
+
+impl Loss for TripletMarginLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [anchor, positive, negative] = expect_inputs(inputs, "TripletMarginLoss");
+        functional::triplet_margin_loss(
+            anchor,
+            positive,
+            negative,
+            self.margin,
+            self.p,
+            self.eps,
+            self.swap,
+            self.reduction,
+        )
+    }
+}
+
+/// Hinge嵌入损失，输入为 `[input, target]`
+#[derive(Debug)]
+pub struct HingeEmbeddingLoss {
+    pub margin: f32,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl HingeEmbeddingLoss {
+    pub fn new(margin: f32, reduction: Reduction) -> Self {
+        HingeEmbeddingLoss {
+            margin,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Default for HingeEmbeddingLoss {
+    fn default() -> Self {
+        Self::new(1.0, Reduction::Mean)
+    }
+}
+
+impl Loss for HingeEmbeddingLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [input, target] = expect_inputs(inputs, "HingeEmbeddingLoss");
+        functional::hinge_embedding_loss(input, target, self.margin, self.reduction)
+    }
+}
+
+/// 交叉熵损失，输入为 `[logits, target]`
+#[derive(Debug, Default)]
+pub struct CrossEntropyLoss {
+    pub weight: Option<Tensor>,
+    pub ignore_index: Option<usize>,
+    pub label_smoothing: f32,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl CrossEntropyLoss {
+    pub fn new(
+        weight: Option<Tensor>,
+        ignore_index: Option<usize>,
+        label_smoothing: f32,
+        reduction: Reduction,
+    ) -> Self {
+        CrossEntropyLoss {
+            weight,
+            ignore_index,
+            label_smoothing,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for CrossEntropyLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [input, target] = expect_inputs(inputs, "CrossEntropyLoss");
+        functional::cross_entropy(
+            input,
+            target,
+            self.weight.as_ref(),
+            self.ignore_index,
+            self.label_smoothing,
+            self.reduction,
+        )
+    }
+}
+
+/// 负对数似然损失，输入为 `[log_probability, target]`
+#[derive(Debug, Default)]
+pub struct NLLLoss {
+    pub weight: Option<Tensor>,
+    pub ignore_index: Option<usize>,
+    pub reduction: Reduction,
+    /// 作为 `Module` 使用时预测值之后的其余输入，见 `with_targets`
+    pub targets: Vec<Tensor>,
+}
+
+impl NLLLoss {
+    pub fn new(weight: Option<Tensor>, ignore_index: Option<usize>, reduction: Reduction) -> Self {
+        NLLLoss {
+            weight,
+            ignore_index,
+            reduction,
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl Loss for NLLLoss {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let [input, target] = expect_inputs(inputs, "NLLLoss");
+        functional::nll_loss(
+            input,
+            target,
+            self.weight.as_ref(),
+            self.ignore_index,
+            self.reduction,
+        )
+    }
+}
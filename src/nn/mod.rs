@@ -1,4 +1,6 @@
+pub mod checkpoint;
 pub mod linear;
+pub mod loss;
 pub mod relu;
 pub mod sequential;
 
@@ -8,11 +10,19 @@ use std::fmt::Debug;
 /// 神经网络模块通用trait。
 ///
 /// 支持前向传播、参数获取、训练/评估模式切换。
-pub trait Module: Debug {
+pub trait Module: Debug + Send + Sync {
     /// 前向传播
     fn forward(&self, inputs: &Tensor) -> Tensor;
     /// 获取所有可训练参数
     fn parameters(&self) -> Vec<Tensor>;
+    /// 获取所有可训练参数及其名字，默认按顺序以下标命名
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        self.parameters()
+            .into_iter()
+            .enumerate()
+            .map(|(i, param)| (i.to_string(), param))
+            .collect()
+    }
     /// 切换到训练模式
     fn train(&mut self);
     /// 切换到评估模式
@@ -9,6 +9,12 @@ impl ReLU {
         ReLU
     }
 }
+
+impl Default for ReLU {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Module for ReLU {
     fn forward(&self, input: &Tensor) -> Tensor {
         functional::relu(input)
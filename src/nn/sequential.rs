@@ -33,6 +33,16 @@ impl Module for Sequential {
         params
     }
 
+    fn named_parameters(&self) -> Vec<(String, Tensor)> {
+        let mut params = Vec::new();
+        for (i, layer) in self.layers.iter().enumerate() {
+            for (name, param) in layer.named_parameters() {
+                params.push((format!("{}.{}", i, name), param));
+            }
+        }
+        params
+    }
+
     fn train(&mut self) {
         for layer in &mut self.layers {
             layer.train();
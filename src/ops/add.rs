@@ -1,8 +1,10 @@
-use super::Op;
+use super::broadcast::{binary_result_type, broadcast_binary, sum_to, sum_to_shape};
+use super::complex::{BinaryKind, ComplexBinary};
+use super::{Op, linear_jvp, needs_grad, propagate_tangent};
 use crate::tensor::Tensor;
-use ndarray::{ArrayD, Axis, IxDyn};
+use ndarray::{ArrayD, arr0};
 use std::ops::Add;
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct AddOp {
@@ -15,33 +17,6 @@ impl AddOp {
             input_shapes: vec![shape1, shape2],
         }
     }
-
-    // 获取广播后的形状
-    fn get_broadcasted_shape(shape1: &[usize], shape2: &[usize]) -> Vec<usize> {
-        let len1 = shape1.len();
-        let len2 = shape2.len();
-        let max_len = std::cmp::max(len1, len2);
-
-        let mut result = Vec::with_capacity(max_len);
-
-        // 从尾部开始，依次比较对齐的维度
-        for i in 0..max_len {
-            let dim1 = if i < len1 { shape1[len1 - 1 - i] } else { 1 };
-            let dim2 = if i < len2 { shape2[len2 - 1 - i] } else { 1 };
-
-            if dim1 != 1 && dim2 != 1 && dim1 != dim2 {
-                panic!(
-                    "Incompatible dimensions for broadcasting: {} and {}",
-                    dim1, dim2
-                );
-            }
-
-            result.push(std::cmp::max(dim1, dim2));
-        }
-
-        result.reverse();
-        result
-    }
 }
 
 impl Op for AddOp {
@@ -50,54 +25,29 @@ impl Op for AddOp {
         if inputs.len() != 2 {
             panic!("AddOp requires exactly two input tensors");
         }
+        if inputs.iter().any(|input| input.is_complex()) {
+            return ComplexBinary::new(BinaryKind::Add).forward(inputs);
+        }
 
-        let a = &inputs[0].0.borrow().data;
-        let b = &inputs[1].0.borrow().data;
-
-        // 使用ndarray的广播机制处理张量加法
-        let result_data = match (a.broadcast(b.shape()), b.broadcast(a.shape())) {
-            (Some(a_broadcast), _) => &a_broadcast + b,
-            (_, Some(b_broadcast)) => a + &b_broadcast,
-            // 如果两个都不能直接广播，尝试计算共同的广播形状
-            _ => {
-                let shape1 = a.shape();
-                let shape2 = b.shape();
-                let output_shape = Self::get_broadcasted_shape(shape1, shape2);
-
-                let a_broadcast = a
-                    .broadcast(IxDyn(&output_shape))
-                    .expect("Failed to broadcast first tensor");
-                let b_broadcast = b
-                    .broadcast(IxDyn(&output_shape))
-                    .expect("Failed to broadcast second tensor");
-
-                &a_broadcast.to_owned() + &b_broadcast
-            }
-        };
+        // 使用广播机制处理张量加法，结果按类型提升规则确定类型
+        let dtype = binary_result_type(inputs[0], inputs[1]);
+        let output = broadcast_binary(inputs[0], inputs[1], dtype, |x, y| x + y, |x, y| x + y);
 
         // 创建一个新的op实例，存储输入形状供反向传播使用
-        let op = AddOp::new(a.shape().to_vec(), b.shape().to_vec());
-
-        // 创建新的张量作为输出
-        let output = Tensor::new(result_data);
-        {
+        let op = AddOp::new(inputs[0].shape(), inputs[1].shape());
+        if needs_grad(&[inputs[0], inputs[1]]) {
             let mut output_data = output.0.borrow_mut();
-            output_data.set_creator(Rc::new(op));
-
-            // 添加需要梯度的父节点
-            if inputs[0].0.borrow().requires_grad {
-                output_data.add_parent(inputs[0]);
-            }
+            output_data.set_creator(Arc::new(op));
 
-            if inputs[1].0.borrow().requires_grad {
-                output_data.add_parent(inputs[1]);
-            }
+            // 父节点与输入一一对应，反向传播按位置把梯度分给父节点
+            output_data.add_parent(inputs[0]);
+            output_data.add_parent(inputs[1]);
 
-            // 如果任一输入需要梯度，则输出也需要梯度
-            output_data.requires_grad =
-                inputs[0].0.borrow().requires_grad || inputs[1].0.borrow().requires_grad;
+            // 任一输入需要梯度，输出也需要梯度
+            output_data.requires_grad = true;
         }
 
+        propagate_tangent(self, inputs, &output);
         output
     }
 
@@ -109,45 +59,64 @@ impl Op for AddOp {
             .as_ref()
             .expect("Gradient should not be None")
             .clone();
-        let mut grads = Vec::with_capacity(2);
         // 对于每个输入形状，处理梯度的反向广播
-        for shape in &self.input_shapes {
-            let mut result = grad.clone();
-            // 先对多余的维度 sum
-            while result.ndim() > shape.len() {
-                result = result.sum_axis(Axis(0));
-            }
-            // 再对 shape=1 的维度 sum，倒序处理 axis
-            let result_shape: Vec<usize> = result.shape().to_vec();
-            let mut sum_axes = Vec::new();
-            for (i, (&s, &g)) in shape.iter().zip(result_shape.iter()).enumerate() {
-                if s == 1 && g > 1 {
-                    sum_axes.push(i);
-                }
-            }
-            sum_axes.sort_unstable_by(|a, b| b.cmp(a));
-            for axis in sum_axes {
-                result = result.sum_axis(Axis(axis));
-            }
-            // 确保形状完全匹配
-            if result.shape() != shape.as_slice() {
-                result = result.into_shape(IxDyn(shape)).unwrap();
-            }
-            grads.push(result);
-        }
-        grads
+        self.input_shapes
+            .iter()
+            .map(|shape| sum_to_shape(&grad, shape))
+            .collect()
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        self.input_shapes
+            .iter()
+            .map(|shape| sum_to(grad, shape))
+            .collect()
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
     }
 }
 
-impl<'a, 'b> Add<&'a Tensor> for &'b Tensor {
+impl<'a> Add<&'a Tensor> for &Tensor {
     type Output = Tensor;
 
     fn add(self, other: &'a Tensor) -> Tensor {
         // 直接使用输入形状创建op
-        let op = AddOp::new(
-            self.0.borrow().data.shape().to_vec(),
-            other.0.borrow().data.shape().to_vec(),
-        );
+        let op = AddOp::new(self.shape(), other.shape());
         op.forward(&[self, other])
     }
 }
+
+impl Add<f32> for &Tensor {
+    type Output = Tensor;
+
+    fn add(self, scalar: f32) -> Tensor {
+        let b = Tensor::new(arr0(scalar).into_dyn());
+        self + &b
+    }
+}
+
+impl<'a> Add<&'a Tensor> for f32 {
+    type Output = Tensor;
+
+    fn add(self, tensor: &'a Tensor) -> Tensor {
+        tensor + self
+    }
+}
+
+impl Add<f64> for &Tensor {
+    type Output = Tensor;
+
+    fn add(self, scalar: f64) -> Tensor {
+        self + scalar as f32
+    }
+}
+
+impl<'a> Add<&'a Tensor> for f64 {
+    type Output = Tensor;
+
+    fn add(self, tensor: &'a Tensor) -> Tensor {
+        tensor + self as f32
+    }
+}
@@ -0,0 +1,66 @@
+//! 线性运算的伴随运算，用于构建可微的反向传播。
+
+use super::{Op, linear_jvp, needs_grad, propagate_tangent};
+use crate::autograd::no_grad;
+use crate::tensor::Tensor;
+use ndarray::ArrayD;
+use std::sync::Arc;
+
+/// 线性运算 `L` 的伴随：前向计算 `Lᵀ g`，即 `L` 的反向传播；反向再用 `L` 本身。
+///
+/// 形状变换、切片、归约等线性运算的梯度只取决于运算的配置，与输入数据无关。
+/// 把它们的反向传播包装成这个运算后，得到的梯度仍在计算图中，可以继续求导。
+#[derive(Debug)]
+pub struct Adjoint<O> {
+    /// 前向中记录下来的运算，保存了反向传播需要的形状等信息
+    op: O,
+}
+
+impl<O: Op + Clone + 'static> Adjoint<O> {
+    pub fn new(op: O) -> Self {
+        Adjoint { op }
+    }
+}
+
+impl<O: Op + Clone + 'static> Op for Adjoint<O> {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "Adjoint expects exactly one input tensor"
+        );
+        let grad = inputs[0];
+        // 借一个临时节点承载梯度，复用原运算的反向传播
+        let carrier = grad.detach();
+        carrier.0.borrow_mut().grad = Some(grad.data());
+        let output = self.op.backward(&carrier).remove(0);
+
+        let result = Tensor::new(output);
+        if needs_grad(&[grad]) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(Adjoint::new(self.op.clone())));
+            result_data.add_parent(grad);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let _guard = no_grad();
+        vec![self.op.forward(&[&Tensor::new(grad)]).data()]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![self.op.forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
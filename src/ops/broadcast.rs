@@ -0,0 +1,136 @@
+//! 广播相关的辅助函数，供逐元素二元运算的前向与反向传播共用。
+
+use crate::dtype::{DType, result_type};
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, ArrayViewD, Axis, IxDyn, LinalgScalar, Zip};
+
+/// 计算两个形状广播后的形状（从尾部维度开始对齐）。
+///
+/// 如果两个形状无法广播，则 panic。
+pub fn broadcast_shape(shape1: &[usize], shape2: &[usize]) -> Vec<usize> {
+    let len1 = shape1.len();
+    let len2 = shape2.len();
+    let max_len = std::cmp::max(len1, len2);
+
+    let mut result = Vec::with_capacity(max_len);
+
+    // 从尾部开始，依次比较对齐的维度
+    for i in 0..max_len {
+        let dim1 = if i < len1 { shape1[len1 - 1 - i] } else { 1 };
+        let dim2 = if i < len2 { shape2[len2 - 1 - i] } else { 1 };
+
+        if dim1 != 1 && dim2 != 1 && dim1 != dim2 {
+            panic!(
+                "Incompatible dimensions for broadcasting: {} and {}",
+                dim1, dim2
+            );
+        }
+
+        result.push(std::cmp::max(dim1, dim2));
+    }
+
+    result.reverse();
+    result
+}
+
+/// 将两个数组广播到共同的形状，返回两个只读视图。
+pub fn broadcast_arrays<'a, A>(
+    a: &'a ArrayD<A>,
+    b: &'a ArrayD<A>,
+) -> (ArrayViewD<'a, A>, ArrayViewD<'a, A>) {
+    let shape = broadcast_shape(a.shape(), b.shape());
+    let a_broadcast = a.broadcast(IxDyn(&shape)).unwrap_or_else(|| {
+        panic!(
+            "Cannot broadcast tensor with shape {:?} to {:?}",
+            a.shape(),
+            shape
+        )
+    });
+    let b_broadcast = b.broadcast(IxDyn(&shape)).unwrap_or_else(|| {
+        panic!(
+            "Cannot broadcast tensor with shape {:?} to {:?}",
+            b.shape(),
+            shape
+        )
+    });
+    (a_broadcast, b_broadcast)
+}
+
+/// 两个张量逐元素运算的结果类型，零维张量按 `dtype::result_type` 的规则参与类型提升
+pub fn binary_result_type(a: &Tensor, b: &Tensor) -> DType {
+    result_type(a.dtype(), a.dim() == 0, b.dtype(), b.dim() == 0)
+}
+
+/// 广播后逐元素计算二元运算，结果为 `dtype` 类型。
+///
+/// 结果为 f32 时直接在 f32 下计算，否则在 f64 下计算后再转换，f64 与整数不会损失精度。
+pub fn broadcast_binary(
+    a: &Tensor,
+    b: &Tensor,
+    dtype: DType,
+    f32_op: impl Fn(f32, f32) -> f32,
+    f64_op: impl Fn(f64, f64) -> f64,
+) -> Tensor {
+    if dtype == DType::F32 {
+        let (a, b) = (a.data(), b.data());
+        let (a, b) = broadcast_arrays(&a, &b);
+        Tensor::new(Zip::from(&a).and(&b).map_collect(|&x, &y| f32_op(x, y)))
+    } else {
+        let (a, b) = (a.data_f64(), b.data_f64());
+        let (a, b) = broadcast_arrays(&a, &b);
+        Tensor::with_dtype(
+            Zip::from(&a).and(&b).map_collect(|&x, &y| f64_op(x, y)),
+            dtype,
+        )
+    }
+}
+
+/// 将梯度按目标形状求和，是广播的逆操作。
+/// 用于广播操作的反向传播。
+pub fn sum_to_shape<A: LinalgScalar>(grad: &ArrayD<A>, target_shape: &[usize]) -> ArrayD<A> {
+    if grad.shape() == target_shape {
+        return grad.clone();
+    }
+
+    let mut result = grad.clone();
+
+    // 1. 对多余的前导维度求和
+    while result.ndim() > target_shape.len() {
+        result = result.sum_axis(Axis(0));
+    }
+
+    // 2. 对被广播的维度 (size 1 -> size > 1) 求和，保留该维度
+    for (i, &size) in target_shape.iter().enumerate() {
+        if size == 1 && result.shape()[i] != 1 {
+            result = result.sum_axis(Axis(i)).insert_axis(Axis(i));
+        }
+    }
+
+    result
+}
+
+/// `sum_to_shape` 的可微版本，用于构建可微的反向传播。
+pub fn sum_to(grad: &Tensor, target_shape: &[usize]) -> Tensor {
+    let shape = grad.shape();
+    if shape == target_shape {
+        return grad.clone();
+    }
+    if grad.is_complex() {
+        // 在 view_as_real 的形状上对实部与虚部一起求和，再合成复数
+        let pairs = grad
+            .view_as_real()
+            .expect("Complex gradient can be viewed as real");
+        let pairs_shape = [target_shape, &[2]].concat();
+        return sum_to(&pairs, &pairs_shape)
+            .view_as_complex()
+            .expect("Summed gradient can be viewed as complex");
+    }
+    // 多余的前导维度与被广播的维度都要求和
+    let lead = shape.len() - target_shape.len();
+    let dims: Vec<usize> = (0..shape.len())
+        .filter(|&i| i < lead || (target_shape[i - lead] == 1 && shape[i] != 1))
+        .collect();
+    grad.sum_dims(&dims, true)
+        .reshape(target_shape)
+        .expect("Failed to sum gradient to target shape")
+}
@@ -0,0 +1,76 @@
+use crate::dtype::DType;
+use crate::ops::{Op, linear_jvp, needs_grad, propagate_tangent};
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Axis};
+use std::sync::Arc;
+
+/// 转换元素类型。浮点与复数类型之间的转换梯度原样传回（实数转换为复数时取实部），
+/// 转换为其他类型时不记录计算图。
+#[derive(Debug)]
+pub struct ToDtype {
+    dtype: DType,
+    input_complex: bool,
+}
+
+impl ToDtype {
+    pub fn new(dtype: DType) -> Self {
+        ToDtype {
+            dtype,
+            input_complex: false,
+        }
+    }
+}
+
+impl Op for ToDtype {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "ToDtype expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let input_complex = input.is_complex();
+        let result = match (input_complex, self.dtype.is_complex()) {
+            (_, true) => Tensor::from_complex(input.data_complex(), self.dtype),
+            (false, false) => Tensor::with_dtype(input.data_f64(), self.dtype),
+            (true, false) => panic!(
+                "Casting complex values to {} discards the imaginary part, use real() or abs() instead",
+                self.dtype
+            ),
+        };
+        if !self.dtype.is_floating_point() && !self.dtype.is_complex() {
+            return result;
+        }
+        if needs_grad(&[input]) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(ToDtype {
+                dtype: self.dtype,
+                input_complex,
+            }));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = parent.0.borrow().grad.clone().expect("Gradient not found");
+        if self.dtype.is_complex() && !self.input_complex {
+            // 复数梯度按 view_as_real 保存，实数输入只取实部
+            let last = Axis(grad.ndim() - 1);
+            return vec![grad.index_axis(last, 0).to_owned()];
+        }
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        if self.dtype.is_complex() && !self.input_complex {
+            return vec![grad.real()];
+        }
+        vec![grad.clone()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
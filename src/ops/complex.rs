@@ -0,0 +1,598 @@
+//! 复数运算：由实部与虚部构造、view_as_real/view_as_complex、real/imag、conj、abs、angle、
+//! 四则运算与矩阵乘法。
+//!
+//! 与 PyTorch 一致，复数张量的梯度采用共轭 Wirtinger 约定，按 `view_as_real` 的形状保存
+//! `G = ∂L/∂x + i·∂L/∂y`，因此 `z -= lr · G` 就是对实部与虚部的梯度下降。
+//! 对全纯函数 `w = f(z)`，输入的梯度为 `G_z = G_w · conj(f'(z))`；实数输入取其实部。
+//! 这些运算与形状变换都支持 `create_graph` 与前向模式求导；
+//! 其他运算（归约、逐元素函数等）不支持复数张量，调用时会 panic。
+
+use super::broadcast::{binary_result_type, broadcast_arrays, sum_to, sum_to_shape};
+use super::matmul::{MatMul, matmul_arrays, matmul_grads};
+use super::{Op, linear_jvp, multilinear_jvp, needs_grad, propagate_tangent, sum_tangents};
+use crate::dtype::{DType, complex_to_pairs, pairs_to_complex, promote_types};
+use crate::storage::SavedTensor;
+use crate::tensor::{Tensor, TensorCell, TensorData};
+use ndarray::{ArrayD, Zip, arr0};
+use num_complex::Complex64;
+use std::sync::Arc;
+
+/// 读出输出张量上的梯度，复数输出的梯度由实部、虚部对合成
+fn output_grad(parent: &Tensor) -> ArrayD<Complex64> {
+    let data = parent.0.borrow();
+    let grad = data
+        .grad
+        .as_ref()
+        .expect("Gradient not found in backward pass");
+    if data.is_complex() {
+        pairs_to_complex(&grad.mapv(f64::from))
+    } else {
+        grad.mapv(|g| Complex64::new(g as f64, 0.0))
+    }
+}
+
+/// 把复数梯度对广播的维度求和，再转换为输入的梯度：
+/// 复数输入按 `view_as_real` 拆成实部、虚部对，实数输入取实部
+fn input_grad(grad: &ArrayD<Complex64>, shape: &[usize], complex: bool) -> ArrayD<f32> {
+    let grad = sum_to_shape(grad, shape);
+    if complex {
+        complex_to_pairs(&grad).mapv(|x| x as f32)
+    } else {
+        grad.mapv(|z| z.re as f32)
+    }
+}
+
+/// `input_grad` 的可微版本：复数梯度对广播的维度求和，实数输入取实部
+fn input_grad_graph(grad: &Tensor, input: &Tensor) -> Tensor {
+    let grad = sum_to(grad, &input.shape());
+    if input.is_complex() {
+        grad
+    } else {
+        grad.real()
+    }
+}
+
+/// 模为 0 的位置为 1、其余为 0 的常量，加到分母上避免除以 0，这些位置的梯度取 0
+fn zero_mask(norm: &Tensor) -> Tensor {
+    Tensor::new(norm.data().mapv(|r| if r == 0.0 { 1.0 } else { 0.0 }))
+}
+
+/// 虚部，实数张量的虚部为 0
+fn imag_or_zero(tensor: &Tensor) -> Tensor {
+    tensor.imag().unwrap_or_else(|_| tensor.zeros_like())
+}
+
+/// 任一输入需要梯度时记录反向传播所需的运算，并传播前向模式的切线
+fn track<O: Op + 'static>(
+    op: &impl Op,
+    inputs: &[&Tensor],
+    result: Tensor,
+    make_op: impl FnOnce() -> O,
+) -> Tensor {
+    if needs_grad(inputs) {
+        let mut result_data = result.0.borrow_mut();
+        result_data.set_creator(Arc::new(make_op()));
+        for &input in inputs {
+            result_data.add_parent(input);
+        }
+        result_data.requires_grad = true;
+    }
+    propagate_tangent(op, inputs, &result);
+    result
+}
+
+/// 结果为实数的运算（abs、angle）的类型：复数取实部的类型，整数与布尔为 f32
+fn real_result_dtype(dtype: DType) -> DType {
+    match dtype {
+        dtype if dtype.is_complex() => dtype.to_real(),
+        dtype if dtype.is_floating_point() => dtype,
+        _ => DType::F32,
+    }
+}
+
+/// 由实部与虚部构造复数，`torch.complex`
+#[derive(Debug, Default)]
+pub struct ComplexFromParts;
+
+impl Op for ComplexFromParts {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 2,
+            "ComplexFromParts expects exactly two input tensors"
+        );
+        let (real, imag) = (inputs[0].data_f64(), inputs[1].data_f64());
+        assert_eq!(
+            real.shape(),
+            imag.shape(),
+            "Real and imaginary parts must have the same shape"
+        );
+        let data = Zip::from(&real)
+            .and(&imag)
+            .map_collect(|&re, &im| Complex64::new(re, im));
+        let dtype = promote_types(inputs[0].dtype(), inputs[1].dtype()).to_complex();
+        let result = Tensor::from_complex(data, dtype);
+        track(self, inputs, result, || ComplexFromParts)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // z = x + iy，∂L/∂x 与 ∂L/∂y 分别是 G 的实部与虚部
+        let grad = output_grad(parent);
+        vec![grad.mapv(|g| g.re as f32), grad.mapv(|g| g.im as f32)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad.real(), imag_or_zero(grad)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 把复数张量视为末尾多一维（实部、虚部）的实数张量，与输入共享存储
+#[derive(Debug, Default)]
+pub struct ViewAsReal;
+
+impl Op for ViewAsReal {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "ViewAsReal expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let result = {
+            let data = input.0.borrow();
+            assert!(data.is_complex(), "ViewAsReal expects a complex tensor");
+            let view =
+                TensorData::from_storage(Arc::clone(data.storage()), data.layout().view_as_real());
+            Tensor(Arc::new(TensorCell::new(view)))
+        };
+        track(self, inputs, result, || ViewAsReal)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // 复数张量的梯度本来就按 view_as_real 的形状保存
+        let grad = parent.0.borrow().grad.clone().expect("Gradient not found");
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let grad = grad
+            .contiguous()
+            .view_as_complex()
+            .expect("Gradient of view_as_real can be viewed as complex");
+        vec![grad]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// `ViewAsReal` 的逆操作：把末尾一维长度为 2 的实数张量视为复数张量，与输入共享存储
+#[derive(Debug, Default)]
+pub struct ViewAsComplex;
+
+impl Op for ViewAsComplex {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "ViewAsComplex expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let result = {
+            let data = input.0.borrow();
+            assert!(!data.is_complex(), "ViewAsComplex expects a real tensor");
+            let layout = data
+                .layout()
+                .view_as_complex()
+                .expect("ViewAsComplex expects a last dimension of size 2 with stride 1");
+            let view = TensorData::from_complex_storage(Arc::clone(data.storage()), layout);
+            Tensor(Arc::new(TensorCell::new(view)))
+        };
+        track(self, inputs, result, || ViewAsComplex)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = parent.0.borrow().grad.clone().expect("Gradient not found");
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let grad = grad
+            .view_as_real()
+            .expect("Gradient of view_as_complex is complex");
+        vec![grad]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 共轭 `conj(z)`，它不是全纯函数，梯度为 `conj(G)`
+#[derive(Debug, Default)]
+pub struct Conj {
+    input_shape: Vec<usize>,
+}
+
+impl Op for Conj {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Conj expects exactly one input tensor");
+        let input = inputs[0];
+        assert!(input.is_complex(), "Conj expects a complex tensor");
+        let result = Tensor::from_complex(input.data_complex().mapv(|z| z.conj()), input.dtype());
+        track(self, inputs, result, || Conj {
+            input_shape: input.shape(),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = output_grad(parent).mapv(|g| g.conj());
+        vec![input_grad(&grad, &self.input_shape, true)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad.conj()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 复数的模 `|z|`，梯度为 `G · z / |z|`，在 0 处取 0
+#[derive(Debug, Default)]
+pub struct ComplexAbs {
+    input: Option<SavedTensor>,
+}
+
+impl Op for ComplexAbs {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "ComplexAbs expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let output = input.data_complex().mapv(|z| z.norm());
+        let result = Tensor::with_dtype(output, real_result_dtype(input.dtype()));
+        track(self, inputs, result, || ComplexAbs {
+            input: Some(SavedTensor::new(input)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let input = self.input.as_ref().expect("input is None in backward");
+        let z = input.unpack_complex();
+        let grad = Zip::from(&output_grad(parent))
+            .and(&z)
+            .map_collect(|&g, &z| {
+                let r = z.norm();
+                if r == 0.0 {
+                    Complex64::new(0.0, 0.0)
+                } else {
+                    z * (g.re / r)
+                }
+            });
+        vec![input_grad(&grad, input.shape(), input.is_complex())]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let z = &inputs[0];
+        let norm = output + &zero_mask(output);
+        vec![input_grad_graph(&(&(z * grad) / &norm), z)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // d|z| = Re(conj(z)·dz) / |z|
+        let z = inputs[0];
+        let tangent = tangents[0].as_ref().expect("Forward AD requires a tangent");
+        let norm = output + &zero_mask(output);
+        &(&z.conj() * tangent).real() / &norm
+    }
+}
+
+/// 复数的辐角 `atan2(y, x)`，梯度为 `G · i·z / |z|²`，在 0 处取 0
+#[derive(Debug, Default)]
+pub struct Angle {
+    input: Option<SavedTensor>,
+}
+
+impl Op for Angle {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Angle expects exactly one input tensor");
+        let input = inputs[0];
+        let output = input.data_complex().mapv(|z| z.arg());
+        let result = Tensor::with_dtype(output, real_result_dtype(input.dtype()));
+        track(self, inputs, result, || Angle {
+            input: Some(SavedTensor::new(input)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let input = self.input.as_ref().expect("input is None in backward");
+        let z = input.unpack_complex();
+        let grad = Zip::from(&output_grad(parent))
+            .and(&z)
+            .map_collect(|&g, &z| {
+                let r2 = z.norm_sqr();
+                if r2 == 0.0 {
+                    Complex64::new(0.0, 0.0)
+                } else {
+                    Complex64::i() * z * (g.re / r2)
+                }
+            });
+        vec![input_grad(&grad, input.shape(), input.is_complex())]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let z = &inputs[0];
+        let i = Tensor::from_complex(arr0(Complex64::i()).into_dyn(), DType::Complex64);
+        let norm_sqr = (z * &z.conj()).real();
+        let norm_sqr = &norm_sqr + &zero_mask(&norm_sqr);
+        vec![input_grad_graph(&(&(&(grad * &i) * z) / &norm_sqr), z)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // dθ = Im(conj(z)·dz) / |z|²
+        let z = inputs[0];
+        let tangent = tangents[0].as_ref().expect("Forward AD requires a tangent");
+        let norm_sqr = (z * &z.conj()).real();
+        let norm_sqr = &norm_sqr + &zero_mask(&norm_sqr);
+        &imag_or_zero(&(&z.conj() * tangent)) / &norm_sqr
+    }
+}
+
+/// 复数的逐元素二元运算
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryKind {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// 至少有一个操作数为复数的逐元素四则运算，支持广播
+#[derive(Debug)]
+pub struct ComplexBinary {
+    kind: BinaryKind,
+    input_shapes: Vec<Vec<usize>>,
+    input_complex: Vec<bool>,
+    a_data: Option<SavedTensor>,
+    b_data: Option<SavedTensor>,
+}
+
+impl ComplexBinary {
+    pub fn new(kind: BinaryKind) -> Self {
+        ComplexBinary {
+            kind,
+            input_shapes: Vec::new(),
+            input_complex: Vec::new(),
+            a_data: None,
+            b_data: None,
+        }
+    }
+}
+
+impl Op for ComplexBinary {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 2,
+            "ComplexBinary expects exactly two input tensors"
+        );
+        let dtype = binary_result_type(inputs[0], inputs[1]).to_complex();
+        let (a, b) = (inputs[0].data_complex(), inputs[1].data_complex());
+        let (a_broadcast, b_broadcast) = broadcast_arrays(&a, &b);
+        let output = match self.kind {
+            BinaryKind::Add => &a_broadcast + &b_broadcast,
+            BinaryKind::Sub => &a_broadcast - &b_broadcast,
+            BinaryKind::Mul => &a_broadcast * &b_broadcast,
+            BinaryKind::Div => &a_broadcast / &b_broadcast,
+        };
+        let result = Tensor::from_complex(output, dtype);
+
+        // 加减法的梯度与操作数的值无关，不需要保存
+        let saves_inputs = matches!(self.kind, BinaryKind::Mul | BinaryKind::Div);
+        track(self, inputs, result, || ComplexBinary {
+            kind: self.kind,
+            input_shapes: inputs.iter().map(|input| input.shape()).collect(),
+            input_complex: inputs.iter().map(|input| input.is_complex()).collect(),
+            a_data: saves_inputs.then(|| SavedTensor::new(inputs[0])),
+            b_data: saves_inputs.then(|| SavedTensor::new(inputs[1])),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = output_grad(parent);
+        let saved = |data: &Option<SavedTensor>| {
+            data.as_ref()
+                .expect("saved data is None in backward")
+                .unpack_complex()
+        };
+        let (grad_a, grad_b) = match self.kind {
+            BinaryKind::Add => (grad.clone(), grad),
+            BinaryKind::Sub => (grad.clone(), grad.mapv(|g| -g)),
+            // ∂(ab)/∂a = b，∂(ab)/∂b = a
+            BinaryKind::Mul => {
+                let (a, b) = (saved(&self.a_data), saved(&self.b_data));
+                (&grad * &b.mapv(|b| b.conj()), &grad * &a.mapv(|a| a.conj()))
+            }
+            // ∂(a/b)/∂a = 1/b，∂(a/b)/∂b = -a/b²
+            BinaryKind::Div => {
+                let (a, b) = (saved(&self.a_data), saved(&self.b_data));
+                let (a_broadcast, b_broadcast) = broadcast_arrays(&a, &b);
+                let grad_a = &grad / &b.mapv(|b| b.conj());
+                let grad_b = Zip::from(&grad)
+                    .and(&a_broadcast)
+                    .and(&b_broadcast)
+                    .map_collect(|&g, &a, &b| -g * (a / (b * b)).conj());
+                (grad_a, grad_b)
+            }
+        };
+        vec![
+            input_grad(&grad_a, &self.input_shapes[0], self.input_complex[0]),
+            input_grad(&grad_b, &self.input_shapes[1], self.input_complex[1]),
+        ]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let (a, b) = (&inputs[0], &inputs[1]);
+        let (grad_a, grad_b) = match self.kind {
+            BinaryKind::Add => (grad.clone(), grad.clone()),
+            BinaryKind::Sub => (grad.clone(), grad * -1.0),
+            BinaryKind::Mul => (grad * &b.conj(), grad * &a.conj()),
+            // a / b² = output / b
+            BinaryKind::Div => (grad / &b.conj(), &(grad * &(output / b).conj()) * -1.0),
+        };
+        vec![input_grad_graph(&grad_a, a), input_grad_graph(&grad_b, b)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        match self.kind {
+            BinaryKind::Add | BinaryKind::Sub => linear_jvp(self, inputs, tangents),
+            BinaryKind::Mul => multilinear_jvp(self, inputs, tangents),
+            // d(a/b) = da / b - (a/b)·db / b
+            BinaryKind::Div => {
+                let b = inputs[1];
+                sum_tangents([
+                    tangents[0].as_ref().map(|da| da / b),
+                    tangents[1].as_ref().map(|db| &(&(output * db) / b) * -1.0),
+                ])
+            }
+        }
+    }
+}
+
+/// 至少有一个操作数为复数的矩阵乘法，语义与 `MatMul` 相同，
+/// 梯度为 `G_a = G @ bᴴ`，`G_b = aᴴ @ G`
+#[derive(Debug)]
+pub struct ComplexMatMul {
+    transpose_a: bool,
+    transpose_b: bool,
+    a_data: Option<SavedTensor>,
+    b_data: Option<SavedTensor>,
+}
+
+impl ComplexMatMul {
+    pub fn with_transpose(transpose_a: bool, transpose_b: bool) -> Self {
+        ComplexMatMul {
+            transpose_a,
+            transpose_b,
+            a_data: None,
+            b_data: None,
+        }
+    }
+}
+
+impl Op for ComplexMatMul {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 2,
+            "ComplexMatMul expects exactly two input tensors"
+        );
+        let (a, b) = (inputs[0].data_complex(), inputs[1].data_complex());
+        let output = matmul_arrays(&a, &b, self.transpose_a, self.transpose_b);
+        let dtype = promote_types(inputs[0].dtype(), inputs[1].dtype()).to_complex();
+        let result = Tensor::from_complex(output, dtype);
+        track(self, inputs, result, || ComplexMatMul {
+            a_data: Some(SavedTensor::new(inputs[0])),
+            b_data: Some(SavedTensor::new(inputs[1])),
+            ..ComplexMatMul::with_transpose(self.transpose_a, self.transpose_b)
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let a_data = self.a_data.as_ref().expect("a_data not saved in MatMul");
+        let b_data = self.b_data.as_ref().expect("b_data not saved in MatMul");
+        // 与实数的梯度公式相同，只是操作数取共轭
+        let a = a_data.unpack_complex().mapv(|a| a.conj());
+        let b = b_data.unpack_complex().mapv(|b| b.conj());
+        let (grad_a, grad_b) = matmul_grads(
+            output_grad(parent),
+            &a,
+            &b,
+            self.transpose_a,
+            self.transpose_b,
+        );
+        vec![
+            input_grad(&grad_a, a_data.shape(), a_data.is_complex()),
+            input_grad(&grad_b, b_data.shape(), b_data.is_complex()),
+        ]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let conj: Vec<Tensor> = inputs.iter().map(Tensor::conj).collect();
+        MatMul::with_transpose(self.transpose_a, self.transpose_b)
+            .backward_graph(&conj, output, grad)
+            .iter()
+            .zip(inputs)
+            .map(|(grad, input)| input_grad_graph(grad, input))
+            .collect()
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        multilinear_jvp(self, inputs, tangents)
+    }
+}
+
+impl Tensor {
+    /// 由形状相同的实部与虚部构造复数张量，f64 得到 complex128，其余得到 complex64
+    pub fn complex(real: &Tensor, imag: &Tensor) -> Result<Tensor, &'static str> {
+        if real.is_complex() || imag.is_complex() {
+            return Err("实部与虚部必须是实数张量");
+        }
+        if real.shape() != imag.shape() {
+            return Err("实部与虚部的形状必须相同");
+        }
+        Ok(ComplexFromParts.forward(&[real, imag]))
+    }
+
+    /// 把复数张量视为末尾多一维（实部、虚部）的实数张量，与原张量共享存储
+    pub fn view_as_real(&self) -> Result<Tensor, &'static str> {
+        if !self.is_complex() {
+            return Err("只有复数张量可以调用view_as_real");
+        }
+        Ok(ViewAsReal.forward(&[self]))
+    }
+
+    /// 把末尾一维长度为 2 的 f32 或 f64 张量视为复数张量，与原张量共享存储
+    pub fn view_as_complex(&self) -> Result<Tensor, &'static str> {
+        if !matches!(self.dtype(), DType::F32 | DType::F64) {
+            return Err("只有f32与f64张量可以视为复数");
+        }
+        if self.0.borrow().layout().view_as_complex().is_none() {
+            return Err("最后一维的长度必须为2且步长为1");
+        }
+        Ok(ViewAsComplex.forward(&[self]))
+    }
+
+    /// 实部，复数张量返回共享存储的视图，实数张量返回自身
+    pub fn real(&self) -> Tensor {
+        if !self.is_complex() {
+            return self.clone();
+        }
+        self.view_as_real()
+            .and_then(|pairs| pairs.select(self.dim(), 0))
+            .expect("Complex tensor has a real part")
+    }
+
+    /// 虚部，返回共享存储的视图
+    pub fn imag(&self) -> Result<Tensor, &'static str> {
+        self.view_as_real()
+            .map_err(|_| "只有复数张量有虚部")?
+            .select(self.dim(), 1)
+    }
+
+    /// 共轭，实数张量返回自身
+    pub fn conj(&self) -> Tensor {
+        if !self.is_complex() {
+            return self.clone();
+        }
+        Conj::default().forward(&[self])
+    }
+
+    /// 辐角，实数张量的负数为 π，其余为 0
+    pub fn angle(&self) -> Tensor {
+        Angle::default().forward(&[self])
+    }
+}
@@ -0,0 +1,500 @@
+//! 分类损失：负对数似然（NLL）与交叉熵。
+//!
+//! 两者都直接给出融合后的梯度，而不是由 log_softmax、gather 等基础运算组合求导。
+//! 输入形状为 `[C]`、`[N, C]` 或 `[N, C, d1, ...]`，类别维为第 1 维（一维输入时为第 0 维）；
+//! 目标张量保存类别下标（int64，或取整数值的浮点张量），形状为输入去掉类别维。
+
+use super::{Op, expect_f32, needs_grad, propagate_tangent};
+use crate::functional::Reduction;
+use crate::tensor::Tensor;
+use ndarray::{Array1, Array2, ArrayD, Axis, IxDyn, arr0};
+use std::sync::Arc;
+
+/// 类别维的位置
+fn class_dim(ndim: usize) -> usize {
+    if ndim == 1 { 0 } else { 1 }
+}
+
+/// 把类别维移到最后并展平为 `[M, C]`，每行对应一个样本（位置）
+fn to_class_rows(data: &ArrayD<f32>) -> Array2<f32> {
+    let ndim = data.ndim();
+    let classes = data.shape()[class_dim(ndim)];
+    let mut moved = data.view();
+    if ndim > 1 {
+        let mut order: Vec<usize> = (0..ndim).filter(|&d| d != 1).collect();
+        order.push(1);
+        moved = moved.permuted_axes(IxDyn(&order));
+    }
+    let values: Vec<f32> = moved.iter().cloned().collect();
+    let rows = values.len().checked_div(classes).unwrap_or(0);
+    Array2::from_shape_vec((rows, classes), values).expect("Failed to reshape into class rows")
+}
+
+/// `to_class_rows` 的逆操作，恢复为原输入形状
+fn from_class_rows(rows: Array2<f32>, shape: &[usize]) -> ArrayD<f32> {
+    let ndim = shape.len();
+    if ndim == 1 {
+        return rows.into_shape_with_order(IxDyn(shape)).unwrap();
+    }
+    let mut moved_shape: Vec<usize> = (0..ndim).filter(|&d| d != 1).map(|d| shape[d]).collect();
+    moved_shape.push(shape[1]);
+    let moved = rows.into_shape_with_order(IxDyn(&moved_shape)).unwrap();
+    // 把最后一维移回第 1 维
+    let mut order: Vec<usize> = vec![0, ndim - 1];
+    order.extend(1..ndim - 1);
+    moved
+        .permuted_axes(IxDyn(&order))
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// 解析后的目标：每个样本的类别（被忽略时为 None）及其权重
+#[derive(Debug)]
+struct ClassTargets {
+    /// 目标张量的形状，用于 `Reduction::None`
+    shape: Vec<usize>,
+    classes: Vec<Option<usize>>,
+    /// 每个样本的权重 `weight[y_i]`，被忽略的样本为 0
+    sample_weights: Vec<f32>,
+    /// 各类别的权重
+    class_weights: Array1<f32>,
+}
+
+impl ClassTargets {
+    fn new(
+        input_shape: &[usize],
+        target: &Tensor,
+        weight: Option<&Tensor>,
+        ignore_index: Option<usize>,
+    ) -> Self {
+        let ndim = input_shape.len();
+        if ndim == 0 {
+            panic!("Classification loss expects input with at least 1 dimension");
+        }
+        let num_classes = input_shape[class_dim(ndim)];
+        let expected_shape: Vec<usize> = input_shape
+            .iter()
+            .enumerate()
+            .filter(|&(d, _)| d != class_dim(ndim))
+            .map(|(_, &s)| s)
+            .collect();
+        // 在 f64 下读取，int64 下标超过 2^24 时也不会被舍入
+        let target_data = target.data_f64();
+        if target_data.shape() != expected_shape.as_slice() {
+            panic!(
+                "Target shape {:?} does not match input shape {:?}",
+                target_data.shape(),
+                input_shape
+            );
+        }
+
+        let class_weights = match weight {
+            Some(w) => {
+                expect_f32(&[w], "Classification loss weight");
+                let w = w.data();
+                if w.shape() != [num_classes] {
+                    panic!(
+                        "Weight should have shape [{}], got {:?}",
+                        num_classes,
+                        w.shape()
+                    );
+                }
+                Array1::from_iter(w.iter().cloned())
+            }
+            None => Array1::ones(num_classes),
+        };
+
+        let classes: Vec<Option<usize>> = target_data
+            .iter()
+            .map(|&t| {
+                if t < 0.0 || t.fract() != 0.0 {
+                    panic!(
+                        "Target class index must be a non-negative integer, got {}",
+                        t
+                    );
+                }
+                let class = t as usize;
+                if Some(class) == ignore_index {
+                    return None;
+                }
+                if class >= num_classes {
+                    panic!(
+                        "Target class {} is out of bounds for {} classes",
+                        class, num_classes
+                    );
+                }
+                Some(class)
+            })
+            .collect();
+        let sample_weights = classes
+            .iter()
+            .map(|c| c.map_or(0.0, |c| class_weights[c]))
+            .collect();
+
+        ClassTargets {
+            shape: expected_shape,
+            classes,
+            sample_weights,
+            class_weights,
+        }
+    }
+
+    /// `Reduction::Mean` 的分母：未被忽略样本的权重之和
+    fn weight_sum(&self) -> f32 {
+        self.sample_weights.iter().sum()
+    }
+
+    /// 按归约方式汇总每个样本的损失
+    fn reduce(&self, losses: Vec<f32>, reduction: Reduction) -> ArrayD<f32> {
+        match reduction {
+            Reduction::None => ArrayD::from_shape_vec(IxDyn(&self.shape), losses).unwrap(),
+            Reduction::Sum => arr0(losses.iter().sum()).into_dyn(),
+            Reduction::Mean => arr0(losses.iter().sum::<f32>() / self.weight_sum()).into_dyn(),
+        }
+    }
+
+    /// 每个样本从上游得到的梯度系数
+    fn upstream(&self, grad_output: &ArrayD<f32>, reduction: Reduction) -> Vec<f32> {
+        let n = self.classes.len();
+        match reduction {
+            Reduction::None => grad_output.iter().cloned().collect(),
+            Reduction::Sum => vec![grad_output.iter().sum(); n],
+            Reduction::Mean => vec![grad_output.iter().sum::<f32>() / self.weight_sum(); n],
+        }
+    }
+
+    /// `upstream` 的可微版本，结果可以广播到输入形状
+    fn upstream_graph(&self, grad: &Tensor, input_shape: &[usize], reduction: Reduction) -> Tensor {
+        match reduction {
+            Reduction::None => {
+                let mut shape = input_shape.to_vec();
+                shape[class_dim(input_shape.len())] = 1;
+                grad.reshape(&shape).expect("Failed to reshape gradient")
+            }
+            Reduction::Sum => grad.clone(),
+            Reduction::Mean => grad * (1.0 / self.weight_sum()),
+        }
+    }
+}
+
+/// 按行排列的逐样本梯度（上游梯度为 1）乘上上游梯度系数
+fn scale_rows(mut coefficients: Array2<f32>, upstream: &[f32]) -> Array2<f32> {
+    for (mut row, &u) in coefficients.outer_iter_mut().zip(upstream) {
+        row *= u;
+    }
+    coefficients
+}
+
+/// 损失的切线：逐样本梯度与输入切线逐行做内积，再按归约方式汇总
+fn loss_tangent(
+    coefficients: &Array2<f32>,
+    targets: &ClassTargets,
+    tangents: &[Option<Tensor>],
+    reduction: Reduction,
+) -> Tensor {
+    let tangent = tangents[0]
+        .as_ref()
+        .expect("Forward AD requires at least one tangent")
+        .data();
+    let losses = (coefficients * &to_class_rows(&tangent))
+        .sum_axis(Axis(1))
+        .to_vec();
+    Tensor::new(targets.reduce(losses, reduction))
+}
+
+/// 负对数似然损失，输入为对数概率
+#[derive(Debug)]
+pub struct NllLoss {
+    reduction: Reduction,
+    input_shape: Vec<usize>,
+    targets: Option<ClassTargets>,
+}
+
+impl NllLoss {
+    pub fn new(reduction: Reduction) -> Self {
+        NllLoss {
+            reduction,
+            input_shape: Vec::new(),
+            targets: None,
+        }
+    }
+
+    fn forward_with(
+        &self,
+        input: &Tensor,
+        target: &Tensor,
+        weight: Option<&Tensor>,
+        ignore_index: Option<usize>,
+    ) -> Tensor {
+        expect_f32(&[input], "NllLoss");
+        let input_shape = input.shape();
+        let targets = ClassTargets::new(&input_shape, target, weight, ignore_index);
+        let rows = to_class_rows(&input.data());
+
+        let losses: Vec<f32> = targets
+            .classes
+            .iter()
+            .zip(&targets.sample_weights)
+            .enumerate()
+            .map(|(i, (c, &w))| c.map_or(0.0, |c| -w * rows[[i, c]]))
+            .collect();
+        let result = Tensor::new(targets.reduce(losses, self.reduction));
+
+        let op = NllLoss {
+            reduction: self.reduction,
+            input_shape,
+            targets: Some(targets),
+        };
+        propagate_tangent(&op, &[input], &result);
+        if needs_grad(&[input]) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        result
+    }
+
+    /// 每个样本的损失对输入的梯度，按行排列：`∂l_i/∂x_ij = -w_i`，仅在 j = y_i 处非零
+    fn coefficients(&self) -> Array2<f32> {
+        let targets = self.targets.as_ref().expect("targets is None in backward");
+        let num_classes = self.input_shape[class_dim(self.input_shape.len())];
+        let mut coefficients = Array2::zeros((targets.classes.len(), num_classes));
+        for (i, c) in targets.classes.iter().enumerate() {
+            if let Some(c) = c {
+                coefficients[[i, *c]] = -targets.sample_weights[i];
+            }
+        }
+        coefficients
+    }
+}
+
+impl Op for NllLoss {
+    /// 输入为 `[input, target]`，不使用类别权重，也不忽略任何类别
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 2,
+            "NllLoss expects input and target tensors"
+        );
+        self.forward_with(inputs[0], inputs[1], None, None)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let targets = self.targets.as_ref().expect("targets is None in backward");
+        let upstream = targets.upstream(&grad_output, self.reduction);
+        let grad = scale_rows(self.coefficients(), &upstream);
+        vec![from_class_rows(grad, &self.input_shape)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let targets = self.targets.as_ref().expect("targets is None in backward");
+        let coefficients = Tensor::new(from_class_rows(self.coefficients(), &self.input_shape));
+        let upstream = targets.upstream_graph(grad, &self.input_shape, self.reduction);
+        vec![&coefficients * &upstream]
+    }
+
+    fn jvp(&self, _inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        let targets = self
+            .targets
+            .as_ref()
+            .expect("targets is None in forward AD");
+        loss_tangent(&self.coefficients(), targets, tangents, self.reduction)
+    }
+}
+
+/// 交叉熵损失，输入为未归一化的 logits，内部融合了 log-softmax
+#[derive(Debug)]
+pub struct CrossEntropy {
+    reduction: Reduction,
+    label_smoothing: f32,
+    input_shape: Vec<usize>,
+    targets: Option<ClassTargets>,
+    /// 按行排列的 log-softmax 结果，形状为 `[M, C]`
+    log_probs: Option<Array2<f32>>,
+}
+
+impl CrossEntropy {
+    pub fn new(reduction: Reduction, label_smoothing: f32) -> Self {
+        if !(0.0..=1.0).contains(&label_smoothing) {
+            panic!(
+                "label_smoothing must be between 0.0 and 1.0, got {}",
+                label_smoothing
+            );
+        }
+        CrossEntropy {
+            reduction,
+            label_smoothing,
+            input_shape: Vec::new(),
+            targets: None,
+            log_probs: None,
+        }
+    }
+
+    fn forward_with(
+        &self,
+        input: &Tensor,
+        target: &Tensor,
+        weight: Option<&Tensor>,
+        ignore_index: Option<usize>,
+    ) -> Tensor {
+        expect_f32(&[input], "CrossEntropy");
+        let input_shape = input.shape();
+        let targets = ClassTargets::new(&input_shape, target, weight, ignore_index);
+        let rows = to_class_rows(&input.data());
+        let log_probs = super::softmax::log_softmax_array(&rows.into_dyn(), 1)
+            .into_dimensionality()
+            .unwrap();
+
+        // l_i = (1 - ε) * w_{y_i} * (-log p_{i,y_i}) + ε / C * Σ_c w_c * (-log p_ic)
+        let eps = self.label_smoothing;
+        let num_classes = targets.class_weights.len() as f32;
+        let losses: Vec<f32> = log_probs
+            .outer_iter()
+            .zip(targets.classes.iter().zip(&targets.sample_weights))
+            .map(|(row, (c, &w))| match c {
+                Some(c) => {
+                    let nll = -w * row[*c];
+                    let smooth = -(&row * &targets.class_weights).sum();
+                    (1.0 - eps) * nll + eps / num_classes * smooth
+                }
+                None => 0.0,
+            })
+            .collect();
+        let result = Tensor::new(targets.reduce(losses, self.reduction));
+
+        let op = CrossEntropy {
+            reduction: self.reduction,
+            label_smoothing: eps,
+            input_shape,
+            targets: Some(targets),
+            log_probs: Some(log_probs),
+        };
+        propagate_tangent(&op, &[input], &result);
+        if needs_grad(&[input]) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        result
+    }
+
+    /// 每个样本的损失对 logits 的梯度，按行排列：
+    /// `∂l_i/∂z_ij = (1 - ε) * w_{y_i} * (p_ij - δ_{j,y_i}) + ε / C * (W * p_ij - w_j)`，其中 W = Σ_c w_c
+    fn coefficients(&self) -> Array2<f32> {
+        let targets = self.targets.as_ref().expect("targets is None in backward");
+        let log_probs = self
+            .log_probs
+            .as_ref()
+            .expect("log_probs is None in backward");
+        let eps = self.label_smoothing;
+        let weights = &targets.class_weights;
+        let num_classes = weights.len() as f32;
+        let total_weight = weights.sum();
+        let mut grad = log_probs.mapv(f32::exp);
+        for (i, mut row) in grad.axis_iter_mut(Axis(0)).enumerate() {
+            let Some(c) = targets.classes[i] else {
+                row.fill(0.0);
+                continue;
+            };
+            let w = targets.sample_weights[i];
+            for (j, p) in row.iter_mut().enumerate() {
+                let delta = if j == c { 1.0 } else { 0.0 };
+                let hard = (1.0 - eps) * w * (*p - delta);
+                let smooth = eps / num_classes * (total_weight * *p - weights[j]);
+                *p = hard + smooth;
+            }
+        }
+        grad
+    }
+}
+
+impl Op for CrossEntropy {
+    /// 输入为 `[input, target]`，不使用类别权重，也不忽略任何类别
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 2,
+            "CrossEntropy expects input and target tensors"
+        );
+        self.forward_with(inputs[0], inputs[1], None, None)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let targets = self.targets.as_ref().expect("targets is None in backward");
+        let upstream = targets.upstream(&grad_output, self.reduction);
+        let grad = scale_rows(self.coefficients(), &upstream);
+        vec![from_class_rows(grad, &self.input_shape)]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        // 把融合梯度拆成 p_ij * scale_i - offset_ij，其中 p 由输入重新计算以保留计算图
+        let targets = self.targets.as_ref().expect("targets is None in backward");
+        let eps = self.label_smoothing;
+        let weights = &targets.class_weights;
+        let num_classes = weights.len();
+        let smooth = eps / num_classes as f32;
+        let total_weight = weights.sum();
+        let rows = targets.classes.len();
+        let mut scale = Array2::zeros((rows, num_classes));
+        let mut offset = Array2::zeros((rows, num_classes));
+        for (i, c) in targets.classes.iter().enumerate() {
+            let Some(c) = c else { continue };
+            let w = targets.sample_weights[i];
+            scale
+                .row_mut(i)
+                .fill((1.0 - eps) * w + smooth * total_weight);
+            for j in 0..num_classes {
+                let delta = if j == *c { 1.0 } else { 0.0 };
+                offset[[i, j]] = (1.0 - eps) * w * delta + smooth * weights[j];
+            }
+        }
+        let scale = Tensor::new(from_class_rows(scale, &self.input_shape));
+        let offset = Tensor::new(from_class_rows(offset, &self.input_shape));
+        let probs = inputs[0].softmax(class_dim(self.input_shape.len()));
+        let upstream = targets.upstream_graph(grad, &self.input_shape, self.reduction);
+        vec![&(&(&probs * &scale) - &offset) * &upstream]
+    }
+
+    fn jvp(&self, _inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        let targets = self
+            .targets
+            .as_ref()
+            .expect("targets is None in forward AD");
+        loss_tangent(&self.coefficients(), targets, tangents, self.reduction)
+    }
+}
+
+/// 负对数似然损失的函数接口，参见 `functional::nll_loss`
+pub fn nll_loss(
+    input: &Tensor,
+    target: &Tensor,
+    weight: Option<&Tensor>,
+    ignore_index: Option<usize>,
+    reduction: Reduction,
+) -> Tensor {
+    NllLoss::new(reduction).forward_with(input, target, weight, ignore_index)
+}
+
+/// 交叉熵损失的函数接口，参见 `functional::cross_entropy`
+pub fn cross_entropy(
+    input: &Tensor,
+    target: &Tensor,
+    weight: Option<&Tensor>,
+    ignore_index: Option<usize>,
+    label_smoothing: f32,
+    reduction: Reduction,
+) -> Tensor {
+    CrossEntropy::new(reduction, label_smoothing).forward_with(input, target, weight, ignore_index)
+}
@@ -0,0 +1,149 @@
+use crate::dtype::DType;
+use crate::ops::broadcast::{binary_result_type, broadcast_binary, sum_to, sum_to_shape};
+use crate::ops::complex::{BinaryKind, ComplexBinary};
+use crate::ops::{Op, needs_grad, propagate_tangent, sum_tangents};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, arr0};
+use std::ops::Div;
+use std::sync::Arc;
+
+/// 逐元素除法，支持广播。
+#[derive(Debug)]
+pub struct Divide {
+    a_data: Option<SavedTensor>,
+    b_data: Option<SavedTensor>,
+}
+
+impl Divide {
+    pub fn new() -> Self {
+        Divide {
+            a_data: None,
+            b_data: None,
+        }
+    }
+}
+
+impl Default for Divide {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Op for Divide {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.len() != 2 {
+            panic!("Divide requires exactly two input tensors");
+        }
+        if inputs.iter().any(|input| input.is_complex()) {
+            return ComplexBinary::new(BinaryKind::Div).forward(inputs);
+        }
+        // 整数相除得到浮点结果
+        let dtype = match binary_result_type(inputs[0], inputs[1]) {
+            dtype if dtype.is_floating_point() => dtype,
+            _ => DType::F32,
+        };
+        let result = broadcast_binary(inputs[0], inputs[1], dtype, |x, y| x / y, |x, y| x / y);
+
+        if needs_grad(&[inputs[0], inputs[1]]) {
+            let op = Divide {
+                a_data: Some(SavedTensor::new(inputs[0])),
+                b_data: Some(SavedTensor::new(inputs[1])),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(inputs[0]);
+            result_data.add_parent(inputs[1]);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .as_ref()
+            .expect("Gradient not found in backward pass")
+            .clone();
+        let a_data = &self
+            .a_data
+            .as_ref()
+            .expect("a_data is None in backward")
+            .unpack();
+        let b_data = &self
+            .b_data
+            .as_ref()
+            .expect("b_data is None in backward")
+            .unpack();
+
+        // ∂L/∂a = ∂L/∂c / b
+        let grad_a = sum_to_shape(&(&grad_output / b_data), a_data.shape());
+
+        // ∂L/∂b = -∂L/∂c * a / b²
+        let b_squared = b_data.mapv(|x| x * x);
+        let grad_b = -(&(&grad_output * a_data) / &b_squared);
+        let grad_b = sum_to_shape(&grad_b, b_data.shape());
+
+        vec![grad_a, grad_b]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let (a, b) = (&inputs[0], &inputs[1]);
+        let grad_b = -&(&(grad * a) / &(b * b));
+        vec![sum_to(&(grad / b), &a.shape()), sum_to(&grad_b, &b.shape())]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // d(a/b) = da/b - (a/b)·db/b
+        let b = inputs[1];
+        let da = tangents[0].as_ref().map(|da| da / b);
+        let db = tangents[1].as_ref().map(|db| -&(&(output * db) / b));
+        sum_tangents([da, db])
+    }
+}
+
+impl<'a> Div<&'a Tensor> for &Tensor {
+    type Output = Tensor;
+
+    fn div(self, other: &'a Tensor) -> Tensor {
+        let op = Divide::new();
+        op.forward(&[self, other])
+    }
+}
+
+impl Div<f32> for &Tensor {
+    type Output = Tensor;
+
+    fn div(self, scalar: f32) -> Tensor {
+        let b = Tensor::new(arr0(scalar).into_dyn());
+        self / &b
+    }
+}
+
+impl<'a> Div<&'a Tensor> for f32 {
+    type Output = Tensor;
+
+    fn div(self, tensor: &'a Tensor) -> Tensor {
+        let a = Tensor::new(arr0(self).into_dyn());
+        &a / tensor
+    }
+}
+
+impl Div<f64> for &Tensor {
+    type Output = Tensor;
+
+    fn div(self, scalar: f64) -> Tensor {
+        self / scalar as f32
+    }
+}
+
+impl<'a> Div<&'a Tensor> for f64 {
+    type Output = Tensor;
+
+    fn div(self, tensor: &'a Tensor) -> Tensor {
+        self as f32 / tensor
+    }
+}
@@ -0,0 +1,277 @@
+use super::{Op, expect_f32, multilinear_jvp, needs_grad, propagate_tangent};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Dimension, IxDyn};
+use ndarray_einsum::ArrayLike;
+use std::sync::Arc;
+
+/// 解析后的 einsum 表达式，每个下标是一个小写字母
+#[derive(Debug, Clone)]
+struct Equation {
+    inputs: Vec<Vec<char>>,
+    output: Vec<char>,
+}
+
+impl Equation {
+    /// 解析形如 `"ij,jk->ik"` 的表达式。
+    ///
+    /// 省略 `->` 时与 numpy 一致：输出为只出现一次的下标，按字母序排列。
+    fn parse(equation: &str, num_operands: usize) -> Self {
+        let equation: String = equation.chars().filter(|c| !c.is_whitespace()).collect();
+        if equation.contains("...") {
+            panic!("einsum does not support ellipsis: {}", equation);
+        }
+        let (lhs, rhs) = match equation.split_once("->") {
+            Some((lhs, rhs)) => (lhs, Some(rhs)),
+            None => (equation.as_str(), None),
+        };
+        let inputs: Vec<Vec<char>> = lhs.split(',').map(|s| s.chars().collect()).collect();
+        if inputs.len() != num_operands {
+            panic!(
+                "einsum equation {} has {} operands, but {} tensors were given",
+                equation,
+                inputs.len(),
+                num_operands
+            );
+        }
+        let output: Vec<char> = match rhs {
+            Some(rhs) => rhs.chars().collect(),
+            None => {
+                let mut once: Vec<char> = inputs
+                    .iter()
+                    .flatten()
+                    .copied()
+                    .filter(|&c| inputs.iter().flatten().filter(|&&x| x == c).count() == 1)
+                    .collect();
+                once.sort();
+                once
+            }
+        };
+        if let Some(c) = inputs
+            .iter()
+            .flatten()
+            .chain(&output)
+            .find(|c| !c.is_ascii_lowercase())
+        {
+            panic!("einsum subscripts must be lowercase letters, got '{}'", c);
+        }
+        Equation { inputs, output }
+    }
+
+    /// 下标 `c` 是否出现在输出或除第 `skip` 个以外的操作数中
+    fn appears_outside(&self, c: char, skip: usize) -> bool {
+        self.output.contains(&c)
+            || self
+                .inputs
+                .iter()
+                .enumerate()
+                .any(|(j, subs)| j != skip && subs.contains(&c))
+    }
+}
+
+/// 计算 einsum，出错时附带表达式 panic
+fn contract(inputs: &[String], output: &[char], operands: &[&dyn ArrayLike<f32>]) -> ArrayD<f32> {
+    let output: String = output.iter().collect();
+    let equation = format!("{}->{}", inputs.join(","), output);
+    ndarray_einsum::einsum(&equation, operands)
+        .unwrap_or_else(|e| panic!("Invalid einsum equation {}: {}", equation, e))
+}
+
+/// 爱因斯坦求和约定表示的张量缩并。
+///
+/// 反向传播时，对每个操作数把表达式改写为"输出梯度与其余操作数缩并到该操作数的下标"，
+/// 例如 `ij,jk->ik` 对第一个操作数的梯度为 `ik,jk->ij`。
+/// 只在该操作数内部求和的下标按广播还原，重复下标（对角线）只在对角线上有梯度。
+#[derive(Debug)]
+pub struct Einsum {
+    equation: String,
+    input_data: Vec<SavedTensor>,
+}
+
+impl Einsum {
+    pub fn new(equation: &str) -> Self {
+        Einsum {
+            equation: equation.to_string(),
+            input_data: Vec::new(),
+        }
+    }
+
+    /// 计算第 `i` 个操作数的梯度
+    fn operand_grad(
+        &self,
+        equation: &Equation,
+        input_data: &[ArrayD<f32>],
+        grad_output: &ArrayD<f32>,
+        i: usize,
+    ) -> ArrayD<f32> {
+        let subs = &equation.inputs[i];
+        let shape = input_data[i].shape();
+
+        // 能从输出梯度与其余操作数得到的下标（去重，保持出现顺序）
+        let mut kept: Vec<char> = Vec::new();
+        for &c in subs {
+            if !kept.contains(&c) && equation.appears_outside(c, i) {
+                kept.push(c);
+            }
+        }
+
+        // ndarray-einsum 不接受空下标，标量输出梯度单独相乘
+        let mut terms = Vec::new();
+        let mut operands: Vec<&dyn ArrayLike<f32>> = Vec::new();
+        if !equation.output.is_empty() {
+            terms.push(equation.output.iter().collect::<String>());
+            operands.push(grad_output);
+        }
+        for (j, data) in input_data.iter().enumerate() {
+            if j != i {
+                terms.push(equation.inputs[j].iter().collect::<String>());
+                operands.push(data);
+            }
+        }
+        let mut reduced = if operands.is_empty() {
+            grad_output.clone()
+        } else {
+            contract(&terms, &kept, &operands)
+        };
+        if equation.output.is_empty() && !operands.is_empty() {
+            let scale = grad_output.first().copied().unwrap_or(0.0);
+            reduced.mapv_inplace(|x| x * scale);
+        }
+
+        if kept == *subs {
+            return reduced;
+        }
+        ArrayD::from_shape_fn(IxDyn(shape), |index| {
+            let mut values: Vec<(char, usize)> = Vec::with_capacity(subs.len());
+            for (&c, &v) in subs.iter().zip(index.as_array_view()) {
+                match values.iter().find(|(x, _)| *x == c) {
+                    // 重复下标取值不同，不在对角线上
+                    Some(&(_, prev)) if prev != v => return 0.0,
+                    Some(_) => {}
+                    None => values.push((c, v)),
+                }
+            }
+            let position: Vec<usize> = kept
+                .iter()
+                .map(|c| values.iter().find(|(x, _)| x == c).unwrap().1)
+                .collect();
+            reduced[IxDyn(&position)]
+        })
+    }
+}
+
+impl Op for Einsum {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.is_empty() {
+            panic!("einsum requires at least one input tensor");
+        }
+        expect_f32(inputs, "einsum");
+        let equation = Equation::parse(&self.equation, inputs.len());
+        let input_data: Vec<ArrayD<f32>> = inputs.iter().map(|t| t.data()).collect();
+        let terms: Vec<String> = equation
+            .inputs
+            .iter()
+            .map(|subs| subs.iter().collect())
+            .collect();
+        let operands: Vec<&dyn ArrayLike<f32>> = input_data
+            .iter()
+            .map(|d| d as &dyn ArrayLike<f32>)
+            .collect();
+        let result = Tensor::new(contract(&terms, &equation.output, &operands));
+
+        if needs_grad(inputs) {
+            let op = Einsum {
+                equation: self.equation.clone(),
+                input_data: inputs.iter().map(|t| SavedTensor::new(t)).collect(),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            for input in inputs {
+                result_data.add_parent(input);
+            }
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let equation = Equation::parse(&self.equation, self.input_data.len());
+        let input_data: Vec<ArrayD<f32>> = self.input_data.iter().map(|t| t.unpack()).collect();
+        (0..input_data.len())
+            .map(|i| self.operand_grad(&equation, &input_data, &grad_output, i))
+            .collect()
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let equation = Equation::parse(&self.equation, inputs.len());
+        (0..inputs.len())
+            .map(|i| operand_grad_graph(&equation, inputs, grad, i))
+            .collect()
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        multilinear_jvp(self, inputs, tangents)
+    }
+}
+
+/// `Einsum::operand_grad` 的可微版本，梯度本身也由 einsum 计算
+fn operand_grad_graph(equation: &Equation, inputs: &[Tensor], grad: &Tensor, i: usize) -> Tensor {
+    let subs = &equation.inputs[i];
+    if subs.iter().enumerate().any(|(k, c)| subs[..k].contains(c)) {
+        panic!("einsum with repeated subscripts does not support create_graph");
+    }
+    let kept: Vec<char> = subs
+        .iter()
+        .copied()
+        .filter(|&c| equation.appears_outside(c, i))
+        .collect();
+
+    let mut terms = Vec::new();
+    let mut operands: Vec<&Tensor> = Vec::new();
+    if !equation.output.is_empty() {
+        terms.push(equation.output.iter().collect::<String>());
+        operands.push(grad);
+    }
+    for (j, input) in inputs.iter().enumerate() {
+        if j != i {
+            terms.push(equation.inputs[j].iter().collect::<String>());
+            operands.push(input);
+        }
+    }
+    let mut reduced = if operands.is_empty() {
+        grad.clone()
+    } else {
+        let kept: String = kept.iter().collect();
+        einsum(&format!("{}->{}", terms.join(","), kept), &operands)
+    };
+    if equation.output.is_empty() && !operands.is_empty() {
+        reduced = &reduced * grad;
+    }
+    if kept == *subs {
+        return reduced;
+    }
+
+    // 只在该操作数内部求和的下标补成大小为 1 的维度，再广播回原形状
+    let shape = inputs[i].shape();
+    let keep_shape: Vec<usize> = subs
+        .iter()
+        .zip(&shape)
+        .map(|(c, &s)| if kept.contains(c) { s } else { 1 })
+        .collect();
+    reduced
+        .reshape(&keep_shape)
+        .and_then(|r| r.expand(&shape))
+        .expect("Failed to broadcast einsum gradient")
+}
+
+/// 按爱因斯坦求和约定计算张量缩并，例如 `einsum("bqd,bkd->bqk", &[&q, &k])`
+pub fn einsum(equation: &str, operands: &[&Tensor]) -> Tensor {
+    Einsum::new(equation).forward(operands)
+}
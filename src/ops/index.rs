@@ -0,0 +1,707 @@
+//! 切片与高级索引：slice、index_select、gather、scatter、scatter_add、masked_select、布尔掩码索引。
+//!
+//! 下标张量可以是整数（如 `argmax` 返回的 int64）或取整数值的浮点张量，不参与求导。
+
+use super::adjoint::Adjoint;
+use super::shape::view_result;
+use super::{Op, linear_jvp, needs_grad, propagate_tangent};
+use crate::storage::Layout;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Axis, Dimension, IxDyn, SliceInfo, SliceInfoElem};
+use std::ops::{Range, RangeFrom, RangeFull, RangeTo};
+use std::sync::Arc;
+
+/// 读取输出张量上的梯度
+fn output_grad(parent: &Tensor) -> ArrayD<f32> {
+    parent
+        .0
+        .borrow()
+        .grad
+        .clone()
+        .expect("Gradient not found in backward pass")
+}
+
+/// 切片中的一项，对应 Python 索引语法中以逗号分隔的一段
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TensorIndex {
+    /// 取单个下标并去掉该维度，负数从末尾开始计数
+    Index(isize),
+    /// `start:end:step`，负数从末尾开始计数，越界时按 Python 规则截断，`step` 必须为正
+    Slice {
+        start: Option<isize>,
+        end: Option<isize>,
+        step: isize,
+    },
+    /// 插入长度为 1 的新维度（Python 中的 `None`）
+    NewAxis,
+    /// 展开为若干个完整切片，补齐剩余的维度（Python 中的 `...`）
+    Ellipsis,
+}
+
+impl TensorIndex {
+    /// 完整切片 `:`
+    pub fn full() -> Self {
+        TensorIndex::Slice {
+            start: None,
+            end: None,
+            step: 1,
+        }
+    }
+
+    /// 带步长的切片 `start:end:step`
+    pub fn slice(start: Option<isize>, end: Option<isize>, step: isize) -> Self {
+        TensorIndex::Slice { start, end, step }
+    }
+}
+
+impl From<isize> for TensorIndex {
+    fn from(index: isize) -> Self {
+        TensorIndex::Index(index)
+    }
+}
+
+impl From<Range<isize>> for TensorIndex {
+    fn from(r: Range<isize>) -> Self {
+        TensorIndex::slice(Some(r.start), Some(r.end), 1)
+    }
+}
+
+impl From<RangeFrom<isize>> for TensorIndex {
+    fn from(r: RangeFrom<isize>) -> Self {
+        TensorIndex::slice(Some(r.start), None, 1)
+    }
+}
+
+impl From<RangeTo<isize>> for TensorIndex {
+    fn from(r: RangeTo<isize>) -> Self {
+        TensorIndex::slice(None, Some(r.end), 1)
+    }
+}
+
+impl From<RangeFull> for TensorIndex {
+    fn from(_: RangeFull) -> Self {
+        TensorIndex::full()
+    }
+}
+
+/// 把 Python 风格的索引解析为 ndarray 的切片描述，所有下标都转换为非负且不越界
+fn resolve_indices(
+    indices: &[TensorIndex],
+    shape: &[usize],
+) -> Result<Vec<SliceInfoElem>, &'static str> {
+    let ellipsis_count = indices
+        .iter()
+        .filter(|i| **i == TensorIndex::Ellipsis)
+        .count();
+    if ellipsis_count > 1 {
+        return Err("索引中最多只能有一个省略号");
+    }
+    let consumed = indices
+        .iter()
+        .filter(|i| matches!(i, TensorIndex::Index(_) | TensorIndex::Slice { .. }))
+        .count();
+    if consumed > shape.len() {
+        return Err("索引数量超过张量维度");
+    }
+
+    let mut elems = Vec::with_capacity(indices.len() + shape.len());
+    let mut dim = 0;
+    for index in indices {
+        match *index {
+            TensorIndex::Index(i) => {
+                let size = shape[dim] as isize;
+                let i = if i < 0 { i + size } else { i };
+                if i < 0 || i >= size {
+                    return Err("索引超出范围");
+                }
+                elems.push(SliceInfoElem::Index(i));
+                dim += 1;
+            }
+            TensorIndex::Slice { start, end, step } => {
+                if step <= 0 {
+                    return Err("切片步长必须为正数");
+                }
+                let size = shape[dim] as isize;
+                let clamp = |v: isize| {
+                    let v = if v < 0 { v + size } else { v };
+                    v.clamp(0, size)
+                };
+                let start = start.map_or(0, clamp);
+                let end = end.map_or(size, clamp).max(start);
+                elems.push(SliceInfoElem::Slice {
+                    start,
+                    end: Some(end),
+                    step,
+                });
+                dim += 1;
+            }
+            TensorIndex::NewAxis => elems.push(SliceInfoElem::NewAxis),
+            TensorIndex::Ellipsis => {
+                for _ in 0..shape.len() - consumed {
+                    elems.push(SliceInfoElem::from(..));
+                    dim += 1;
+                }
+            }
+        }
+    }
+    // 未指定的尾部维度取完整切片
+    while dim < shape.len() {
+        elems.push(SliceInfoElem::from(..));
+        dim += 1;
+    }
+    Ok(elems)
+}
+
+/// 切片得到的视图布局，步长乘以切片步长，新轴的步长为 0
+fn slice_layout(layout: &Layout, elems: &[SliceInfoElem]) -> Layout {
+    let mut result = Layout {
+        shape: Vec::new(),
+        strides: Vec::new(),
+        offset: layout.offset,
+    };
+    let mut dim = 0;
+    for elem in elems {
+        match *elem {
+            SliceInfoElem::Index(i) => {
+                result.offset += i as usize * layout.strides[dim];
+                dim += 1;
+            }
+            SliceInfoElem::Slice { start, end, step } => {
+                let start = start as usize;
+                let end = end.map_or(layout.shape[dim], |e| e as usize);
+                let step = step as usize;
+                let length = (end.saturating_sub(start)).div_ceil(step);
+                if length > 0 {
+                    result.offset += start * layout.strides[dim];
+                }
+                result.shape.push(length);
+                result.strides.push(layout.strides[dim] * step);
+                dim += 1;
+            }
+            SliceInfoElem::NewAxis => {
+                result.shape.push(1);
+                result.strides.push(0);
+            }
+        }
+    }
+    result.shape.extend_from_slice(&layout.shape[dim..]);
+    result.strides.extend_from_slice(&layout.strides[dim..]);
+    result
+}
+
+fn slice_info(elems: &[SliceInfoElem]) -> SliceInfo<&[SliceInfoElem], IxDyn, IxDyn> {
+    SliceInfo::try_from(elems).expect("Invalid slice description")
+}
+
+/// 基本切片（下标、范围、步长、新轴），反向传播时把梯度写回被选中的位置
+#[derive(Debug, Clone)]
+pub struct Slicing {
+    elems: Vec<SliceInfoElem>,
+    input_shape: Vec<usize>,
+}
+
+impl Slicing {
+    /// `elems` 中的下标必须已经是非负且不越界的
+    pub fn new(elems: Vec<SliceInfoElem>) -> Self {
+        Slicing {
+            elems,
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Slicing {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "Slicing expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let input_shape = input.shape();
+        let layout = slice_layout(input.0.borrow().layout(), &self.elems);
+        view_result(self, input, layout, || Slicing {
+            elems: self.elems.clone(),
+            input_shape,
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let mut grad = ArrayD::zeros(IxDyn(&self.input_shape));
+        grad.slice_mut(slice_info(&self.elems))
+            .assign(&output_grad(parent));
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 把读出的下标值转换为 usize，下标必须是 `[0, size)` 内的整数
+fn to_index(value: f64, size: usize) -> Result<usize, &'static str> {
+    if value.fract() != 0.0 || value < 0.0 || value as usize >= size {
+        return Err("下标必须是不越界的非负整数");
+    }
+    Ok(value as usize)
+}
+
+/// 校验并转换下标张量，`size` 为下标所指维度的长度
+fn index_array(index: &Tensor, size: usize) -> Result<ArrayD<usize>, &'static str> {
+    // 在 f64 下读取，i64 下标超过 2^24 时也不会被舍入
+    let data = index.data_f64();
+    let converted = data
+        .iter()
+        .map(|&v| to_index(v, size))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(ArrayD::from_shape_vec(data.raw_dim(), converted).unwrap())
+}
+
+/// 对 `index` 的每个位置，给出把第 `dim` 维替换为下标值后的目标位置
+fn scatter_positions(index: &ArrayD<usize>, dim: usize, mut f: impl FnMut(&[usize], &[usize])) {
+    let mut target = vec![0; index.ndim()];
+    for (position, &i) in index.indexed_iter() {
+        let position = position.slice();
+        target.copy_from_slice(position);
+        target[dim] = i;
+        f(position, &target);
+    }
+}
+
+/// 沿指定维度按下标取切片，例如嵌入查找
+#[derive(Debug, Clone)]
+pub struct IndexSelect {
+    dim: usize,
+    index: Vec<usize>,
+    input_shape: Vec<usize>,
+}
+
+impl IndexSelect {
+    pub fn new(dim: usize, index: Vec<usize>) -> Self {
+        IndexSelect {
+            dim,
+            index,
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for IndexSelect {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "IndexSelect expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let data = &input.data_f64();
+        let output = data.select(Axis(self.dim), &self.index);
+
+        let result = Tensor::with_dtype(output, input.dtype());
+        if needs_grad(&[input]) {
+            let op = IndexSelect {
+                dim: self.dim,
+                index: self.index.clone(),
+                input_shape: data.shape().to_vec(),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // 同一下标被选中多次时梯度累加
+        let grad_output = output_grad(parent);
+        let mut grad = ArrayD::zeros(IxDyn(&self.input_shape));
+        for (k, &i) in self.index.iter().enumerate() {
+            let mut row = grad.index_axis_mut(Axis(self.dim), i);
+            row += &grad_output.index_axis(Axis(self.dim), k);
+        }
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿指定维度按逐元素的下标取值：`out[i][j] = input[index[i][j]][j]`（dim = 0）
+#[derive(Debug, Clone)]
+pub struct Gather {
+    dim: usize,
+    index: ArrayD<usize>,
+    input_shape: Vec<usize>,
+}
+
+impl Gather {
+    pub fn new(dim: usize, index: ArrayD<usize>) -> Self {
+        Gather {
+            dim,
+            index,
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Gather {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Gather expects exactly one input tensor");
+        let input = inputs[0];
+        let data = &input.data_f64();
+        let mut output = ArrayD::zeros(self.index.raw_dim());
+        scatter_positions(&self.index, self.dim, |position, source| {
+            output[position] = data[source];
+        });
+
+        let result = Tensor::with_dtype(output, input.dtype());
+        if needs_grad(&[input]) {
+            let op = Gather {
+                dim: self.dim,
+                index: self.index.clone(),
+                input_shape: data.shape().to_vec(),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = output_grad(parent);
+        let mut grad = ArrayD::zeros(IxDyn(&self.input_shape));
+        scatter_positions(&self.index, self.dim, |position, target| {
+            grad[target] += grad_output[position];
+        });
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿指定维度把 `src` 按下标写入（或累加到）`input` 的副本：
+/// `out[index[i][j]][j] = src[i][j]`（dim = 0）
+///
+/// 非累加模式下同一位置被写入多次时保留最后一次写入的值。
+/// `src` 可以比 `index` 大，只有与 `index` 形状相同的前部区域被写入。
+#[derive(Debug)]
+pub struct Scatter {
+    dim: usize,
+    index: ArrayD<usize>,
+    accumulate: bool,
+    src_shape: Vec<usize>,
+}
+
+impl Scatter {
+    pub fn new(dim: usize, index: ArrayD<usize>, accumulate: bool) -> Self {
+        Scatter {
+            dim,
+            index,
+            accumulate,
+            src_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Scatter {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 2,
+            "Scatter expects exactly two input tensors (input, src)"
+        );
+        // 结果保持 input 的类型，src 转换为该类型写入
+        let mut output = inputs[0].data_f64();
+        let src = &inputs[1].data_f64();
+        scatter_positions(&self.index, self.dim, |position, target| {
+            if self.accumulate {
+                output[target] += src[position];
+            } else {
+                output[target] = src[position];
+            }
+        });
+
+        let result = Tensor::with_dtype(output, inputs[0].dtype());
+        if needs_grad(&[inputs[0], inputs[1]]) {
+            let op = Scatter {
+                src_shape: inputs[1].shape(),
+                ..Scatter::new(self.dim, self.index.clone(), self.accumulate)
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(inputs[0]);
+            result_data.add_parent(inputs[1]);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = output_grad(parent);
+        let mut grad_input = grad_output.clone();
+        // 没有被写入的 src 元素梯度为 0
+        let mut grad_src = ArrayD::zeros(IxDyn(&self.src_shape));
+        scatter_positions(&self.index, self.dim, |position, target| {
+            grad_src[position] = grad_output[target];
+        });
+        if !self.accumulate {
+            // 被覆盖的位置与原输入无关
+            scatter_positions(&self.index, self.dim, |_, target| {
+                grad_input[target] = 0.0;
+            });
+        }
+        vec![grad_input, grad_src]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let mut grad_input = grad.clone();
+        if !self.accumulate {
+            let mut keep = ArrayD::ones(grad.shape());
+            scatter_positions(&self.index, self.dim, |_, target| {
+                keep[target] = 0.0;
+            });
+            grad_input = grad * &Tensor::new(keep);
+        }
+        let mut grad_src = Gather::new(self.dim, self.index.clone()).forward(&[grad]);
+        if grad_src.shape() != self.src_shape {
+            // 把与 index 形状相同的梯度放回 src 的前部区域，其余位置为 0
+            let region = Slicing {
+                elems: self
+                    .index
+                    .shape()
+                    .iter()
+                    .map(|&size| SliceInfoElem::from(0..size as isize))
+                    .collect(),
+                input_shape: self.src_shape.clone(),
+            };
+            grad_src = Adjoint::new(region).forward(&[&grad_src]);
+        }
+        vec![grad_input, grad_src]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 按布尔掩码选取元素：掩码覆盖输入的前若干维，结果形状为 `[选中个数, 其余维度...]`
+#[derive(Debug, Clone)]
+pub struct MaskedIndex {
+    /// 被选中位置在掩码（展平后）中的线性下标
+    positions: Vec<usize>,
+    mask_ndim: usize,
+    input_shape: Vec<usize>,
+}
+
+impl MaskedIndex {
+    pub fn new(positions: Vec<usize>, mask_ndim: usize) -> Self {
+        MaskedIndex {
+            positions,
+            mask_ndim,
+            input_shape: Vec::new(),
+        }
+    }
+
+    /// 把输入的前 `mask_ndim` 维展平为一维
+    fn flatten_shape(&self, shape: &[usize]) -> Vec<usize> {
+        let mut flat = vec![shape[..self.mask_ndim].iter().product()];
+        flat.extend_from_slice(&shape[self.mask_ndim..]);
+        flat
+    }
+}
+
+impl Op for MaskedIndex {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "MaskedIndex expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let data = &input.data_f64();
+        let flat = data
+            .as_standard_layout()
+            .into_owned()
+            .into_shape_with_order(IxDyn(&self.flatten_shape(data.shape())))
+            .expect("Failed to flatten masked dimensions");
+        let output = flat.select(Axis(0), &self.positions);
+
+        let result = Tensor::with_dtype(output, input.dtype());
+        if needs_grad(&[input]) {
+            let op = MaskedIndex {
+                positions: self.positions.clone(),
+                mask_ndim: self.mask_ndim,
+                input_shape: data.shape().to_vec(),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = output_grad(parent);
+        let mut grad = ArrayD::zeros(IxDyn(&self.flatten_shape(&self.input_shape)));
+        for (k, &i) in self.positions.iter().enumerate() {
+            grad.index_axis_mut(Axis(0), i)
+                .assign(&grad_output.index_axis(Axis(0), k));
+        }
+        let grad = grad
+            .into_shape_with_order(IxDyn(&self.input_shape))
+            .expect("Failed to restore masked gradient");
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 校验 gather/scatter 的下标形状：维数相同，除 `dim` 外不超过 `shape` 对应维度
+fn check_scatter_index(
+    index: &Tensor,
+    shape: &[usize],
+    dim: usize,
+) -> Result<ArrayD<usize>, &'static str> {
+    if dim >= shape.len() {
+        return Err("维度超出范围");
+    }
+    let index_shape = index.shape();
+    if index_shape.len() != shape.len() {
+        return Err("下标张量的维度必须与输入相同");
+    }
+    if index_shape
+        .iter()
+        .zip(shape)
+        .enumerate()
+        .any(|(d, (&i, &s))| d != dim && i > s)
+    {
+        return Err("下标张量的形状超出输入范围");
+    }
+    index_array(index, shape[dim])
+}
+
+impl Tensor {
+    /// Python 风格的切片，例如 `x[1:, ::2, None, ..., -1]` 对应
+    /// `x.slice(&[(1..).into(), TensorIndex::slice(None, None, 2), TensorIndex::NewAxis, TensorIndex::Ellipsis, (-1).into()])`
+    pub fn slice(&self, indices: &[TensorIndex]) -> Result<Tensor, &'static str> {
+        let elems = resolve_indices(indices, &self.shape())?;
+        Ok(Slicing::new(elems).forward(&[self]))
+    }
+
+    /// 沿指定维度按一维下标张量选取切片
+    pub fn index_select(&self, dim: usize, index: &Tensor) -> Result<Tensor, &'static str> {
+        let size = self.size(dim).ok_or("维度超出范围")?;
+        if index.dim() != 1 {
+            return Err("下标张量必须是一维的");
+        }
+        let index = index_array(index, size)?.into_raw_vec_and_offset().0;
+        Ok(IndexSelect::new(dim, index).forward(&[self]))
+    }
+
+    /// 沿指定维度按逐元素的下标取值，结果形状与 `index` 相同
+    pub fn gather(&self, dim: usize, index: &Tensor) -> Result<Tensor, &'static str> {
+        let index = check_scatter_index(index, &self.shape(), dim)?;
+        Ok(Gather::new(dim, index).forward(&[self]))
+    }
+
+    /// 把 `src` 按下标写入自身的副本，`gather` 的逆操作
+    pub fn scatter(
+        &self,
+        dim: usize,
+        index: &Tensor,
+        src: &Tensor,
+    ) -> Result<Tensor, &'static str> {
+        let index = self.scatter_index(dim, index, src)?;
+        Ok(Scatter::new(dim, index, false).forward(&[self, src]))
+    }
+
+    /// 把 `src` 按下标累加到自身的副本，同一位置的多个值会相加
+    pub fn scatter_add(
+        &self,
+        dim: usize,
+        index: &Tensor,
+        src: &Tensor,
+    ) -> Result<Tensor, &'static str> {
+        let index = self.scatter_index(dim, index, src)?;
+        Ok(Scatter::new(dim, index, true).forward(&[self, src]))
+    }
+
+    fn scatter_index(
+        &self,
+        dim: usize,
+        index: &Tensor,
+        src: &Tensor,
+    ) -> Result<ArrayD<usize>, &'static str> {
+        let index_array = check_scatter_index(index, &self.shape(), dim)?;
+        let src_shape = src.shape();
+        if src_shape.len() != index_array.ndim()
+            || index_array
+                .shape()
+                .iter()
+                .zip(&src_shape)
+                .any(|(i, s)| i > s)
+        {
+            return Err("下标张量的形状超出源张量范围");
+        }
+        Ok(index_array)
+    }
+
+    /// 布尔掩码索引（非零即为真）：掩码形状与输入的前若干维相同，
+    /// 结果形状为 `[选中个数, 其余维度...]`
+    pub fn index_mask(&self, mask: &Tensor) -> Result<Tensor, &'static str> {
+        let shape = self.shape();
+        let mask_shape = mask.shape();
+        if mask_shape.len() > shape.len() || mask_shape[..] != shape[..mask_shape.len()] {
+            return Err("掩码形状必须与张量的前若干维相同");
+        }
+        let positions = mask
+            .data()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &m)| m != 0.0)
+            .map(|(i, _)| i)
+            .collect();
+        Ok(MaskedIndex::new(positions, mask_shape.len()).forward(&[self]))
+    }
+
+    /// 按可广播到自身形状的掩码选取元素，返回一维张量
+    pub fn masked_select(&self, mask: &Tensor) -> Result<Tensor, &'static str> {
+        let shape = self.shape();
+        let mask_data = mask.data();
+        let full_mask = mask_data
+            .broadcast(IxDyn(&shape))
+            .ok_or("掩码无法广播到张量的形状")?;
+        let positions = full_mask
+            .iter()
+            .enumerate()
+            .filter(|&(_, &m)| m != 0.0)
+            .map(|(i, _)| i)
+            .collect();
+        Ok(MaskedIndex::new(positions, shape.len()).forward(&[self]))
+    }
+}
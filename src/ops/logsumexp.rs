@@ -0,0 +1,162 @@
+use super::reduce::{expand_grad, normalize_dims, reduced_shape, to_rows};
+use super::{Op, needs_grad, propagate_tangent};
+use crate::dtype::DType;
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Axis, IxDyn, NdFloat, Zip};
+use std::sync::Arc;
+
+/// 沿指定维度计算 `ln(Σ e^x)`。
+///
+/// 先减去每行的最大值再求指数，避免上溢。
+#[derive(Debug)]
+pub struct LogSumExp {
+    dims: Vec<usize>,
+    keepdim: bool,
+    input_data: Option<SavedTensor>,
+    /// 每个输出位置的平移量（行内最大值）
+    shift: Option<ArrayD<f32>>,
+    /// 每个输出位置平移后的 `ln(Σ e^(x - shift))`
+    log_sum: Option<ArrayD<f32>>,
+}
+
+impl LogSumExp {
+    pub fn new(ndim: usize, dims: &[usize], keepdim: bool) -> Self {
+        LogSumExp {
+            dims: normalize_dims(dims, ndim),
+            keepdim,
+            input_data: None,
+            shift: None,
+            log_sum: None,
+        }
+    }
+
+    /// 每个输出位置的平移量与平移后的 `ln(Σ e^(x - shift))`
+    fn parts<A: NdFloat>(&self, data: &ArrayD<A>) -> (ArrayD<A>, ArrayD<A>) {
+        let output_shape = reduced_shape(data.shape(), &self.dims, self.keepdim);
+        let parts = to_rows(data, &self.dims).map_axis(Axis(1), shifted_log_sum_exp);
+        let shift = parts
+            .mapv(|(m, _)| m)
+            .into_shape_with_order(IxDyn(&output_shape))
+            .expect("Failed to reshape logsumexp result");
+        let log_sum = parts
+            .mapv(|(_, l)| l)
+            .into_shape_with_order(IxDyn(&output_shape))
+            .expect("Failed to reshape logsumexp result");
+        (shift, log_sum)
+    }
+}
+
+/// 对一行元素计算平移量 m 与 `ln(Σ e^(x - m))`，两者之和即为 logsumexp。
+///
+/// m 取行内最大值；最大值为无穷时取 0，使全为 -inf 的行得到 -inf。
+/// 分开保存两部分可以在反向传播中得到更精确的 `e^(x - lse)`。
+pub(crate) fn shifted_log_sum_exp<'a, A: NdFloat>(
+    row: impl IntoIterator<Item = &'a A> + Clone,
+) -> (A, A) {
+    let max = row
+        .clone()
+        .into_iter()
+        .fold(A::neg_infinity(), |m, &x| m.max(x));
+    let shift = if max.is_finite() { max } else { A::zero() };
+    let sum = row
+        .into_iter()
+        .fold(A::zero(), |acc, &x| acc + (x - shift).exp());
+    (shift, sum.ln())
+}
+
+impl Op for LogSumExp {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "LogSumExp expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        // 结果不是 f32 时在 f64 下计算；反向传播所需的两部分与梯度一样以 f32 保存
+        let dtype = input.dtype().to_floating();
+        let (result, shift, log_sum) = if dtype == DType::F32 {
+            let (shift, log_sum) = self.parts(&input.data());
+            (Tensor::new(&shift + &log_sum), shift, log_sum)
+        } else {
+            let (shift, log_sum) = self.parts(&input.data_f64());
+            let result = Tensor::with_dtype(&shift + &log_sum, dtype);
+            (result, shift.mapv(|x| x as f32), log_sum.mapv(|x| x as f32))
+        };
+
+        if needs_grad(&[input]) {
+            let op = LogSumExp {
+                dims: self.dims.clone(),
+                keepdim: self.keepdim,
+                input_data: Some(SavedTensor::new(input)),
+                shift: Some(shift),
+                log_sum: Some(log_sum),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let input = &self
+            .input_data
+            .as_ref()
+            .expect("input_data is None in backward")
+            .unpack();
+        let shift = self.shift.as_ref().expect("shift is None in backward");
+        let log_sum = self.log_sum.as_ref().expect("log_sum is None in backward");
+
+        // ∂lse/∂x_i = e^(x_i - lse)，即沿归约维度的 softmax
+        let grad = expand_grad(&grad_output, input.shape(), &self.dims);
+        let shift = expand_grad(shift, input.shape(), &self.dims);
+        let log_sum = expand_grad(log_sum, input.shape(), &self.dims);
+        let grad_input = Zip::from(&grad)
+            .and(input)
+            .and(&shift)
+            .and(&log_sum)
+            .map_collect(|&g, &x, &m, &l| g * ((x - m) - l).exp());
+        vec![grad_input]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        // ∂lse/∂x = e^(x - lse)
+        let x = &inputs[0];
+        let keep_shape = reduced_shape(&x.shape(), &self.dims, true);
+        let grad = grad
+            .reshape(&keep_shape)
+            .expect("Failed to reshape gradient");
+        let output = output
+            .reshape(&keep_shape)
+            .expect("Failed to reshape output");
+        vec![&grad * &(x - &output).exp()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // d lse = Σ e^(x - lse)·dx
+        let x = inputs[0];
+        let tangent = tangents[0]
+            .as_ref()
+            .expect("Forward AD requires at least one tangent");
+        let keep_shape = reduced_shape(&x.shape(), &self.dims, true);
+        let output = output
+            .reshape(&keep_shape)
+            .expect("Failed to reshape output");
+        (&(x - &output).exp() * tangent).sum_dims(&self.dims, self.keepdim)
+    }
+}
+
+impl Tensor {
+    /// 沿指定维度计算数值稳定的 `ln(Σ e^x)`（空列表表示所有维度）
+    pub fn logsumexp(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        LogSumExp::new(self.dim(), dims, keepdim).forward(&[self])
+    }
+}
@@ -1,87 +1,143 @@
-use super::Op;
+use super::broadcast::{broadcast_shape, sum_to, sum_to_shape};
+use super::complex::ComplexMatMul;
+use super::{Op, multilinear_jvp, needs_grad, propagate_tangent};
+use crate::dtype::{DType, promote_types};
+use crate::storage::SavedTensor;
 use crate::tensor::Tensor;
 use core::panic;
-use ndarray::{ArrayD, Axis, Ix2};
-use ndarray_einsum::tensordot;
-use std::rc::Rc;
+use ndarray::{Array3, ArrayD, ArrayViewD, Axis, Ix1, Ix2, IxDyn, LinalgScalar, Zip};
+use std::sync::Arc;
 
+/// 矩阵乘法，语义与 `torch.matmul` 一致：
+///
+/// - 两个操作数都至少为 2 维时，最后两维做矩阵乘法，其余的批量维度按广播规则对齐：
+///   `[..., n, k] @ [..., k, m] -> [..., n, m]`
+/// - 1 维的左操作数视为 `[1, k]`，1 维的右操作数视为 `[k, 1]`，结果中再去掉补上的维度
+///
+/// `transpose_a` / `transpose_b` 表示在相乘前交换对应操作数的最后两维（对 1 维操作数无效），
+/// 无需先复制出转置后的张量。
 #[derive(Debug)]
 pub struct MatMul {
-    input_shapes: Vec<Vec<usize>>,
-    a_data: Option<ArrayD<f32>>,
-    b_data: Option<ArrayD<f32>>,
+    transpose_a: bool,
+    transpose_b: bool,
+    a_data: Option<SavedTensor>,
+    b_data: Option<SavedTensor>,
 }
 
 impl MatMul {
     pub fn new() -> Self {
+        Self::with_transpose(false, false)
+    }
+
+    /// 创建一个在相乘前转置操作数的矩阵乘法，例如 `with_transpose(false, true)` 计算 `A @ Bᵀ`
+    pub fn with_transpose(transpose_a: bool, transpose_b: bool) -> Self {
         MatMul {
-            input_shapes: Vec::new(),
+            transpose_a,
+            transpose_b,
             a_data: None,
             b_data: None,
         }
     }
 }
 
+impl Default for MatMul {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 交换视图的最后两维
+fn transpose_last<A>(mut view: ArrayViewD<'_, A>) -> ArrayViewD<'_, A> {
+    let ndim = view.ndim();
+    view.swap_axes(ndim - 2, ndim - 1);
+    view
+}
+
+/// 把操作数整理成至少 2 维的矩阵视图。
+///
+/// 1 维的左操作数补成 `[1, k]`，右操作数补成 `[k, 1]`；其余情况按需转置最后两维。
+fn as_matrix<A>(data: &ArrayD<A>, transpose: bool, is_left: bool) -> ArrayViewD<'_, A> {
+    match data.ndim() {
+        0 => panic!("MatMul does not support 0D tensors"),
+        1 if is_left => data.view().insert_axis(Axis(0)),
+        1 => data.view().insert_axis(Axis(1)),
+        _ if transpose => transpose_last(data.view()),
+        _ => data.view(),
+    }
+}
+
+/// 批量矩阵乘法：`[..., n, k] @ [..., k, m] -> [..., n, m]`，批量维度按广播规则对齐
+fn batched_matmul<A: LinalgScalar>(a: &ArrayViewD<A>, b: &ArrayViewD<A>) -> ArrayD<A> {
+    let (a_batch, a_mat) = a.shape().split_at(a.ndim() - 2);
+    let (b_batch, b_mat) = b.shape().split_at(b.ndim() - 2);
+    let (n, k, m) = (a_mat[0], a_mat[1], b_mat[1]);
+    if k != b_mat[0] {
+        panic!(
+            "Matrix dimensions do not match for multiplication: {:?} @ {:?}",
+            a.shape(),
+            b.shape()
+        );
+    }
+
+    let batch = broadcast_shape(a_batch, b_batch);
+    let batch_size = batch.iter().product();
+    let flatten = |x: &ArrayViewD<A>, rows: usize, cols: usize| {
+        let shape: Vec<usize> = batch.iter().copied().chain([rows, cols]).collect();
+        x.broadcast(IxDyn(&shape))
+            .expect("Failed to broadcast batch dimensions")
+            .as_standard_layout()
+            .into_owned()
+            .into_shape_with_order((batch_size, rows, cols))
+            .expect("Failed to flatten batch dimensions")
+    };
+    let a3 = flatten(a, n, k);
+    let b3 = flatten(b, k, m);
+
+    let mut output = Array3::zeros((batch_size, n, m));
+    Zip::from(output.outer_iter_mut())
+        .and(a3.outer_iter())
+        .and(b3.outer_iter())
+        .for_each(|mut out, a, b| out.assign(&a.dot(&b)));
+
+    let output_shape: Vec<usize> = batch.into_iter().chain([n, m]).collect();
+    output
+        .into_shape_with_order(IxDyn(&output_shape))
+        .expect("Failed to reshape matmul result")
+}
+
 impl Op for MatMul {
     fn forward(&self, inputs: &[&Tensor]) -> Tensor {
-        // A: [batch, input] or [batch, channel, input]
-        // B: [input, output]
-        // Output: [batch, output] or [batch, channel, output]
         if inputs.len() != 2 {
             panic!("MatMul requires exactly two input tensors");
         }
-        let a = &inputs[0].0.borrow().data;
-        let b = &inputs[1].0.borrow().data;
-
-        if b.ndim() != 2 {
-            panic!("Input B must be a 2D tensor (weights), got {}D", b.ndim());
-        }
-
-        let a_ndim = a.ndim();
-        if a_ndim != 2 && a_ndim != 3 {
-            panic!("Input A must be a 2D or 3D tensor, got {}D", a_ndim);
-        }
-
-        let a_last_dim_idx = a_ndim - 1;
-        let a_last_dim_size = a.shape()[a_last_dim_idx];
-        let b_first_dim_size = b.shape()[0];
-
-        if a_last_dim_size != b_first_dim_size {
-            panic!(
-                "Matrix dimensions do not match for multiplication. A's last dim ({}) is {} and B's first dim ({}) is {}",
-                a_last_dim_idx, a_last_dim_size, 0, b_first_dim_size
-            );
+        if inputs.iter().any(|input| input.is_complex()) {
+            return ComplexMatMul::with_transpose(self.transpose_a, self.transpose_b)
+                .forward(inputs);
         }
-
-        let output_data = if a_ndim == 2 {
-            // A: [batch, input], B: [input, output] -> Output: [batch, output]
-            let a_2d = a.view().into_dimensionality::<Ix2>().unwrap();
-            let b_2d = b.view().into_dimensionality::<Ix2>().unwrap();
-            a_2d.dot(&b_2d).into_dyn()
+        // 结果为提升后的类型，不是 f32 时在 f64 下计算再转换，与逐元素运算一致
+        let dtype = promote_types(inputs[0].dtype(), inputs[1].dtype());
+        let (transpose_a, transpose_b) = (self.transpose_a, self.transpose_b);
+        let result = if dtype == DType::F32 {
+            let (a, b) = (&inputs[0].data(), &inputs[1].data());
+            Tensor::new(matmul_arrays(a, b, transpose_a, transpose_b))
         } else {
-            // a_ndim == 3
-            // A: [batch, channel, input], B: [input, output] -> Output: [batch, channel, output]
-            tensordot(a, b, &[Axis(2)], &[Axis(0)]).into_dyn()
+            let (a, b) = (&inputs[0].data_f64(), &inputs[1].data_f64());
+            Tensor::with_dtype(matmul_arrays(a, b, transpose_a, transpose_b), dtype)
         };
-
-        let result = Tensor::new(output_data);
-        if inputs[0].0.borrow().requires_grad || inputs[1].0.borrow().requires_grad {
-            result.0.borrow_mut().grad = Some(ArrayD::zeros(result.shape()));
+        if needs_grad(&[inputs[0], inputs[1]]) {
             let op = MatMul {
-                input_shapes: vec![inputs[0].shape().to_vec(), inputs[1].shape().to_vec()],
-                a_data: Some(a.clone()),
-                b_data: Some(b.clone()),
+                transpose_a: self.transpose_a,
+                transpose_b: self.transpose_b,
+                a_data: Some(SavedTensor::new(inputs[0])),
+                b_data: Some(SavedTensor::new(inputs[1])),
             };
-            result.0.borrow_mut().set_creator(Rc::new(op));
-        }
-        if inputs[0].0.borrow().requires_grad {
-            result.0.borrow_mut().add_parent(inputs[0]);
-        }
-        if inputs[1].0.borrow().requires_grad {
-            result.0.borrow_mut().add_parent(inputs[1]);
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(inputs[0]);
+            result_data.add_parent(inputs[1]);
+            result_data.requires_grad = true;
         }
-        result.0.borrow_mut().requires_grad =
-            inputs[0].0.borrow().requires_grad || inputs[1].0.borrow().requires_grad;
+        propagate_tangent(self, inputs, &result);
         result
     }
 
@@ -93,38 +149,154 @@ impl Op for MatMul {
             .as_ref()
             .expect("Gradient not found in backward pass")
             .clone();
-        let a = self.a_data.as_ref().expect("a_data not saved in MatMul");
-        let b = self.b_data.as_ref().expect("b_data not saved in MatMul");
+        let a = &self
+            .a_data
+            .as_ref()
+            .expect("a_data not saved in MatMul")
+            .unpack();
+        let b = &self
+            .b_data
+            .as_ref()
+            .expect("b_data not saved in MatMul")
+            .unpack();
 
-        let a_ndim = a.ndim();
-        let b_2d = b.view().into_dimensionality::<Ix2>().unwrap();
+        let (grad_a, grad_b) = matmul_grads(grad_output, a, b, self.transpose_a, self.transpose_b);
+        vec![grad_a, grad_b]
+    }
 
-        // --- Calculate grad_a = grad_output @ b.T ---
-        let b_t = b_2d.t();
-        let grad_a = if a_ndim == 2 {
-            let grad_output_2d = grad_output.view().into_dimensionality::<Ix2>().unwrap();
-            grad_output_2d.dot(&b_t).into_dyn()
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let (a, b) = (&inputs[0], &inputs[1]);
+        let mut grad = grad.clone();
+        if a.dim() == 1 {
+            grad = unsqueeze(&grad, grad.dim() + 1 - b.dim().min(2));
+        }
+        if b.dim() == 1 {
+            grad = unsqueeze(&grad, grad.dim());
+        }
+        // 1 维操作数补成矩阵后转置标记无效，与 as_matrix 一致
+        let transpose_a = self.transpose_a && a.dim() > 1;
+        let transpose_b = self.transpose_b && b.dim() > 1;
+        let a_mat = if a.dim() == 1 {
+            unsqueeze(a, 0)
         } else {
-            // a_ndim == 3
-            // grad_output: [B, C, N], b_t: [N, K] -> grad_a: [B, C, K]
-            tensordot(&grad_output, &b_t, &[Axis(2)], &[Axis(0)]).into_dyn()
+            a.clone()
         };
-
-        // --- Calculate grad_b = a.T @ grad_output ---
-        // This involves summing over the batch dimension (and channel if it exists).
-        let grad_b = if a_ndim == 2 {
-            // a: [B, K], grad_output: [B, N] -> grad_b: [K, N]
-            let a_2d = a.view().into_dimensionality::<Ix2>().unwrap();
-            let grad_output_2d = grad_output.view().into_dimensionality::<Ix2>().unwrap();
-            a_2d.t().dot(&grad_output_2d).into_dyn()
+        let b_mat = if b.dim() == 1 {
+            unsqueeze(b, 1)
         } else {
-            // a_ndim == 3
-            // a: [B, C, K], grad_output: [B, C, N] -> grad_b: [K, N]
-            // We need to contract the first two axes (batch and channel).
-            tensordot(a, &grad_output, &[Axis(0), Axis(1)], &[Axis(0), Axis(1)]).into_dyn()
+            b.clone()
         };
 
-        vec![grad_a, grad_b]
+        // grad_a = grad_output @ bᵀ，grad_b = aᵀ @ grad_output，转置通过标记完成
+        let grad_a = MatMul::with_transpose(false, !transpose_b).forward(&[&grad, &b_mat]);
+        let grad_b = MatMul::with_transpose(!transpose_a, false).forward(&[&a_mat, &grad]);
+        vec![
+            restore_operand_graph(grad_a, a, transpose_a),
+            restore_operand_graph(grad_b, b, transpose_b),
+        ]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        multilinear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 按 `MatMul` 的语义计算两个数组的乘积
+pub(crate) fn matmul_arrays<A: LinalgScalar>(
+    a: &ArrayD<A>,
+    b: &ArrayD<A>,
+    transpose_a: bool,
+    transpose_b: bool,
+) -> ArrayD<A> {
+    let a_mat = as_matrix(a, transpose_a, true);
+    let b_mat = as_matrix(b, transpose_b, false);
+    let mut output = batched_matmul(&a_mat, &b_mat);
+    // 去掉为 1 维操作数补上的维度：n 在倒数第二维，m 在最后一维
+    if a.ndim() == 1 {
+        let n_axis = output.ndim() - 2;
+        output = output.remove_axis(Axis(n_axis));
+    }
+    if b.ndim() == 1 {
+        let m_axis = output.ndim() - 1;
+        output = output.remove_axis(Axis(m_axis));
+    }
+    output
+}
+
+/// 由输出梯度计算 `matmul_arrays` 两个操作数的梯度
+pub(crate) fn matmul_grads<A: LinalgScalar>(
+    mut grad_output: ArrayD<A>,
+    a: &ArrayD<A>,
+    b: &ArrayD<A>,
+    transpose_a: bool,
+    transpose_b: bool,
+) -> (ArrayD<A>, ArrayD<A>) {
+    // 恢复前向中去掉的维度，使梯度形状为 [..., n, m]
+    if a.ndim() == 1 {
+        let axis = grad_output.ndim() + 1 - b.ndim().min(2);
+        grad_output = grad_output.insert_axis(Axis(axis));
+    }
+    if b.ndim() == 1 {
+        let last = grad_output.ndim();
+        grad_output = grad_output.insert_axis(Axis(last));
+    }
+
+    let a_mat = as_matrix(a, transpose_a, true);
+    let b_mat = as_matrix(b, transpose_b, false);
+    let grad_view = grad_output.view();
+
+    // grad_a = grad_output @ bᵀ，grad_b = aᵀ @ grad_output，再对广播的批量维度求和
+    let grad_a = batched_matmul(&grad_view, &transpose_last(b_mat.view()));
+    let grad_a = restore_operand(sum_to_shape(&grad_a, a_mat.shape()), a, transpose_a);
+    let grad_b = batched_matmul(&transpose_last(a_mat.view()), &grad_view);
+    let grad_b = restore_operand(sum_to_shape(&grad_b, b_mat.shape()), b, transpose_b);
+    (grad_a, grad_b)
+}
+
+/// 把矩阵形式的梯度还原成操作数原本的形状（`as_matrix` 的逆操作）
+fn restore_operand<A: Clone>(grad: ArrayD<A>, data: &ArrayD<A>, transpose: bool) -> ArrayD<A> {
+    if data.ndim() == 1 {
+        // [1, k] 或 [k, 1] 恰好有 k 个元素，直接按原形状重排
+        return grad
+            .into_shape_with_order(IxDyn(data.shape()))
+            .expect("Failed to restore vector gradient");
+    }
+    if transpose {
+        transpose_last(grad.view())
+            .as_standard_layout()
+            .into_owned()
+    } else {
+        grad
+    }
+}
+
+/// 在指定位置插入大小为 1 的维度
+fn unsqueeze(tensor: &Tensor, dim: usize) -> Tensor {
+    tensor
+        .unsqueeze(dim)
+        .expect("Failed to insert axis into gradient")
+}
+
+/// `restore_operand` 的可微版本：先对广播的批量维度求和，再还原成操作数的形状
+fn restore_operand_graph(grad: Tensor, operand: &Tensor, transpose: bool) -> Tensor {
+    let ndim = operand.dim();
+    if ndim == 1 {
+        // [1, k] 或 [k, 1] 恰好有 k 个元素，求和后直接按原形状重排
+        let matrix_shape = grad.shape()[grad.dim() - 2..].to_vec();
+        return sum_to(&grad, &matrix_shape)
+            .reshape(&operand.shape())
+            .expect("Failed to restore vector gradient");
+    }
+    let mut shape = operand.shape();
+    if transpose {
+        shape.swap(ndim - 2, ndim - 1);
+    }
+    let grad = sum_to(&grad, &shape);
+    if transpose {
+        grad.transpose(ndim - 2, ndim - 1)
+            .expect("Failed to transpose gradient")
+    } else {
+        grad
     }
 }
 
@@ -132,3 +304,136 @@ pub fn matmul(a: &Tensor, b: &Tensor) -> Tensor {
     let opt = MatMul::new();
     opt.forward(&[a, b])
 }
+
+/// 按 `Outer` 的语义计算两个向量的外积
+fn outer_arrays<A: LinalgScalar>(a: &ArrayD<A>, b: &ArrayD<A>) -> ArrayD<A> {
+    let a_col = a.view().insert_axis(Axis(1));
+    let b_row = b.view().insert_axis(Axis(0));
+    &a_col * &b_row
+}
+
+/// 向量外积 `a ⊗ b`：`[n] ⊗ [m] -> [n, m]`
+#[derive(Debug, Default)]
+pub struct Outer {
+    a_data: Option<SavedTensor>,
+    b_data: Option<SavedTensor>,
+}
+
+impl Op for Outer {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.len() != 2 {
+            panic!("Outer requires exactly two input tensors");
+        }
+        let (a, b) = (inputs[0], inputs[1]);
+        if a.dim() != 1 || b.dim() != 1 {
+            panic!(
+                "Outer expects two 1D tensors, got {}D and {}D",
+                a.dim(),
+                b.dim()
+            );
+        }
+
+        let dtype = promote_types(a.dtype(), b.dtype());
+        let result = if dtype == DType::F32 {
+            Tensor::new(outer_arrays(&a.data(), &b.data()))
+        } else {
+            Tensor::with_dtype(outer_arrays(&a.data_f64(), &b.data_f64()), dtype)
+        };
+        if needs_grad(&[inputs[0], inputs[1]]) {
+            let op = Outer {
+                a_data: Some(SavedTensor::new(inputs[0])),
+                b_data: Some(SavedTensor::new(inputs[1])),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(inputs[0]);
+            result_data.add_parent(inputs[1]);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, grad: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = grad
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let a = &self
+            .a_data
+            .as_ref()
+            .expect("a_data not saved in Outer")
+            .unpack();
+        let b = &self
+            .b_data
+            .as_ref()
+            .expect("b_data not saved in Outer")
+            .unpack();
+        let g = grad_output.into_dimensionality::<Ix2>().unwrap();
+        let a = a.view().into_dimensionality::<Ix1>().unwrap();
+        let b = b.view().into_dimensionality::<Ix1>().unwrap();
+
+        // ∂L/∂a = G @ b，∂L/∂b = Gᵀ @ a
+        vec![g.dot(&b).into_dyn(), g.t().dot(&a).into_dyn()]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let (a, b) = (&inputs[0], &inputs[1]);
+        let grad_t = grad.transpose(0, 1).expect("Outer gradient must be 2D");
+        vec![grad.mv(b), grad_t.mv(a)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        multilinear_jvp(self, inputs, tangents)
+    }
+}
+
+impl Tensor {
+    /// 矩阵乘法，支持批量维度广播与 1 维向量，语义同 `torch.matmul`
+    pub fn matmul(&self, other: &Tensor) -> Tensor {
+        matmul(self, other)
+    }
+
+    /// 批量矩阵乘法：`[B, n, k] @ [B, k, m] -> [B, n, m]`，不做广播
+    pub fn bmm(&self, other: &Tensor) -> Tensor {
+        let (a_shape, b_shape) = (self.shape(), other.shape());
+        if a_shape.len() != 3 || b_shape.len() != 3 || a_shape[0] != b_shape[0] {
+            panic!(
+                "bmm expects two 3D tensors with the same batch size, got {:?} and {:?}",
+                a_shape, b_shape
+            );
+        }
+        matmul(self, other)
+    }
+
+    /// 矩阵与向量相乘：`[n, k] @ [k] -> [n]`
+    pub fn mv(&self, vec: &Tensor) -> Tensor {
+        if self.dim() != 2 || vec.dim() != 1 {
+            panic!(
+                "mv expects a 2D matrix and a 1D vector, got {}D and {}D",
+                self.dim(),
+                vec.dim()
+            );
+        }
+        matmul(self, vec)
+    }
+
+    /// 向量内积：`[k] · [k] -> 标量`
+    pub fn dot(&self, other: &Tensor) -> Tensor {
+        if self.dim() != 1 || other.dim() != 1 {
+            panic!(
+                "dot expects two 1D tensors, got {}D and {}D",
+                self.dim(),
+                other.dim()
+            );
+        }
+        matmul(self, other)
+    }
+
+    /// 向量外积：`[n] ⊗ [m] -> [n, m]`
+    pub fn outer(&self, other: &Tensor) -> Tensor {
+        Outer::default().forward(&[self, other])
+    }
+}
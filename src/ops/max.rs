@@ -0,0 +1,305 @@
+use super::index::Scatter;
+use super::reduce::{normalize_dims, reduced_shape, row_indices, to_rows};
+use super::{Op, needs_grad, propagate_tangent};
+use crate::dtype::DType;
+use crate::tensor::Tensor;
+use ndarray::{Array2, ArrayD, IxDyn};
+use std::sync::Arc;
+
+/// 在每一行中选出极值所在的位置（并列时取第一个）。
+///
+/// `better(a, b)` 为真表示 a 比当前最优值 b 更优。NaN 视为最优，与PyTorch的传播行为一致。
+fn select_in_rows(rows: &Array2<f64>, better: fn(f64, f64) -> bool) -> Vec<usize> {
+    if rows.ncols() == 0 {
+        panic!("Cannot reduce over an empty dimension.");
+    }
+    rows.outer_iter()
+        .map(|row| {
+            let mut best = 0;
+            for (i, &x) in row.iter().enumerate() {
+                if row[best].is_nan() {
+                    break;
+                }
+                if x.is_nan() || better(x, row[best]) {
+                    best = i;
+                }
+            }
+            best
+        })
+        .collect()
+}
+
+fn greater(a: f64, b: f64) -> bool {
+    a > b
+}
+
+fn less(a: f64, b: f64) -> bool {
+    a < b
+}
+
+/// 每个输出元素对应的输入线性下标与被选中的值
+fn select(
+    data: &ArrayD<f64>,
+    dims: &[usize],
+    better: fn(f64, f64) -> bool,
+) -> (Vec<usize>, Vec<f64>) {
+    let rows = to_rows(data, dims);
+    let positions = select_in_rows(&rows, better);
+    let linear = row_indices(data.shape(), dims);
+    positions
+        .iter()
+        .enumerate()
+        .map(|(row, &col)| (linear[[row, col]], rows[[row, col]]))
+        .unzip()
+}
+
+/// 最大值/最小值归约的公共实现，记录被选中元素在输入中的线性下标
+#[derive(Debug)]
+struct Selection {
+    input_shape: Vec<usize>,
+    /// 每个输出元素对应的输入线性下标
+    indices: Vec<usize>,
+}
+
+impl Selection {
+    fn forward<O, M>(
+        op: &O,
+        input: &Tensor,
+        dims: &[usize],
+        keepdim: bool,
+        better: fn(f64, f64) -> bool,
+        make_op: M,
+    ) -> Tensor
+    where
+        O: Op + 'static,
+        M: FnOnce(Selection) -> O,
+    {
+        // 在 f64 下比较，结果保持输入的类型，f32 与整数的值都不会改变
+        let data = &input.data_f64();
+        let shape = data.shape().to_vec();
+        let (indices, values) = select(data, dims, better);
+        let output_shape = reduced_shape(&shape, dims, keepdim);
+        let result = Tensor::with_dtype(
+            ArrayD::from_shape_vec(IxDyn(&output_shape), values)
+                .expect("Failed to build reduction result"),
+            input.dtype(),
+        );
+
+        if needs_grad(&[input]) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(make_op(Selection {
+                input_shape: shape,
+                indices,
+            })));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(op, &[input], &result);
+        result
+    }
+
+    /// 切线取输入切线在被选中位置上的值
+    fn jvp(
+        input: &Tensor,
+        output: &Tensor,
+        tangents: &[Option<Tensor>],
+        dims: &[usize],
+        better: fn(f64, f64) -> bool,
+    ) -> Tensor {
+        let (indices, _) = select(&input.data_f64(), dims, better);
+        let tangent = tangents[0]
+            .as_ref()
+            .expect("Forward AD requires at least one tangent")
+            .data();
+        let tangent = tangent.as_standard_layout();
+        let flat = tangent.as_slice().expect("Standard layout is contiguous");
+        let values = indices.iter().map(|&index| flat[index]).collect();
+        Tensor::new(
+            ArrayD::from_shape_vec(IxDyn(&output.shape()), values)
+                .expect("Failed to build reduction tangent"),
+        )
+    }
+
+    /// 将输出梯度路由到被选中的输入位置，其余位置梯度为 0
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let numel = self.input_shape.iter().product();
+        let mut grad = vec![0.0; numel];
+        for (&index, &g) in self.indices.iter().zip(grad_output.iter()) {
+            grad[index] += g;
+        }
+        vec![ArrayD::from_shape_vec(IxDyn(&self.input_shape), grad).unwrap()]
+    }
+
+    /// 可微版本：把展平的输出梯度按线性下标累加到全零张量中
+    fn backward_graph(&self, grad: &Tensor) -> Vec<Tensor> {
+        let numel = self.input_shape.iter().product();
+        let index = ArrayD::from_shape_vec(IxDyn(&[self.indices.len()]), self.indices.clone())
+            .expect("Failed to build selection index");
+        let grad = grad
+            .reshape(&[self.indices.len()])
+            .expect("Failed to flatten gradient");
+        let scattered = Scatter::new(0, index, true).forward(&[&Tensor::zeros(&[numel]), &grad]);
+        let grad = scattered
+            .reshape(&self.input_shape)
+            .expect("Failed to restore gradient shape");
+        vec![grad]
+    }
+}
+
+/// 沿指定维度取最大值，梯度只传给被选中的元素
+#[derive(Debug)]
+pub struct Max {
+    dims: Vec<usize>,
+    keepdim: bool,
+    selection: Option<Selection>,
+}
+
+impl Max {
+    pub fn new(ndim: usize, dims: &[usize], keepdim: bool) -> Self {
+        Max {
+            dims: normalize_dims(dims, ndim),
+            keepdim,
+            selection: None,
+        }
+    }
+}
+
+impl Op for Max {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Max expects exactly one input tensor");
+        let (dims, keepdim) = (self.dims.clone(), self.keepdim);
+        Selection::forward(self, inputs[0], &self.dims, self.keepdim, greater, |s| {
+            Max {
+                dims,
+                keepdim,
+                selection: Some(s),
+            }
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        self.selection
+            .as_ref()
+            .expect("selection is None in backward")
+            .backward(parent)
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        self.selection
+            .as_ref()
+            .expect("selection is None in backward")
+            .backward_graph(grad)
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        Selection::jvp(inputs[0], output, tangents, &self.dims, greater)
+    }
+}
+
+/// 沿指定维度取最小值，梯度只传给被选中的元素
+#[derive(Debug)]
+pub struct Min {
+    dims: Vec<usize>,
+    keepdim: bool,
+    selection: Option<Selection>,
+}
+
+impl Min {
+    pub fn new(ndim: usize, dims: &[usize], keepdim: bool) -> Self {
+        Min {
+            dims: normalize_dims(dims, ndim),
+            keepdim,
+            selection: None,
+        }
+    }
+}
+
+impl Op for Min {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Min expects exactly one input tensor");
+        let (dims, keepdim) = (self.dims.clone(), self.keepdim);
+        Selection::forward(self, inputs[0], &self.dims, self.keepdim, less, |s| Min {
+            dims,
+            keepdim,
+            selection: Some(s),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        self.selection
+            .as_ref()
+            .expect("selection is None in backward")
+            .backward(parent)
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        self.selection
+            .as_ref()
+            .expect("selection is None in backward")
+            .backward_graph(grad)
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        Selection::jvp(inputs[0], output, tangents, &self.dims, less)
+    }
+}
+
+/// argmax/argmin 的公共实现，返回被归约维度内的行主序下标（int64，不参与求导）
+fn arg_select(
+    tensor: &Tensor,
+    dims: &[usize],
+    keepdim: bool,
+    better: fn(f64, f64) -> bool,
+) -> Tensor {
+    let data = &tensor.data_f64();
+    let dims = normalize_dims(dims, data.ndim());
+    let positions = select_in_rows(&to_rows(data, &dims), better);
+    let output_shape = reduced_shape(data.shape(), &dims, keepdim);
+    let values: Vec<f64> = positions.into_iter().map(|p| p as f64).collect();
+    Tensor::with_dtype(
+        ArrayD::from_shape_vec(IxDyn(&output_shape), values)
+            .expect("Failed to build argmax result"),
+        DType::I64,
+    )
+}
+
+impl Tensor {
+    /// 所有元素中的最大值
+    pub fn max(&self) -> Tensor {
+        self.max_dims(&[], false)
+    }
+
+    /// 沿指定维度取最大值（空列表表示所有维度）
+    pub fn max_dims(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        Max::new(self.dim(), dims, keepdim).forward(&[self])
+    }
+
+    /// 所有元素中的最小值
+    pub fn min(&self) -> Tensor {
+        self.min_dims(&[], false)
+    }
+
+    /// 沿指定维度取最小值（空列表表示所有维度）
+    pub fn min_dims(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        Min::new(self.dim(), dims, keepdim).forward(&[self])
+    }
+
+    /// 沿指定维度取最大值的下标。
+    ///
+    /// 对多个维度归约时，下标为被归约维度展平后的行主序下标；
+    /// 空列表表示对整个张量展平后取下标。
+    pub fn argmax(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        arg_select(self, dims, keepdim, |a, b| a > b)
+    }
+
+    /// 沿指定维度取最小值的下标，约定同 `argmax`
+    pub fn argmin(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        arg_select(self, dims, keepdim, |a, b| a < b)
+    }
+}
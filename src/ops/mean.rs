@@ -1,17 +1,43 @@
-use super::Op;
+use super::adjoint::Adjoint;
+use super::reduce::{expand_grad, normalize_dims, reduced_count, reduced_shape, to_rows};
+use super::{Op, linear_jvp, map_data, needs_grad, propagate_tangent};
 use crate::tensor::Tensor;
-use ndarray::{Array, ArrayD, IxDyn};
-use std::rc::Rc;
+use ndarray::{ArrayD, Axis, IxDyn, NdFloat};
+use std::sync::Arc;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Mean {
     /// The shape of the input tensor, saved for the backward pass.
     input_shape: Vec<usize>,
+    /// The dimensions being reduced (all of them by default).
+    dims: Vec<usize>,
+    /// Whether reduced dimensions are kept with size 1.
+    keepdim: bool,
 }
 
 impl Mean {
     pub fn new(input_shape: Vec<usize>) -> Self {
-        Mean { input_shape }
+        Mean::with_dims(input_shape, &[], false)
+    }
+
+    /// Creates a mean over the given dimensions. An empty list reduces all of them.
+    pub fn with_dims(input_shape: Vec<usize>, dims: &[usize], keepdim: bool) -> Self {
+        let dims = normalize_dims(dims, input_shape.len());
+        Mean {
+            input_shape,
+            dims,
+            keepdim,
+        }
+    }
+
+    /// Reduces every output position over its row of reduced elements.
+    fn apply<A: NdFloat>(&self, data: &ArrayD<A>) -> ArrayD<A> {
+        let output_shape = reduced_shape(data.shape(), &self.dims, self.keepdim);
+        let rows = to_rows(data, &self.dims);
+        let count = A::from(rows.ncols()).expect("Element count fits in a float");
+        (rows.sum_axis(Axis(1)) / count)
+            .into_shape_with_order(IxDyn(&output_shape))
+            .expect("Failed to reshape mean result")
     }
 }
 
@@ -22,29 +48,33 @@ impl Op for Mean {
             panic!("Mean operation takes exactly one input.");
         }
         let input = &inputs[0];
-        let data = &input.0.borrow().data;
-
-        // Calculate the mean. ndarray's mean() returns Option<f32>.
-        let mean_val = data
-            .mean()
-            .expect("Cannot compute mean of an empty tensor.");
+        if reduced_count(&input.shape(), &self.dims) == 0 {
+            panic!("Cannot compute mean of an empty tensor.");
+        }
 
-        // Create a true scalar (0-dim) tensor
-        let result_data = Array::from_elem(IxDyn(&[]), mean_val).into_dyn();
-        let result = Tensor::new(result_data);
+        // Integer and bool inputs are averaged as float32; float inputs keep their dtype.
+        let result = map_data(
+            input,
+            input.dtype().to_floating(),
+            |data| self.apply(&data),
+            |data| self.apply(&data),
+        );
 
         // Set up the computation graph for backpropagation.
-        if input.0.borrow().requires_grad {
-            // For a scalar tensor, we need a scalar gradient
-            result.0.borrow_mut().grad = Some(ArrayD::from_elem(IxDyn(&[]), 1.0));
-            let op = Mean::new(input.shape());
-            result.0.borrow_mut().set_creator(Rc::new(op));
+        if needs_grad(&[input]) {
+            let op = Mean {
+                input_shape: input.shape(),
+                dims: self.dims.clone(),
+                keepdim: self.keepdim,
+            };
+            result.0.borrow_mut().set_creator(Arc::new(op));
             result.0.borrow_mut().add_parent(input);
             result.0.borrow_mut().requires_grad = true;
         } else {
             // Explicitly set requires_grad to false if input doesn't require grad
             result.0.borrow_mut().requires_grad = false;
         }
+        propagate_tangent(self, inputs, &result);
         result
     }
 
@@ -59,22 +89,26 @@ impl Op for Mean {
             .expect("Gradient not found in backward pass")
             .clone();
 
-        let grad_output_scalar = grad_output.iter().next().cloned().unwrap_or(1.0);
-
-        // The number of elements in the original input tensor.
-        let num_elements = self.input_shape.iter().product::<usize>() as f32;
+        // The number of elements averaged into each output element.
+        let num_elements = reduced_count(&self.input_shape, &self.dims) as f32;
         if num_elements == 0.0 {
             return vec![ArrayD::zeros(IxDyn(&self.input_shape))];
         }
 
-        // The gradient for each element of the input is grad_output / N.
-        let grad_val = grad_output_scalar / num_elements;
-
-        // Create the gradient array for the input, with the same shape as the original input.
-        let grad_input = ArrayD::from_elem(IxDyn(&self.input_shape), grad_val);
+        // The gradient for each element of the input is grad_output / N,
+        // broadcast back over the reduced dimensions.
+        let grad_input = expand_grad(&grad_output, &self.input_shape, &self.dims) / num_elements;
 
         vec![grad_input]
     }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
 }
 
 /// Functional interface for the mean operation.
@@ -83,9 +117,20 @@ pub fn mean(tensor: &Tensor) -> Tensor {
     op.forward(&[tensor])
 }
 
+/// Functional interface for the mean over the given dimensions.
+pub fn mean_dims(tensor: &Tensor, dims: &[usize], keepdim: bool) -> Tensor {
+    let op = Mean::with_dims(tensor.shape(), dims, keepdim);
+    op.forward(&[tensor])
+}
+
 impl Tensor {
     /// Computes the mean of the tensor.
     pub fn mean(&self) -> Tensor {
         mean(self)
     }
+
+    /// Computes the mean over the given dimensions (an empty list means all of them).
+    pub fn mean_dims(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        mean_dims(self, dims, keepdim)
+    }
 }
@@ -1,16 +1,173 @@
 pub mod add;
+pub mod adjoint;
+pub mod broadcast;
+pub mod cast;
+pub mod complex;
+pub mod cross_entropy;
+pub mod div;
+pub mod einsum;
+pub mod index;
+pub mod logsumexp;
 pub mod matmul;
+pub mod max;
 pub mod mean;
 pub mod mul;
+pub mod neg;
+pub mod pow;
+pub mod prod;
+pub mod reduce;
 pub mod relu;
+pub mod shape;
+pub mod softmax;
+pub mod sub;
+pub mod sum;
+pub mod unary;
+use crate::autograd::forward_ad::{set_tangent, tangent_of};
+use crate::autograd::{is_grad_enabled, no_grad};
+use crate::dtype::DType;
 use crate::tensor::Tensor;
 use ndarray::ArrayD;
 use std::fmt::Debug;
 
-pub trait Op: Debug {
+pub trait Op: Debug + Send + Sync {
+    /// 运算的名称，用于错误信息与计算图可视化
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
+
     /// 前向传播
     fn forward(&self, inputs: &[&Tensor]) -> Tensor;
 
     /// 反向传播（返回输入梯度）
     fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>>;
+
+    /// 用可微运算构建反向传播，供 `autograd::grad` 的 `create_graph` 使用。
+    ///
+    /// `inputs` 为前向的输入（即计算图中的父节点），`output` 为前向的结果，
+    /// `grad` 为输出梯度。返回的梯度会记录计算图，因此可以继续求导得到高阶导数。
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let _ = (inputs, output, grad);
+        panic!(
+            "{} does not support create_graph",
+            std::any::type_name::<Self>()
+        );
+    }
+
+    /// 前向模式求导：由输入的切线计算输出的切线，即雅可比-向量积。
+    ///
+    /// `inputs` 和 `output` 是与计算图断开的原值，`tangents` 与 `inputs` 一一对应，
+    /// 没有切线的输入为 `None`（至少有一个不是）。调用时梯度记录已关闭。
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        let _ = (inputs, output, tangents);
+        panic!(
+            "{} does not support forward AD",
+            std::any::type_name::<Self>()
+        );
+    }
+}
+
+/// 按结果类型 `dtype` 变换输入的数据：结果为 f32 时在 f32 下计算，
+/// 否则在 f64 下计算再转换为 `dtype`，与 `broadcast::broadcast_binary` 的规则相同
+pub fn map_data(
+    input: &Tensor,
+    dtype: DType,
+    f32_op: impl FnOnce(ArrayD<f32>) -> ArrayD<f32>,
+    f64_op: impl FnOnce(ArrayD<f64>) -> ArrayD<f64>,
+) -> Tensor {
+    if dtype == DType::F32 {
+        Tensor::new(f32_op(input.data()))
+    } else {
+        Tensor::with_dtype(f64_op(input.data_f64()), dtype)
+    }
+}
+
+/// 只在 f32 下实现的运算检查输入的类型，其他类型直接报错，而不是悄悄转换为 f32
+pub fn expect_f32(inputs: &[&Tensor], name: &str) {
+    for input in inputs {
+        let dtype = input.dtype();
+        assert!(
+            dtype == DType::F32,
+            "{} only supports float32 tensors, got {}; convert with to_dtype(DType::F32) first",
+            name,
+            dtype
+        );
+    }
+}
+
+/// 是否需要为这次运算记录计算图：梯度模式开启且至少一个输入需要梯度
+pub fn needs_grad(inputs: &[&Tensor]) -> bool {
+    is_grad_enabled() && inputs.iter().any(|t| t.0.borrow().requires_grad)
+}
+
+/// 前向计算结束后传播切线：处于对偶层级且有输入带切线时，用 `Op::jvp` 算出结果的切线
+pub fn propagate_tangent<O: Op + ?Sized>(op: &O, inputs: &[&Tensor], output: &Tensor) {
+    let tangents: Vec<Option<Tensor>> = inputs.iter().map(|input| tangent_of(input)).collect();
+    if tangents.iter().all(Option::is_none) {
+        return;
+    }
+    let _guard = no_grad();
+    let primals: Vec<Tensor> = inputs.iter().map(|input| input.detach()).collect();
+    let primals: Vec<&Tensor> = primals.iter().collect();
+    let tangent = op.jvp(&primals, &output.detach(), &tangents);
+    assert_eq!(
+        tangent.shape(),
+        output.shape(),
+        "Tangent shape does not match the output of {:?}",
+        op
+    );
+    set_tangent(output, &tangent);
+}
+
+/// 线性运算的切线：把各输入的切线（没有的视为 0）代入运算本身
+pub fn linear_jvp<O: Op + ?Sized>(
+    op: &O,
+    inputs: &[&Tensor],
+    tangents: &[Option<Tensor>],
+) -> Tensor {
+    let tangents: Vec<Tensor> = inputs
+        .iter()
+        .zip(tangents)
+        .map(|(input, tangent)| tangent.clone().unwrap_or_else(|| input.zeros_like()))
+        .collect();
+    op.forward(&tangents.iter().collect::<Vec<_>>())
+}
+
+/// 对每个输入分别线性的运算（逐元素乘法、矩阵乘法等）的切线：
+/// 依次把一个输入替换为它的切线代入运算，再把结果相加
+pub fn multilinear_jvp<O: Op + ?Sized>(
+    op: &O,
+    inputs: &[&Tensor],
+    tangents: &[Option<Tensor>],
+) -> Tensor {
+    let terms = tangents.iter().enumerate().map(|(i, tangent)| {
+        tangent.as_ref().map(|tangent| {
+            let mut operands = inputs.to_vec();
+            operands[i] = tangent;
+            op.forward(&operands)
+        })
+    });
+    sum_tangents(terms)
+}
+
+/// 把各输入贡献的切线相加，跳过没有切线的输入
+pub fn sum_tangents(terms: impl IntoIterator<Item = Option<Tensor>>) -> Tensor {
+    terms
+        .into_iter()
+        .flatten()
+        .reduce(|total, term| &total + &term)
+        .expect("Forward AD requires at least one tangent")
+}
+
+/// 单输入逐元素运算的切线：雅可比矩阵是对角的，与把切线当作输出梯度反向传播相同
+pub fn elementwise_jvp<O: Op + ?Sized>(
+    op: &O,
+    inputs: &[&Tensor],
+    output: &Tensor,
+    tangents: &[Option<Tensor>],
+) -> Tensor {
+    let tangent = tangents[0]
+        .as_ref()
+        .expect("Forward AD requires at least one tangent");
+    let inputs: Vec<Tensor> = inputs.iter().map(|&input| input.clone()).collect();
+    op.backward_graph(&inputs, output, tangent).remove(0)
 }
@@ -0,0 +1,58 @@
+use crate::ops::{Op, linear_jvp, map_data, needs_grad, propagate_tangent};
+use crate::tensor::Tensor;
+use ndarray::ArrayD;
+use std::ops::Neg;
+use std::sync::Arc;
+
+/// 取负操作。
+#[derive(Debug)]
+pub struct Negate;
+
+impl Negate {
+    pub fn new() -> Self {
+        Negate
+    }
+}
+
+impl Default for Negate {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Op for Negate {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Negate expects exactly one input tensor");
+        let input = inputs[0];
+        let result = map_data(input, input.dtype(), |data| -data, |data| -data);
+        if needs_grad(&[input]) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(Negate::new()));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = parent.0.borrow().grad.clone().expect("Gradient not found");
+        vec![grad.mapv(|g| -g)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![-grad]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+impl Neg for &Tensor {
+    type Output = Tensor;
+
+    fn neg(self) -> Tensor {
+        Negate::new().forward(&[self])
+    }
+}
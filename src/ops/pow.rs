@@ -0,0 +1,217 @@
+use crate::ops::broadcast::{
+    binary_result_type, broadcast_arrays, broadcast_binary, sum_to, sum_to_shape,
+};
+use crate::ops::{Op, elementwise_jvp, map_data, needs_grad, propagate_tangent, sum_tangents};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Zip};
+use std::sync::Arc;
+
+/// 逐元素幂运算 `a^b`，指数为张量，支持广播。
+#[derive(Debug)]
+pub struct Pow {
+    a_data: Option<SavedTensor>,
+    b_data: Option<SavedTensor>,
+}
+
+impl Pow {
+    pub fn new() -> Self {
+        Pow {
+            a_data: None,
+            b_data: None,
+        }
+    }
+}
+
+impl Default for Pow {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Op for Pow {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.len() != 2 {
+            panic!("Pow requires exactly two input tensors");
+        }
+        let dtype = binary_result_type(inputs[0], inputs[1]).to_floating();
+        let result = broadcast_binary(
+            inputs[0],
+            inputs[1],
+            dtype,
+            |x, y| x.powf(y),
+            |x, y| x.powf(y),
+        );
+
+        if needs_grad(&[inputs[0], inputs[1]]) {
+            let op = Pow {
+                a_data: Some(SavedTensor::new(inputs[0])),
+                b_data: Some(SavedTensor::new(inputs[1])),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(inputs[0]);
+            result_data.add_parent(inputs[1]);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .as_ref()
+            .expect("Gradient not found in backward pass")
+            .clone();
+        let a_data = &self
+            .a_data
+            .as_ref()
+            .expect("a_data is None in backward")
+            .unpack();
+        let b_data = &self
+            .b_data
+            .as_ref()
+            .expect("b_data is None in backward")
+            .unpack();
+        let (a_broadcast, b_broadcast) = broadcast_arrays(a_data, b_data);
+
+        // ∂L/∂a = ∂L/∂c * b * a^(b-1)
+        let grad_a = Zip::from(&grad_output)
+            .and(&a_broadcast)
+            .and(&b_broadcast)
+            .map_collect(|&g, &x, &y| g * y * x.powf(y - 1.0));
+
+        // ∂L/∂b = ∂L/∂c * a^b * ln(a)，a = 0 时约定梯度为 0
+        let grad_b = Zip::from(&grad_output)
+            .and(&a_broadcast)
+            .and(&b_broadcast)
+            .map_collect(|&g, &x, &y| {
+                if x == 0.0 {
+                    0.0
+                } else {
+                    g * x.powf(y) * x.ln()
+                }
+            });
+
+        vec![
+            sum_to_shape(&grad_a, a_data.shape()),
+            sum_to_shape(&grad_b, b_data.shape()),
+        ]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let (a, b) = (&inputs[0], &inputs[1]);
+        let grad_a = &(grad * b) * &a.pow(&(b - 1.0_f32));
+        // a = 0 处把 ln(a) 换成 ln(1) = 0，使梯度为 0 而不是 NaN
+        let zero_mask = Tensor::new(a.data().mapv(|x| if x == 0.0 { 1.0 } else { 0.0 }));
+        let grad_b = &(grad * output) * &(a + &zero_mask).log();
+        vec![sum_to(&grad_a, &a.shape()), sum_to(&grad_b, &b.shape())]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // d(a^b) = b·a^(b-1)·da + a^b·ln(a)·db，a = 0 处与反向传播一样约定 ln(a) 的贡献为 0
+        let (a, b) = (inputs[0], inputs[1]);
+        let da = tangents[0]
+            .as_ref()
+            .map(|da| &(da * b) * &a.pow(&(b - 1.0_f32)));
+        let db = tangents[1].as_ref().map(|db| {
+            let zero_mask = Tensor::new(a.data().mapv(|x| if x == 0.0 { 1.0 } else { 0.0 }));
+            &(db * output) * &(a + &zero_mask).log()
+        });
+        sum_tangents([da, db])
+    }
+}
+
+/// 逐元素幂运算 `a^p`，指数为常数。
+#[derive(Debug)]
+pub struct PowScalar {
+    exponent: f32,
+    input_data: Option<SavedTensor>,
+}
+
+impl PowScalar {
+    pub fn new(exponent: f32) -> Self {
+        PowScalar {
+            exponent,
+            input_data: None,
+        }
+    }
+}
+
+impl Op for PowScalar {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "PowScalar expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let exponent = self.exponent;
+        let result = map_data(
+            input,
+            input.dtype().to_floating(),
+            |data| data.mapv(|x| x.powf(exponent)),
+            |data| data.mapv(|x| x.powf(exponent as f64)),
+        );
+        if needs_grad(&[input]) {
+            let op = PowScalar {
+                exponent,
+                input_data: Some(SavedTensor::new(input)),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent.0.borrow().grad.clone().expect("Gradient not found");
+        let input = &self
+            .input_data
+            .as_ref()
+            .expect("input_data is None in backward")
+            .unpack();
+        let exponent = self.exponent;
+        // ∂L/∂a = ∂L/∂c * p * a^(p-1)
+        let grad = Zip::from(&grad_output)
+            .and(input)
+            .map_collect(|&g, &x| g * exponent * x.powf(exponent - 1.0));
+        vec![grad]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let x = &inputs[0];
+        vec![&(grad * self.exponent) * &x.powf(self.exponent - 1.0)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 逐元素幂运算，指数为张量。
+pub fn pow(base: &Tensor, exponent: &Tensor) -> Tensor {
+    Pow::new().forward(&[base, exponent])
+}
+
+/// 逐元素幂运算，指数为常数。
+pub fn powf(base: &Tensor, exponent: f32) -> Tensor {
+    PowScalar::new(exponent).forward(&[base])
+}
+
+impl Tensor {
+    /// 逐元素幂运算，指数为张量（支持广播）
+    pub fn pow(&self, exponent: &Tensor) -> Tensor {
+        pow(self, exponent)
+    }
+
+    /// 逐元素幂运算，指数为常数
+    pub fn powf(&self, exponent: f32) -> Tensor {
+        powf(self, exponent)
+    }
+}
@@ -0,0 +1,156 @@
+use super::reduce::{accumulate_dtype, normalize_dims, reduced_shape, row_indices, to_rows};
+use super::{Op, map_data, needs_grad, propagate_tangent};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Axis, IxDyn, NdFloat};
+use std::sync::Arc;
+
+/// 沿指定维度求积
+#[derive(Debug)]
+pub struct Prod {
+    dims: Vec<usize>,
+    keepdim: bool,
+    input_data: Option<SavedTensor>,
+}
+
+impl Prod {
+    pub fn new(ndim: usize, dims: &[usize], keepdim: bool) -> Self {
+        Prod {
+            dims: normalize_dims(dims, ndim),
+            keepdim,
+            input_data: None,
+        }
+    }
+
+    fn apply<A: NdFloat>(&self, data: &ArrayD<A>) -> ArrayD<A> {
+        let output_shape = reduced_shape(data.shape(), &self.dims, self.keepdim);
+        to_rows(data, &self.dims)
+            .map_axis(Axis(1), |row| row.product())
+            .into_shape_with_order(IxDyn(&output_shape))
+            .expect("Failed to reshape prod result")
+    }
+}
+
+impl Op for Prod {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Prod expects exactly one input tensor");
+        let input = inputs[0];
+        let dtype = accumulate_dtype(input.dtype());
+        let result = map_data(
+            input,
+            dtype,
+            |data| self.apply(&data),
+            |data| self.apply(&data),
+        );
+
+        if needs_grad(&[input]) {
+            let op = Prod {
+                dims: self.dims.clone(),
+                keepdim: self.keepdim,
+                input_data: Some(SavedTensor::new(input)),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let input = &self
+            .input_data
+            .as_ref()
+            .expect("input_data is None in backward")
+            .unpack();
+
+        // ∂(∏x)/∂x_i 为其余元素之积。用前缀积与后缀积计算，避免除以 0。
+        let rows = to_rows(input, &self.dims);
+        let linear = row_indices(input.shape(), &self.dims);
+        let mut grad = vec![0.0; input.len()];
+        for ((row, indices), &g) in rows
+            .outer_iter()
+            .zip(linear.outer_iter())
+            .zip(grad_output.iter())
+        {
+            let others = exclusive_products(&row.to_vec());
+            for (&index, other) in indices.iter().zip(others) {
+                grad[index] += g * other;
+            }
+        }
+        vec![ArrayD::from_shape_vec(IxDyn(input.shape()), grad).unwrap()]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        // ∂(∏x)/∂x_i = ∏x / x_i，输入含 0 时无法用可微运算这样表示
+        let x = &inputs[0];
+        if x.data().iter().any(|&v| v == 0.0) {
+            panic!("Prod does not support create_graph when the input contains zeros");
+        }
+        let keep_shape = reduced_shape(&x.shape(), &self.dims, true);
+        let grad = grad
+            .reshape(&keep_shape)
+            .expect("Failed to reshape gradient");
+        let output = output
+            .reshape(&keep_shape)
+            .expect("Failed to reshape output");
+        vec![&(&grad * &output) / x]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // d(∏x) = Σ_i (∏_{j≠i} x_j)·dx_i，同样用前缀积与后缀积避免除以 0
+        let tangent = tangents[0]
+            .as_ref()
+            .expect("Forward AD requires at least one tangent")
+            .data();
+        let rows = to_rows(&inputs[0].data(), &self.dims);
+        let tangent_rows = to_rows(&tangent, &self.dims);
+        let values = rows
+            .outer_iter()
+            .zip(tangent_rows.outer_iter())
+            .map(|(row, tangent)| {
+                let others = exclusive_products(&row.to_vec());
+                others.iter().zip(tangent).map(|(p, t)| p * t).sum()
+            })
+            .collect();
+        Tensor::new(
+            ArrayD::from_shape_vec(IxDyn(&output.shape()), values)
+                .expect("Failed to reshape prod tangent"),
+        )
+    }
+}
+
+/// 对每个位置计算除自身外所有元素的乘积
+fn exclusive_products(values: &[f32]) -> Vec<f32> {
+    let mut result = vec![1.0; values.len()];
+    let mut prefix = 1.0;
+    for (r, &v) in result.iter_mut().zip(values) {
+        *r = prefix;
+        prefix *= v;
+    }
+    let mut suffix = 1.0;
+    for (r, &v) in result.iter_mut().zip(values).rev() {
+        *r *= suffix;
+        suffix *= v;
+    }
+    result
+}
+
+impl Tensor {
+    /// 所有元素的乘积
+    pub fn prod(&self) -> Tensor {
+        self.prod_dims(&[], false)
+    }
+
+    /// 沿指定维度求积（空列表表示所有维度）
+    pub fn prod_dims(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        Prod::new(self.dim(), dims, keepdim).forward(&[self])
+    }
+}
@@ -0,0 +1,118 @@
+//! 按维度归约的辅助函数，供 sum/mean/max/min/prod/logsumexp 等归约运算共用。
+//!
+//! 约定：维度列表为空时表示对所有维度归约。
+
+use crate::dtype::DType;
+use crate::tensor::Tensor;
+use ndarray::{Array2, ArrayD, IxDyn};
+
+/// 规范化归约维度：空列表展开为全部维度，排序并去重。
+///
+/// 如果维度超出范围，则 panic。
+pub fn normalize_dims(dims: &[usize], ndim: usize) -> Vec<usize> {
+    if dims.is_empty() {
+        return (0..ndim).collect();
+    }
+    let mut dims = dims.to_vec();
+    for &d in &dims {
+        if d >= ndim {
+            panic!(
+                "Dimension out of range: dim {} for tensor with {} dims",
+                d, ndim
+            );
+        }
+    }
+    dims.sort_unstable();
+    dims.dedup();
+    dims
+}
+
+/// 归约后的形状。`keepdim` 为真时被归约的维度保留为 1。
+pub fn reduced_shape(shape: &[usize], dims: &[usize], keepdim: bool) -> Vec<usize> {
+    shape
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &s)| {
+            if !dims.contains(&i) {
+                Some(s)
+            } else if keepdim {
+                Some(1)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// 求和与求积的结果类型：浮点与复数类型保持不变，整数与布尔得到 i64，与 PyTorch 一致
+pub fn accumulate_dtype(dtype: DType) -> DType {
+    if dtype.to_floating() == dtype {
+        dtype
+    } else {
+        DType::I64
+    }
+}
+
+/// 被归约维度中元素的个数
+pub fn reduced_count(shape: &[usize], dims: &[usize]) -> usize {
+    dims.iter().map(|&d| shape[d]).product()
+}
+
+/// 将归约结果的梯度广播回输入形状。
+///
+/// 梯度先被重塑为 keepdim 形状，再沿被归约的维度广播。
+pub fn expand_grad(grad: &ArrayD<f32>, input_shape: &[usize], dims: &[usize]) -> ArrayD<f32> {
+    let keep_shape = reduced_shape(input_shape, dims, true);
+    let grad = grad
+        .to_shape(IxDyn(&keep_shape))
+        .expect("Gradient shape does not match reduction output");
+    grad.broadcast(IxDyn(input_shape))
+        .expect("Failed to broadcast gradient to input shape")
+        .to_owned()
+}
+
+/// 将数组重排为二维：每一行对应一个输出位置，行内为被归约的元素。
+///
+/// 行按保留维度的行主序排列，与归约结果的元素顺序一致；
+/// 行内元素按被归约维度的行主序排列。
+pub fn to_rows<T: Clone>(data: &ArrayD<T>, dims: &[usize]) -> Array2<T> {
+    let ndim = data.ndim();
+    let kept: Vec<usize> = (0..ndim).filter(|d| !dims.contains(d)).collect();
+    let mut order = kept.clone();
+    order.extend_from_slice(dims);
+
+    let outer: usize = kept.iter().map(|&d| data.shape()[d]).product();
+    let inner = reduced_count(data.shape(), dims);
+    let permuted = data.view().permuted_axes(IxDyn(&order));
+    let values: Vec<T> = permuted.iter().cloned().collect();
+    Array2::from_shape_vec((outer, inner), values).expect("Failed to reshape into rows")
+}
+
+/// 与 `to_rows` 对应的输入线性下标，用于把按行选出的元素映射回输入位置
+pub fn row_indices(shape: &[usize], dims: &[usize]) -> Array2<usize> {
+    let numel = shape.iter().product();
+    let linear = ArrayD::from_shape_vec(IxDyn(shape), (0..numel).collect())
+        .expect("Failed to build index array");
+    to_rows(&linear, dims)
+}
+
+impl Tensor {
+    /// 沿指定维度计算方差。
+    ///
+    /// `unbiased` 为真时使用贝塞尔校正（除以 N - 1）。
+    pub fn var(&self, dims: &[usize], unbiased: bool, keepdim: bool) -> Tensor {
+        let dims = normalize_dims(dims, self.dim());
+        let count = reduced_count(&self.shape(), &dims);
+        let correction = if unbiased { 1 } else { 0 };
+        let divisor = count.saturating_sub(correction) as f32;
+
+        let mean = self.mean_dims(&dims, true);
+        let diff = self - &mean;
+        &(&diff * &diff).sum_dims(&dims, keepdim) / divisor
+    }
+
+    /// 沿指定维度计算标准差。
+    pub fn std(&self, dims: &[usize], unbiased: bool, keepdim: bool) -> Tensor {
+        self.var(dims, unbiased, keepdim).sqrt()
+    }
+}
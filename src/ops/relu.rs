@@ -1,6 +1,6 @@
-use std::rc::Rc;
+use std::sync::Arc;
 
-use crate::ops::Op;
+use crate::ops::{Op, elementwise_jvp, map_data, needs_grad, propagate_tangent};
 use crate::tensor::Tensor;
 
 #[derive(Debug)]
@@ -10,24 +10,44 @@ impl ReLU {
         ReLU
     }
 }
+
+impl Default for ReLU {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl Op for ReLU {
     fn forward(&self, inputs: &[&Tensor]) -> Tensor {
         assert!(inputs.len() == 1, "ReLU expects exactly one input tensor");
         let input = inputs[0];
-        let res = Tensor::new(input.0.borrow().data.mapv(|x| x.max(0.0)).into_dyn())
-            .require_grad(input.0.borrow().requires_grad);
-        let op = ReLU::new();
-        res.0.borrow_mut().set_creator(Rc::new(op));
-        if input.0.borrow().requires_grad {
-            // 如果输入需要梯度，则将当前操作添加为父节点
+        let res = map_data(
+            input,
+            input.dtype(),
+            |data| data.mapv(|x| x.max(0.0)),
+            |data| data.mapv(|x| x.max(0.0)),
+        );
+        if needs_grad(&[input]) {
+            // 如果输入需要梯度，则记录当前操作并将输入添加为父节点
+            res.0.borrow_mut().requires_grad = true;
+            res.0.borrow_mut().set_creator(Arc::new(ReLU::new()));
             res.0.borrow_mut().add_parent(input);
         }
+        propagate_tangent(self, inputs, &res);
         res
     }
 
     fn backward(&self, parent: &Tensor) -> Vec<ndarray::ArrayD<f32>> {
         let grad = parent.0.borrow().grad.clone().expect("Gradient not found");
-        let data = parent.0.borrow().data.clone();
+        let data = parent.data();
         vec![data.mapv(|x| if x > 0.0 { 1.0 } else { 0.0 }) * grad]
     }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let mask = output.data().mapv(|x| if x > 0.0 { 1.0 } else { 0.0 });
+        vec![grad * &Tensor::new(mask)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
 }
@@ -0,0 +1,915 @@
+//! 形状变换运算：reshape、permute、expand、repeat、cat、narrow、select、flip、roll。
+//!
+//! 这些运算只重新排列元素，反向传播时把梯度按相反的方式放回输入的位置。
+//! reshape、permute、expand、narrow、select 的结果是与输入共享存储的视图，不复制数据；
+//! 视图与 contiguous、需要复制的 reshape 保持输入的元素类型，也可用于复数张量。
+
+use super::adjoint::Adjoint;
+use super::broadcast::sum_to_shape;
+use super::{Op, linear_jvp, map_data, needs_grad, propagate_tangent};
+use crate::dtype::{DType, promote_types};
+use crate::storage::Layout;
+use crate::tensor::{Tensor, TensorCell};
+use ndarray::{ArrayD, Axis, Dimension, IxDyn, Slice, concatenate};
+use std::sync::Arc;
+
+/// 读取输出张量上的梯度
+fn output_grad(parent: &Tensor) -> ArrayD<f32> {
+    parent
+        .0
+        .borrow()
+        .grad
+        .clone()
+        .expect("Gradient not found in backward pass")
+}
+
+/// 用 `f32_op` 或 `f64_op` 变换输入的数据，保持输入的元素类型，输入需要梯度时记录反向传播所需的运算
+fn unary_result<O: Op + 'static>(
+    op: &O,
+    input: &Tensor,
+    f32_op: impl FnOnce(ArrayD<f32>) -> ArrayD<f32>,
+    f64_op: impl FnOnce(ArrayD<f64>) -> ArrayD<f64>,
+    make_op: impl FnOnce() -> O,
+) -> Tensor {
+    let output = map_data(input, input.dtype(), f32_op, f64_op);
+    track(op, input, output, make_op)
+}
+
+/// 复制输入的数据并重排为 `shape`，保持输入的元素类型，输入需要梯度时记录反向传播所需的运算
+fn copy_result<O: Op + 'static>(
+    op: &O,
+    input: &Tensor,
+    shape: &[usize],
+    make_op: impl FnOnce() -> O,
+) -> Tensor {
+    let output = if input.is_complex() {
+        Tensor::from_complex(reshape_array(input.data_complex(), shape), input.dtype())
+    } else {
+        Tensor::with_dtype(reshape_array(input.data_f64(), shape), input.dtype())
+    };
+    track(op, input, output, make_op)
+}
+
+/// 构造与输入共享存储、按 `layout` 解释数据的视图，输入需要梯度时记录反向传播所需的运算
+pub(crate) fn view_result<O: Op + 'static>(
+    op: &O,
+    input: &Tensor,
+    layout: Layout,
+    make_op: impl FnOnce() -> O,
+) -> Tensor {
+    let view = input.0.borrow().share_storage(layout);
+    track(op, input, Tensor(Arc::new(TensorCell::new(view))), make_op)
+}
+
+/// 记录反向传播所需的运算，并传播前向模式的切线；`op` 为执行前向计算的运算本身。
+///
+/// 复数输入的运算包装为 `ComplexView`，分别对梯度的实部与虚部反向传播
+fn track<O: Op + 'static>(
+    op: &O,
+    input: &Tensor,
+    result: Tensor,
+    make_op: impl FnOnce() -> O,
+) -> Tensor {
+    if needs_grad(&[input]) {
+        let creator: Arc<dyn Op> = if input.is_complex() {
+            Arc::new(ComplexView {
+                op: make_op(),
+                output_shape: result.shape(),
+            })
+        } else {
+            Arc::new(make_op())
+        };
+        let mut result_data = result.0.borrow_mut();
+        result_data.set_creator(creator);
+        result_data.add_parent(input);
+        result_data.requires_grad = true;
+    }
+    propagate_tangent(op, &[input], &result);
+    result
+}
+
+/// 复数张量上的形状变换。
+///
+/// 形状变换是线性的且不混合实部与虚部，所以把梯度拆成实部与虚部，
+/// 分别交给实数的反向传播，再合成 `view_as_real` 形状的梯度
+#[derive(Debug)]
+struct ComplexView<O> {
+    op: O,
+    output_shape: Vec<usize>,
+}
+
+impl<O: Op> Op for ComplexView<O> {
+    fn name(&self) -> &'static str {
+        self.op.name()
+    }
+
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        self.op.forward(inputs)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = output_grad(parent);
+        let last = Axis(self.output_shape.len());
+        let parts: Vec<ArrayD<f32>> = grad
+            .axis_iter(last)
+            .map(|part| {
+                // 借一个临时节点承载实部或虚部的梯度，复用实数的反向传播
+                let carrier = Tensor::zeros(&self.output_shape);
+                carrier.0.borrow_mut().grad = Some(part.to_owned());
+                self.op.backward(&carrier).remove(0)
+            })
+            .collect();
+        let views: Vec<_> = parts.iter().map(|part| part.view()).collect();
+        vec![ndarray::stack(Axis(parts[0].ndim()), &views).expect("Failed to stack gradient")]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let imag = grad.imag().expect("Complex view has a complex gradient");
+        let real = self
+            .op
+            .backward_graph(inputs, output, &grad.real())
+            .remove(0);
+        let imag = self.op.backward_graph(inputs, output, &imag).remove(0);
+        vec![Tensor::complex(&real, &imag).expect("Gradient parts have the same shape")]
+    }
+}
+
+/// 按行主序重排数组的形状（非连续的数组先复制为标准布局）
+fn reshape_array<A: Clone>(data: ArrayD<A>, shape: &[usize]) -> ArrayD<A> {
+    data.as_standard_layout()
+        .into_owned()
+        .into_shape_with_order(IxDyn(shape))
+        .unwrap_or_else(|_| panic!("Cannot reshape tensor to {:?}", shape))
+}
+
+/// 改变形状而不改变元素的行主序排列，view、reshape、squeeze、unsqueeze、flatten 共用
+#[derive(Debug)]
+pub struct Reshape {
+    shape: Vec<usize>,
+    input_shape: Vec<usize>,
+}
+
+impl Reshape {
+    pub fn new(shape: &[usize]) -> Self {
+        Reshape {
+            shape: shape.to_vec(),
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Reshape {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "Reshape expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let input_shape = input.shape();
+        if input.numel() != self.shape.iter().product::<usize>() {
+            panic!(
+                "Cannot reshape tensor of shape {:?} to {:?}",
+                input_shape, self.shape
+            );
+        }
+        let make_op = || Reshape {
+            shape: self.shape.clone(),
+            input_shape: input_shape.clone(),
+        };
+        // 内存布局允许时返回视图，否则复制
+        let layout = input.0.borrow().layout().view(&self.shape);
+        match layout {
+            Some(layout) => view_result(self, input, layout, make_op),
+            None => copy_result(self, input, &self.shape, make_op),
+        }
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![reshape_array(output_grad(parent), &self.input_shape)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let grad = grad
+            .reshape(&self.input_shape)
+            .expect("Failed to reshape gradient");
+        vec![grad]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 把数据复制为行主序连续存放，梯度原样传回
+#[derive(Debug)]
+pub struct Contiguous;
+
+impl Op for Contiguous {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "Contiguous expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        copy_result(self, input, &input.shape(), || Contiguous)
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![output_grad(parent)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad.clone()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 按给定顺序重排维度，transpose 是交换两个维度的特例
+#[derive(Debug)]
+pub struct Permute {
+    axes: Vec<usize>,
+}
+
+impl Permute {
+    pub fn new(axes: &[usize]) -> Self {
+        Permute {
+            axes: axes.to_vec(),
+        }
+    }
+
+    /// 逆排列：输出的第 i 维来自输入的第 axes[i] 维
+    fn inverse(&self) -> Vec<usize> {
+        let mut inverse = vec![0; self.axes.len()];
+        for (i, &axis) in self.axes.iter().enumerate() {
+            inverse[axis] = i;
+        }
+        inverse
+    }
+}
+
+impl Op for Permute {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "Permute expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        let layout = input.0.borrow().layout().permute(&self.axes);
+        view_result(self, input, layout, || Permute::new(&self.axes))
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = output_grad(parent)
+            .permuted_axes(IxDyn(&self.inverse()))
+            .as_standard_layout()
+            .into_owned();
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Permute::new(&self.inverse()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 把大小为 1 的维度（或新增的前导维度）广播到指定形状
+#[derive(Debug, Clone)]
+pub struct Expand {
+    shape: Vec<usize>,
+    input_shape: Vec<usize>,
+}
+
+impl Expand {
+    pub fn new(shape: &[usize]) -> Self {
+        Expand {
+            shape: shape.to_vec(),
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Expand {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Expand expects exactly one input tensor");
+        let input = inputs[0];
+        let input_shape = input.shape();
+        let compatible = self.shape.len() >= input_shape.len()
+            && input_shape
+                .iter()
+                .rev()
+                .zip(self.shape.iter().rev())
+                .all(|(&from, &to)| from == to || from == 1);
+        if !compatible {
+            panic!(
+                "Cannot expand tensor of shape {:?} to {:?}",
+                input_shape, self.shape
+            );
+        }
+        let layout = input.0.borrow().layout().expand(&self.shape);
+        view_result(self, input, layout, || Expand {
+            shape: self.shape.clone(),
+            input_shape,
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![sum_to_shape(&output_grad(parent), &self.input_shape)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿各维度平铺重复，语义同 `torch.Tensor.repeat`
+#[derive(Debug, Clone)]
+pub struct Repeat {
+    repeats: Vec<usize>,
+    input_shape: Vec<usize>,
+}
+
+impl Repeat {
+    pub fn new(repeats: &[usize]) -> Self {
+        Repeat {
+            repeats: repeats.to_vec(),
+            input_shape: Vec::new(),
+        }
+    }
+
+    /// 在前面补 1，使输入形状与 `repeats` 等长
+    fn padded_shape(&self, shape: &[usize]) -> Vec<usize> {
+        if self.repeats.len() < shape.len() {
+            panic!(
+                "Number of repeats ({}) must be at least the number of dimensions ({})",
+                self.repeats.len(),
+                shape.len()
+            );
+        }
+        let mut padded = vec![1; self.repeats.len() - shape.len()];
+        padded.extend_from_slice(shape);
+        padded
+    }
+
+    /// 按 `repeats` 平铺数据
+    fn apply<A: Clone>(&self, data: ArrayD<A>) -> ArrayD<A> {
+        let padded = self.padded_shape(data.shape());
+        let source = reshape_array(data, &padded);
+        let output_shape: Vec<usize> = padded
+            .iter()
+            .zip(&self.repeats)
+            .map(|(&s, &r)| s * r)
+            .collect();
+        ArrayD::from_shape_fn(IxDyn(&output_shape), |index| {
+            let position: Vec<usize> = index
+                .as_array_view()
+                .iter()
+                .zip(&padded)
+                .map(|(&i, &s)| i % s)
+                .collect();
+            source[IxDyn(&position)].clone()
+        })
+    }
+}
+
+impl Op for Repeat {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Repeat expects exactly one input tensor");
+        let input = inputs[0];
+        let input_shape = input.shape();
+        unary_result(
+            self,
+            input,
+            |data| self.apply(data),
+            |data| self.apply(data),
+            || Repeat {
+                repeats: self.repeats.clone(),
+                input_shape,
+            },
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // 把每个维度拆成 [重复次数, 原大小]，再对所有重复次数的维度求和
+        let padded = self.padded_shape(&self.input_shape);
+        let split_shape: Vec<usize> = self
+            .repeats
+            .iter()
+            .zip(&padded)
+            .flat_map(|(&r, &s)| [r, s])
+            .collect();
+        let mut grad = reshape_array(output_grad(parent), &split_shape);
+        for axis in (0..self.repeats.len()).rev() {
+            grad = grad.sum_axis(Axis(2 * axis));
+        }
+        vec![reshape_array(grad, &self.input_shape)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿指定维度拼接多个张量
+#[derive(Debug)]
+pub struct Concat {
+    dim: usize,
+    /// 每个输入沿 `dim` 的长度
+    sizes: Vec<usize>,
+}
+
+impl Concat {
+    pub fn new(dim: usize) -> Self {
+        Concat {
+            dim,
+            sizes: Vec::new(),
+        }
+    }
+}
+
+/// 沿 `dim` 拼接数组
+fn concat_arrays<A: Clone>(arrays: &[ArrayD<A>], dim: usize) -> ArrayD<A> {
+    let views: Vec<_> = arrays.iter().map(|a| a.view()).collect();
+    concatenate(Axis(dim), &views)
+        .unwrap_or_else(|e| panic!("Cannot concatenate tensors along dim {}: {}", dim, e))
+}
+
+impl Op for Concat {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            !inputs.is_empty(),
+            "Concat expects at least one input tensor"
+        );
+        // 元素类型不同时按提升规则取公共类型，与二元运算一致
+        let dtype = inputs
+            .iter()
+            .map(|t| t.dtype())
+            .reduce(promote_types)
+            .unwrap();
+        let result = if dtype == DType::F32 {
+            let arrays: Vec<ArrayD<f32>> = inputs.iter().map(|t| t.data()).collect();
+            Tensor::new(concat_arrays(&arrays, self.dim))
+        } else {
+            let arrays: Vec<ArrayD<f64>> = inputs.iter().map(|t| t.data_f64()).collect();
+            Tensor::with_dtype(concat_arrays(&arrays, self.dim), dtype)
+        };
+
+        if needs_grad(inputs) {
+            let op = Concat {
+                dim: self.dim,
+                sizes: inputs.iter().map(|t| t.shape()[self.dim]).collect(),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            for input in inputs {
+                result_data.add_parent(input);
+            }
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = output_grad(parent);
+        let mut start = 0;
+        self.sizes
+            .iter()
+            .map(|&size| {
+                let slice = Slice::from(start..start + size);
+                start += size;
+                grad_output.slice_axis(Axis(self.dim), slice).to_owned()
+            })
+            .collect()
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let mut start = 0;
+        self.sizes
+            .iter()
+            .map(|&size| {
+                let piece = Narrow::new(self.dim, start, size).forward(&[grad]);
+                start += size;
+                piece
+            })
+            .collect()
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿指定维度取连续的一段 `[start, start + length)`，split 与 chunk 基于此实现
+#[derive(Debug, Clone)]
+pub struct Narrow {
+    dim: usize,
+    start: usize,
+    length: usize,
+    input_shape: Vec<usize>,
+}
+
+impl Narrow {
+    pub fn new(dim: usize, start: usize, length: usize) -> Self {
+        Narrow {
+            dim,
+            start,
+            length,
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Narrow {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Narrow expects exactly one input tensor");
+        let input = inputs[0];
+        let input_shape = input.shape();
+        if self.dim >= input_shape.len() || self.start + self.length > input_shape[self.dim] {
+            panic!(
+                "Narrow range {}..{} on dim {} is out of bounds for shape {:?}",
+                self.start,
+                self.start + self.length,
+                self.dim,
+                input_shape
+            );
+        }
+        let layout = input
+            .0
+            .borrow()
+            .layout()
+            .slice(self.dim, self.start, self.length, 1);
+        view_result(self, input, layout, || Narrow {
+            input_shape,
+            ..Narrow::new(self.dim, self.start, self.length)
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let mut grad = ArrayD::zeros(IxDyn(&self.input_shape));
+        grad.slice_axis_mut(
+            Axis(self.dim),
+            Slice::from(self.start..self.start + self.length),
+        )
+        .assign(&output_grad(parent));
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿指定维度取第 `index` 个切片，结果去掉该维度
+#[derive(Debug, Clone)]
+pub struct Select {
+    dim: usize,
+    index: usize,
+    input_shape: Vec<usize>,
+}
+
+impl Select {
+    pub fn new(dim: usize, index: usize) -> Self {
+        Select {
+            dim,
+            index,
+            input_shape: Vec::new(),
+        }
+    }
+}
+
+impl Op for Select {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Select expects exactly one input tensor");
+        let input = inputs[0];
+        let input_shape = input.shape();
+        if self.dim >= input_shape.len() || self.index >= input_shape[self.dim] {
+            panic!(
+                "Index {} on dim {} is out of bounds for shape {:?}",
+                self.index, self.dim, input_shape
+            );
+        }
+        let layout = input.0.borrow().layout().select(self.dim, self.index);
+        view_result(self, input, layout, || Select {
+            input_shape,
+            ..Select::new(self.dim, self.index)
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let mut grad = ArrayD::zeros(IxDyn(&self.input_shape));
+        grad.index_axis_mut(Axis(self.dim), self.index)
+            .assign(&output_grad(parent));
+        vec![grad]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 反转指定维度上元素的顺序
+fn flip_array<A: Clone>(data: ArrayD<A>, dims: &[usize]) -> ArrayD<A> {
+    let mut view = data.view();
+    for &dim in dims {
+        view.invert_axis(Axis(dim));
+    }
+    view.as_standard_layout().into_owned()
+}
+
+/// 沿指定维度翻转
+#[derive(Debug)]
+pub struct Flip {
+    dims: Vec<usize>,
+}
+
+impl Flip {
+    pub fn new(dims: &[usize]) -> Self {
+        Flip {
+            dims: dims.to_vec(),
+        }
+    }
+}
+
+impl Op for Flip {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Flip expects exactly one input tensor");
+        let input = inputs[0];
+        unary_result(
+            self,
+            input,
+            |data| flip_array(data, &self.dims),
+            |data| flip_array(data, &self.dims),
+            || Flip::new(&self.dims),
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![flip_array(output_grad(parent), &self.dims)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Flip::new(&self.dims).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 沿 `dim` 循环移动 `shift` 个位置（正数向后移动）
+fn roll_array<A: Clone>(data: &ArrayD<A>, shift: isize, dim: usize) -> ArrayD<A> {
+    let len = data.shape()[dim];
+    if len == 0 {
+        return data.clone();
+    }
+    let shift = shift.rem_euclid(len as isize) as usize;
+    let split = len - shift;
+    let tail = data.slice_axis(Axis(dim), Slice::from(split..));
+    let head = data.slice_axis(Axis(dim), Slice::from(..split));
+    concatenate(Axis(dim), &[tail, head]).expect("Failed to roll tensor")
+}
+
+/// 沿指定维度循环移动元素
+#[derive(Debug)]
+pub struct Roll {
+    shifts: Vec<isize>,
+    dims: Vec<usize>,
+}
+
+impl Roll {
+    pub fn new(shifts: &[isize], dims: &[usize]) -> Self {
+        assert!(
+            shifts.len() == dims.len(),
+            "Roll expects the same number of shifts and dims"
+        );
+        Roll {
+            shifts: shifts.to_vec(),
+            dims: dims.to_vec(),
+        }
+    }
+
+    fn apply<A: Clone>(&self, data: ArrayD<A>, sign: isize) -> ArrayD<A> {
+        self.shifts
+            .iter()
+            .zip(&self.dims)
+            .fold(data, |acc, (&shift, &dim)| {
+                roll_array(&acc, sign * shift, dim)
+            })
+    }
+}
+
+impl Op for Roll {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(inputs.len() == 1, "Roll expects exactly one input tensor");
+        let input = inputs[0];
+        unary_result(
+            self,
+            input,
+            |data| self.apply(data, 1),
+            |data| self.apply(data, 1),
+            || Roll::new(&self.shifts, &self.dims),
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![self.apply(output_grad(parent), -1)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let shifts: Vec<isize> = self.shifts.iter().map(|&s| -s).collect();
+        vec![Roll::new(&shifts, &self.dims).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 检查维度列表合法且不重复
+fn check_dims(dims: &[usize], ndim: usize) -> Result<(), &'static str> {
+    for (i, &dim) in dims.iter().enumerate() {
+        if dim >= ndim {
+            return Err("维度超出范围");
+        }
+        if dims[..i].contains(&dim) {
+            return Err("维度不能重复");
+        }
+    }
+    Ok(())
+}
+
+impl Tensor {
+    /// 交换两个维度
+    pub fn transpose(&self, dim0: usize, dim1: usize) -> Result<Tensor, &'static str> {
+        let ndim = self.dim();
+        if dim0 >= ndim || dim1 >= ndim {
+            return Err("维度超出范围");
+        }
+        let mut axes: Vec<usize> = (0..ndim).collect();
+        axes.swap(dim0, dim1);
+        Ok(Permute::new(&axes).forward(&[self]))
+    }
+
+    /// 按给定顺序重排所有维度
+    pub fn permute(&self, dims: &[usize]) -> Result<Tensor, &'static str> {
+        if dims.len() != self.dim() {
+            return Err("维度个数必须与张量维度相同");
+        }
+        check_dims(dims, self.dim())?;
+        Ok(Permute::new(dims).forward(&[self]))
+    }
+
+    /// 把 `start_dim..=end_dim` 的维度展平为一维
+    pub fn flatten(&self, start_dim: usize, end_dim: usize) -> Result<Tensor, &'static str> {
+        let shape = self.shape();
+        if shape.is_empty() {
+            return self.reshape(&[1]);
+        }
+        if start_dim > end_dim || end_dim >= shape.len() {
+            return Err("维度超出范围");
+        }
+        let mut new_shape = shape[..start_dim].to_vec();
+        new_shape.push(shape[start_dim..=end_dim].iter().product());
+        new_shape.extend_from_slice(&shape[end_dim + 1..]);
+        self.reshape(&new_shape)
+    }
+
+    /// 把大小为 1 的维度广播到指定形状，也可以在前面新增维度
+    pub fn expand(&self, shape: &[usize]) -> Result<Tensor, &'static str> {
+        let current = self.shape();
+        if shape.len() < current.len() {
+            return Err("目标形状的维度不能少于原张量");
+        }
+        let offset = shape.len() - current.len();
+        for (i, &size) in current.iter().enumerate() {
+            if size != 1 && size != shape[offset + i] {
+                return Err("只能扩展大小为1的维度");
+            }
+        }
+        Ok(Expand::new(shape).forward(&[self]))
+    }
+
+    /// 沿各维度重复张量，`repeats` 的长度不能少于张量维度
+    pub fn repeat(&self, repeats: &[usize]) -> Result<Tensor, &'static str> {
+        if repeats.len() < self.dim() {
+            return Err("重复次数的个数不能少于张量维度");
+        }
+        Ok(Repeat::new(repeats).forward(&[self]))
+    }
+
+    /// 沿指定维度拼接张量，除 `dim` 外其余维度必须相同
+    pub fn cat(tensors: &[Tensor], dim: usize) -> Result<Tensor, &'static str> {
+        if tensors.is_empty() {
+            return Err("输入张量列表不能为空");
+        }
+        let first_shape = tensors[0].shape();
+        if dim >= first_shape.len() {
+            return Err("维度超出范围");
+        }
+        for tensor in tensors.iter().skip(1) {
+            let shape = tensor.shape();
+            let same = shape.len() == first_shape.len()
+                && shape
+                    .iter()
+                    .zip(&first_shape)
+                    .enumerate()
+                    .all(|(i, (a, b))| i == dim || a == b);
+            if !same {
+                return Err("除拼接维度外，所有张量的形状必须相同");
+            }
+        }
+        let inputs: Vec<&Tensor> = tensors.iter().collect();
+        Ok(Concat::new(dim).forward(&inputs))
+    }
+
+    /// 沿指定维度取 `[start, start + length)` 一段
+    pub fn narrow(&self, dim: usize, start: usize, length: usize) -> Result<Tensor, &'static str> {
+        let size = self.size(dim).ok_or("维度超出范围")?;
+        if start + length > size {
+            return Err("索引超出范围");
+        }
+        Ok(Narrow::new(dim, start, length).forward(&[self]))
+    }
+
+    /// 沿指定维度取第 `index` 个切片，结果去掉该维度
+    pub fn select(&self, dim: usize, index: usize) -> Result<Tensor, &'static str> {
+        let size = self.size(dim).ok_or("维度超出范围")?;
+        if index >= size {
+            return Err("索引超出范围");
+        }
+        Ok(Select::new(dim, index).forward(&[self]))
+    }
+
+    /// 沿指定维度按 `split_size` 切分，最后一块可能较小
+    pub fn split(&self, split_size: usize, dim: usize) -> Result<Vec<Tensor>, &'static str> {
+        let size = self.size(dim).ok_or("维度超出范围")?;
+        if split_size == 0 {
+            return Err("切分大小必须大于0");
+        }
+        (0..size)
+            .step_by(split_size)
+            .map(|start| self.narrow(dim, start, split_size.min(size - start)))
+            .collect()
+    }
+
+    /// 沿指定维度尽量均匀地切成 `chunks` 块，每块大小为 `ceil(size / chunks)`，块数可能少于 `chunks`
+    pub fn chunk(&self, chunks: usize, dim: usize) -> Result<Vec<Tensor>, &'static str> {
+        let size = self.size(dim).ok_or("维度超出范围")?;
+        if chunks == 0 {
+            return Err("块数必须大于0");
+        }
+        self.split(size.div_ceil(chunks).max(1), dim)
+    }
+
+    /// 沿指定维度翻转
+    pub fn flip(&self, dims: &[usize]) -> Result<Tensor, &'static str> {
+        check_dims(dims, self.dim())?;
+        Ok(Flip::new(dims).forward(&[self]))
+    }
+
+    /// 沿指定维度循环移动元素。
+    ///
+    /// `dims` 为空时先展平再移动 `shifts[0]` 个位置，最后恢复原形状。
+    pub fn roll(&self, shifts: &[isize], dims: &[usize]) -> Result<Tensor, &'static str> {
+        if dims.is_empty() {
+            if shifts.len() != 1 {
+                return Err("不指定维度时只能有一个移动量");
+            }
+            let flat = self.reshape(&[self.numel()])?;
+            return Roll::new(shifts, &[0])
+                .forward(&[&flat])
+                .reshape(&self.shape());
+        }
+        if shifts.len() != dims.len() {
+            return Err("移动量与维度的个数必须相同");
+        }
+        for &dim in dims {
+            if dim >= self.dim() {
+                return Err("维度超出范围");
+            }
+        }
+        Ok(Roll::new(shifts, dims).forward(&[self]))
+    }
+}
@@ -0,0 +1,212 @@
+use super::{Op, map_data, needs_grad, propagate_tangent};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Axis, NdFloat, Zip};
+use std::sync::Arc;
+
+/// 沿 `dim` 计算平移量（最大值）与 `ln(Σ e^(x - max))`，结果保留被归约的维度。
+///
+/// 最大值为无穷时平移量取 0，与 `logsumexp` 的约定一致。
+fn shifted_log_sum_exp<A: NdFloat>(data: &ArrayD<A>, dim: usize) -> (ArrayD<A>, ArrayD<A>) {
+    let shift = data
+        .map_axis(Axis(dim), |row| {
+            let max = row.fold(A::neg_infinity(), |m, &x| m.max(x));
+            if max.is_finite() { max } else { A::zero() }
+        })
+        .insert_axis(Axis(dim));
+    let log_sum = (data - &shift)
+        .mapv(A::exp)
+        .sum_axis(Axis(dim))
+        .mapv(A::ln)
+        .insert_axis(Axis(dim));
+    (shift, log_sum)
+}
+
+/// 沿 `dim` 计算数值稳定的 log-softmax：`x - max - ln(Σ e^(x - max))`
+pub(crate) fn log_softmax_array<A: NdFloat>(data: &ArrayD<A>, dim: usize) -> ArrayD<A> {
+    let (shift, log_sum) = shifted_log_sum_exp(data, dim);
+    &(data - &shift) - &log_sum
+}
+
+fn check_dim(dim: usize, ndim: usize) {
+    if dim >= ndim {
+        panic!(
+            "Dimension out of range: dim {} for tensor with {} dims",
+            dim, ndim
+        );
+    }
+}
+
+/// Softmax运算，沿指定维度归一化
+#[derive(Debug)]
+pub struct Softmax {
+    dim: usize,
+    output_data: Option<SavedTensor>,
+}
+
+impl Softmax {
+    pub fn new(dim: usize) -> Self {
+        Softmax {
+            dim,
+            output_data: None,
+        }
+    }
+}
+
+impl Op for Softmax {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "Softmax expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        check_dim(self.dim, input.dim());
+
+        // 先减去最大值再求指数，避免上溢
+        let result = map_data(
+            input,
+            input.dtype().to_floating(),
+            |data| log_softmax_array(&data, self.dim).mapv(f32::exp),
+            |data| log_softmax_array(&data, self.dim).mapv(f64::exp),
+        );
+        if needs_grad(&[input]) {
+            let op = Softmax {
+                dim: self.dim,
+                output_data: Some(SavedTensor::new(&result)),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let y = &self
+            .output_data
+            .as_ref()
+            .expect("output_data is None in backward")
+            .unpack();
+        // ∂L/∂x = y * (g - Σ(g * y))
+        let dot = (&grad_output * y)
+            .sum_axis(Axis(self.dim))
+            .insert_axis(Axis(self.dim));
+        vec![y * &(&grad_output - &dot)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let dot = (grad * output).sum_dims(&[self.dim], true);
+        vec![output * &(grad - &dot)]
+    }
+
+    fn jvp(&self, _inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // 雅可比矩阵 diag(y) - y·yᵀ 是对称的，切线与反向传播的公式相同
+        let tangent = tangents[0]
+            .as_ref()
+            .expect("Forward AD requires at least one tangent");
+        let dot = (tangent * output).sum_dims(&[self.dim], true);
+        output * &(tangent - &dot)
+    }
+}
+
+/// LogSoftmax运算，沿指定维度计算 `ln(softmax(x))`
+#[derive(Debug)]
+pub struct LogSoftmax {
+    dim: usize,
+    output_data: Option<SavedTensor>,
+}
+
+impl LogSoftmax {
+    pub fn new(dim: usize) -> Self {
+        LogSoftmax {
+            dim,
+            output_data: None,
+        }
+    }
+}
+
+impl Op for LogSoftmax {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        assert!(
+            inputs.len() == 1,
+            "LogSoftmax expects exactly one input tensor"
+        );
+        let input = inputs[0];
+        check_dim(self.dim, input.dim());
+
+        let result = map_data(
+            input,
+            input.dtype().to_floating(),
+            |data| log_softmax_array(&data, self.dim),
+            |data| log_softmax_array(&data, self.dim),
+        );
+        if needs_grad(&[input]) {
+            let op = LogSoftmax {
+                dim: self.dim,
+                output_data: Some(SavedTensor::new(&result)),
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        let y = &self
+            .output_data
+            .as_ref()
+            .expect("output_data is None in backward")
+            .unpack();
+        // ∂L/∂x = g - softmax(x) * Σg
+        let grad_sum = grad_output
+            .sum_axis(Axis(self.dim))
+            .insert_axis(Axis(self.dim));
+        let grad_input = Zip::from(&grad_output)
+            .and(y)
+            .and_broadcast(&grad_sum)
+            .map_collect(|&g, &log_p, &s| g - log_p.exp() * s);
+        vec![grad_input]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        let grad_sum = grad.sum_dims(&[self.dim], true);
+        vec![grad - &(&output.exp() * &grad_sum)]
+    }
+
+    fn jvp(&self, _inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        // d log_softmax(x) = dx - Σ(softmax(x)·dx)
+        let tangent = tangents[0]
+            .as_ref()
+            .expect("Forward AD requires at least one tangent");
+        tangent - &(&output.exp() * tangent).sum_dims(&[self.dim], true)
+    }
+}
+
+impl Tensor {
+    /// 沿指定维度计算softmax
+    pub fn softmax(&self, dim: usize) -> Tensor {
+        Softmax::new(dim).forward(&[self])
+    }
+
+    /// 沿指定维度计算log-softmax
+    pub fn log_softmax(&self, dim: usize) -> Tensor {
+        LogSoftmax::new(dim).forward(&[self])
+    }
+}
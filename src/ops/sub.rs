@@ -0,0 +1,113 @@
+use crate::ops::broadcast::{binary_result_type, broadcast_binary, sum_to, sum_to_shape};
+use crate::ops::complex::{BinaryKind, ComplexBinary};
+use crate::ops::{Op, linear_jvp, needs_grad, propagate_tangent};
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, arr0};
+use std::ops::Sub;
+use std::sync::Arc;
+
+/// 逐元素减法，支持广播。
+#[derive(Debug)]
+pub struct Subtract {
+    input_shapes: Vec<Vec<usize>>,
+}
+
+impl Subtract {
+    pub fn new(input_shapes: Vec<Vec<usize>>) -> Self {
+        Subtract { input_shapes }
+    }
+}
+
+impl Op for Subtract {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.len() != 2 {
+            panic!("Subtract requires exactly two input tensors");
+        }
+        if inputs.iter().any(|input| input.is_complex()) {
+            return ComplexBinary::new(BinaryKind::Sub).forward(inputs);
+        }
+        let dtype = binary_result_type(inputs[0], inputs[1]);
+        let result = broadcast_binary(inputs[0], inputs[1], dtype, |x, y| x - y, |x, y| x - y);
+
+        if needs_grad(&[inputs[0], inputs[1]]) {
+            let op = Subtract::new(vec![inputs[0].shape(), inputs[1].shape()]);
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            // 两个输入都作为父节点，保证梯度与父节点按位置对应
+            result_data.add_parent(inputs[0]);
+            result_data.add_parent(inputs[1]);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .as_ref()
+            .expect("Gradient not found in backward pass")
+            .clone();
+
+        // ∂L/∂a = ∂L/∂c, ∂L/∂b = -∂L/∂c，并求和回原始形状
+        let grad_a = sum_to_shape(&grad_output, &self.input_shapes[0]);
+        let grad_b = sum_to_shape(&grad_output.mapv(|g| -g), &self.input_shapes[1]);
+        vec![grad_a, grad_b]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![
+            sum_to(grad, &self.input_shapes[0]),
+            sum_to(&-grad, &self.input_shapes[1]),
+        ]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+impl<'a> Sub<&'a Tensor> for &Tensor {
+    type Output = Tensor;
+
+    fn sub(self, other: &'a Tensor) -> Tensor {
+        let op = Subtract::new(vec![self.shape(), other.shape()]);
+        op.forward(&[self, other])
+    }
+}
+
+impl Sub<f32> for &Tensor {
+    type Output = Tensor;
+
+    fn sub(self, scalar: f32) -> Tensor {
+        let b = Tensor::new(arr0(scalar).into_dyn());
+        self - &b
+    }
+}
+
+impl<'a> Sub<&'a Tensor> for f32 {
+    type Output = Tensor;
+
+    fn sub(self, tensor: &'a Tensor) -> Tensor {
+        let a = Tensor::new(arr0(self).into_dyn());
+        &a - tensor
+    }
+}
+
+impl Sub<f64> for &Tensor {
+    type Output = Tensor;
+
+    fn sub(self, scalar: f64) -> Tensor {
+        self - scalar as f32
+    }
+}
+
+impl<'a> Sub<&'a Tensor> for f64 {
+    type Output = Tensor;
+
+    fn sub(self, tensor: &'a Tensor) -> Tensor {
+        self as f32 - tensor
+    }
+}
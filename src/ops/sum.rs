@@ -0,0 +1,105 @@
+use super::adjoint::Adjoint;
+use super::reduce::{accumulate_dtype, expand_grad, normalize_dims, reduced_shape, to_rows};
+use super::{Op, linear_jvp, map_data, needs_grad, propagate_tangent};
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, Axis, IxDyn, NdFloat};
+use std::sync::Arc;
+
+/// 沿指定维度求和
+#[derive(Debug, Clone)]
+pub struct Sum {
+    input_shape: Vec<usize>,
+    dims: Vec<usize>,
+    keepdim: bool,
+}
+
+impl Sum {
+    pub fn new(input_shape: Vec<usize>, dims: &[usize], keepdim: bool) -> Self {
+        let dims = normalize_dims(dims, input_shape.len());
+        Sum {
+            input_shape,
+            dims,
+            keepdim,
+        }
+    }
+
+    fn apply<A: NdFloat>(&self, data: &ArrayD<A>) -> ArrayD<A> {
+        let output_shape = reduced_shape(data.shape(), &self.dims, self.keepdim);
+        to_rows(data, &self.dims)
+            .sum_axis(Axis(1))
+            .into_shape_with_order(IxDyn(&output_shape))
+            .expect("Failed to reshape sum result")
+    }
+}
+
+impl Op for Sum {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.len() != 1 {
+            panic!("Sum operation takes exactly one input.");
+        }
+        let input = inputs[0];
+        let dtype = accumulate_dtype(input.dtype());
+        let result = map_data(
+            input,
+            dtype,
+            |data| self.apply(&data),
+            |data| self.apply(&data),
+        );
+
+        if needs_grad(&[input]) {
+            let op = Sum {
+                input_shape: input.shape(),
+                dims: self.dims.clone(),
+                keepdim: self.keepdim,
+            };
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(op));
+            result_data.add_parent(input);
+            result_data.requires_grad = true;
+        }
+        propagate_tangent(self, inputs, &result);
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad_output = parent
+            .0
+            .borrow()
+            .grad
+            .clone()
+            .expect("Gradient not found in backward pass");
+        // 求和的梯度为 1，直接沿被归约的维度广播回去
+        vec![expand_grad(&grad_output, &self.input_shape, &self.dims)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![Adjoint::new(self.clone()).forward(&[grad])]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], _output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        linear_jvp(self, inputs, tangents)
+    }
+}
+
+/// 对所有元素求和
+pub fn sum(tensor: &Tensor) -> Tensor {
+    sum_dims(tensor, &[], false)
+}
+
+/// 沿指定维度求和，`keepdim` 为真时保留被归约的维度
+pub fn sum_dims(tensor: &Tensor, dims: &[usize], keepdim: bool) -> Tensor {
+    let op = Sum::new(tensor.shape(), dims, keepdim);
+    op.forward(&[tensor])
+}
+
+impl Tensor {
+    /// 对所有元素求和，返回标量张量
+    pub fn sum(&self) -> Tensor {
+        sum(self)
+    }
+
+    /// 沿指定维度求和（空列表表示所有维度）
+    pub fn sum_dims(&self, dims: &[usize], keepdim: bool) -> Tensor {
+        sum_dims(self, dims, keepdim)
+    }
+}
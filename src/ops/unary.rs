@@ -0,0 +1,594 @@
+//! 逐元素一元运算：exp、log、sqrt、三角函数、激活函数等。
+//!
+//! 每个运算都保存反向传播所需的输入或输出数据，并给出解析梯度。
+
+use crate::ops::complex::ComplexAbs;
+use crate::ops::{Op, elementwise_jvp, map_data, needs_grad, propagate_tangent};
+use crate::storage::SavedTensor;
+use crate::tensor::Tensor;
+use ndarray::{ArrayD, NdFloat, Zip};
+use std::sync::Arc;
+
+/// 一元运算的公共前向逻辑：逐元素计算，并在需要梯度时用 `make_op` 构造反向所需的op。
+///
+/// 浮点输入保持原类型，整数与布尔输入得到 f32；结果为 f32 时用 `f32_op` 计算，否则用 `f64_op`。
+/// `make_op` 接收输入张量和输出张量，可以按需保存其中之一；`op` 为执行前向计算的运算本身，用于传播切线。
+fn unary_forward<O, F, G, M>(
+    op: &O,
+    inputs: &[&Tensor],
+    name: &str,
+    f32_op: F,
+    f64_op: G,
+    make_op: M,
+) -> Tensor
+where
+    O: Op + 'static,
+    F: Fn(f32) -> f32,
+    G: Fn(f64) -> f64,
+    M: FnOnce(&Tensor, &Tensor) -> O,
+{
+    assert!(
+        inputs.len() == 1,
+        "{} expects exactly one input tensor",
+        name
+    );
+    let input = inputs[0];
+    let result = map_data(
+        input,
+        input.dtype().to_floating(),
+        |data| data.mapv(f32_op),
+        |data| data.mapv(f64_op),
+    );
+    if needs_grad(&[input]) {
+        let backward_op = make_op(input, &result);
+        let mut result_data = result.0.borrow_mut();
+        result_data.set_creator(Arc::new(backward_op));
+        result_data.add_parent(input);
+        result_data.requires_grad = true;
+    }
+    propagate_tangent(op, inputs, &result);
+    result
+}
+
+/// 取出输出张量上的梯度
+fn output_grad(parent: &Tensor) -> ArrayD<f32> {
+    parent
+        .0
+        .borrow()
+        .grad
+        .clone()
+        .expect("Gradient not found in backward pass")
+}
+
+/// 用保存的数据和输出梯度逐元素计算输入梯度
+fn chain(grad: &ArrayD<f32>, saved: &Option<SavedTensor>, df: impl Fn(f32) -> f32) -> ArrayD<f32> {
+    let saved = saved
+        .as_ref()
+        .expect("saved data is None in backward")
+        .unpack();
+    Zip::from(grad).and(&saved).map_collect(|&g, &x| g * df(x))
+}
+
+/// 指数函数 `e^x`
+#[derive(Debug, Default)]
+pub struct Exp {
+    output_data: Option<SavedTensor>,
+}
+
+impl Op for Exp {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Exp", f32::exp, f64::exp, |_, y| Exp {
+            output_data: Some(SavedTensor::new(y)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d(e^x)/dx = e^x
+        vec![chain(&output_grad(parent), &self.output_data, |y| y)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad * output]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 自然对数 `ln(x)`
+#[derive(Debug, Default)]
+pub struct Log {
+    input_data: Option<SavedTensor>,
+}
+
+impl Op for Log {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Log", f32::ln, f64::ln, |x, _| Log {
+            input_data: Some(SavedTensor::new(x)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d(ln x)/dx = 1/x
+        vec![chain(&output_grad(parent), &self.input_data, |x| 1.0 / x)]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad / &inputs[0]]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// `ln(1 + x)`，在 x 接近 0 时比 `log(1 + x)` 更精确
+#[derive(Debug, Default)]
+pub struct Log1p {
+    input_data: Option<SavedTensor>,
+}
+
+impl Op for Log1p {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Log1p", f32::ln_1p, f64::ln_1p, |x, _| {
+            Log1p {
+                input_data: Some(SavedTensor::new(x)),
+            }
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d(ln(1+x))/dx = 1/(1+x)
+        vec![chain(&output_grad(parent), &self.input_data, |x| {
+            1.0 / (1.0 + x)
+        })]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad / &(&inputs[0] + 1.0_f32)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 平方根 `√x`
+#[derive(Debug, Default)]
+pub struct Sqrt {
+    output_data: Option<SavedTensor>,
+}
+
+impl Op for Sqrt {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Sqrt", f32::sqrt, f64::sqrt, |_, y| Sqrt {
+            output_data: Some(SavedTensor::new(y)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d(√x)/dx = 1/(2√x)
+        vec![chain(&output_grad(parent), &self.output_data, |y| 0.5 / y)]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![&(grad * 0.5_f32) / output]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 平方根倒数 `1/√x`
+#[derive(Debug, Default)]
+pub struct Rsqrt {
+    output_data: Option<SavedTensor>,
+}
+
+impl Op for Rsqrt {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(
+            self,
+            inputs,
+            "Rsqrt",
+            |x| 1.0 / x.sqrt(),
+            |x| 1.0 / x.sqrt(),
+            |_, y| Rsqrt {
+                output_data: Some(SavedTensor::new(y)),
+            },
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d(x^(-1/2))/dx = -1/2 * x^(-3/2) = -1/2 * y³
+        vec![chain(&output_grad(parent), &self.output_data, |y| {
+            -0.5 * y * y * y
+        })]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![&(grad * -0.5_f32) * &output.powf(3.0)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 绝对值 `|x|`，复数张量交给 `ComplexAbs` 计算模
+#[derive(Debug, Default)]
+pub struct Abs {
+    input_data: Option<SavedTensor>,
+}
+
+impl Op for Abs {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        if inputs.iter().any(|input| input.is_complex()) {
+            return ComplexAbs::default().forward(inputs);
+        }
+        unary_forward(self, inputs, "Abs", f32::abs, f64::abs, |x, _| Abs {
+            input_data: Some(SavedTensor::new(x)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d|x|/dx = sign(x)，x = 0 处取 0
+        vec![chain(&output_grad(parent), &self.input_data, sign)]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad * &inputs[0].sign()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 符号函数，与PyTorch一致：0 的符号为 0
+fn sign<T: NdFloat>(x: T) -> T {
+    if x > T::zero() {
+        T::one()
+    } else if x < T::zero() {
+        -T::one()
+    } else {
+        T::zero()
+    }
+}
+
+/// 符号函数 `sign(x)`，梯度处处为 0
+#[derive(Debug, Default)]
+pub struct Sign {
+    input_shape: Vec<usize>,
+}
+
+impl Op for Sign {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Sign", sign, sign, |x, _| Sign {
+            input_shape: x.shape().to_vec(),
+        })
+    }
+
+    fn backward(&self, _parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![ArrayD::zeros(self.input_shape.as_slice())]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], _output: &Tensor, _grad: &Tensor) -> Vec<Tensor> {
+        vec![Tensor::zeros(&self.input_shape)]
+    }
+
+    fn jvp(&self, _inputs: &[&Tensor], output: &Tensor, _tangents: &[Option<Tensor>]) -> Tensor {
+        output.zeros_like()
+    }
+}
+
+/// 正弦函数 `sin(x)`
+#[derive(Debug, Default)]
+pub struct Sin {
+    input_data: Option<SavedTensor>,
+}
+
+impl Op for Sin {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Sin", f32::sin, f64::sin, |x, _| Sin {
+            input_data: Some(SavedTensor::new(x)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![chain(&output_grad(parent), &self.input_data, f32::cos)]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad * &inputs[0].cos()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 余弦函数 `cos(x)`
+#[derive(Debug, Default)]
+pub struct Cos {
+    input_data: Option<SavedTensor>,
+}
+
+impl Op for Cos {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Cos", f32::cos, f64::cos, |x, _| Cos {
+            input_data: Some(SavedTensor::new(x)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        vec![chain(&output_grad(parent), &self.input_data, |x| -x.sin())]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![-&(grad * &inputs[0].sin())]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 双曲正切 `tanh(x)`
+#[derive(Debug, Default)]
+pub struct Tanh {
+    output_data: Option<SavedTensor>,
+}
+
+impl Op for Tanh {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(self, inputs, "Tanh", f32::tanh, f64::tanh, |_, y| Tanh {
+            output_data: Some(SavedTensor::new(y)),
+        })
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d(tanh x)/dx = 1 - tanh²(x)
+        vec![chain(&output_grad(parent), &self.output_data, |y| {
+            1.0 - y * y
+        })]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad * &(1.0_f32 - &(output * output))]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 数值稳定的 sigmoid：对负数使用 `e^x / (1 + e^x)` 避免溢出
+pub(crate) fn stable_sigmoid<T: NdFloat>(x: T) -> T {
+    if x >= T::zero() {
+        T::one() / (T::one() + (-x).exp())
+    } else {
+        let e = x.exp();
+        e / (T::one() + e)
+    }
+}
+
+/// Sigmoid函数 `1 / (1 + e^(-x))`
+#[derive(Debug, Default)]
+pub struct Sigmoid {
+    output_data: Option<SavedTensor>,
+}
+
+impl Op for Sigmoid {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(
+            self,
+            inputs,
+            "Sigmoid",
+            stable_sigmoid,
+            stable_sigmoid,
+            |_, y| Sigmoid {
+                output_data: Some(SavedTensor::new(y)),
+            },
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // dσ/dx = σ(x) * (1 - σ(x))
+        vec![chain(&output_grad(parent), &self.output_data, |y| {
+            y * (1.0 - y)
+        })]
+    }
+
+    fn backward_graph(&self, _inputs: &[Tensor], output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![&(grad * output) * &(1.0_f32 - output)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// Softplus函数 `ln(1 + e^x)`
+///
+/// 使用 `max(x, 0) + ln(1 + e^(-|x|))` 计算以避免溢出。
+#[derive(Debug, Default)]
+pub struct Softplus {
+    input_data: Option<SavedTensor>,
+}
+
+impl Op for Softplus {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        unary_forward(
+            self,
+            inputs,
+            "Softplus",
+            |x| x.max(0.0) + (-x.abs()).exp().ln_1p(),
+            |x| x.max(0.0) + (-x.abs()).exp().ln_1p(),
+            |x, _| Softplus {
+                input_data: Some(SavedTensor::new(x)),
+            },
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // d softplus(x)/dx = σ(x)
+        vec![chain(
+            &output_grad(parent),
+            &self.input_data,
+            stable_sigmoid,
+        )]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        vec![grad * &inputs[0].sigmoid()]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+/// 将元素限制在 `[min, max]` 区间内，边界为 None 表示不限制
+#[derive(Debug, Default)]
+pub struct Clamp {
+    min: Option<f32>,
+    max: Option<f32>,
+    input_data: Option<SavedTensor>,
+}
+
+impl Clamp {
+    pub fn new(min: Option<f32>, max: Option<f32>) -> Self {
+        Clamp {
+            min,
+            max,
+            input_data: None,
+        }
+    }
+
+    fn clamp<T: NdFloat>(min: Option<T>, max: Option<T>, x: T) -> T {
+        let x = min.map_or(x, |m| x.max(m));
+        max.map_or(x, |m| x.min(m))
+    }
+
+    /// x 是否在区间内（含边界）
+    fn in_range(min: Option<f32>, max: Option<f32>, x: f32) -> bool {
+        min.is_none_or(|m| x >= m) && max.is_none_or(|m| x <= m)
+    }
+}
+
+impl Op for Clamp {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let (min, max) = (self.min, self.max);
+        unary_forward(
+            self,
+            inputs,
+            "Clamp",
+            |x| Clamp::clamp(min, max, x),
+            |x| Clamp::clamp(min.map(f64::from), max.map(f64::from), x),
+            |x, _| Clamp {
+                min,
+                max,
+                input_data: Some(SavedTensor::new(x)),
+            },
+        )
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        // 区间内（含边界）梯度为 1，区间外为 0
+        let (min, max) = (self.min, self.max);
+        vec![chain(&output_grad(parent), &self.input_data, |x| {
+            if Clamp::in_range(min, max, x) {
+                1.0
+            } else {
+                0.0
+            }
+        })]
+    }
+
+    fn backward_graph(&self, inputs: &[Tensor], _output: &Tensor, grad: &Tensor) -> Vec<Tensor> {
+        // 掩码是分段常数，对输入的导数为 0
+        let (min, max) = (self.min, self.max);
+        let mask = inputs[0].data().mapv(|x| {
+            if Clamp::in_range(min, max, x) {
+                1.0
+            } else {
+                0.0
+            }
+        });
+        vec![grad * &Tensor::new(mask)]
+    }
+
+    fn jvp(&self, inputs: &[&Tensor], output: &Tensor, tangents: &[Option<Tensor>]) -> Tensor {
+        elementwise_jvp(self, inputs, output, tangents)
+    }
+}
+
+impl Tensor {
+    /// 逐元素指数
+    pub fn exp(&self) -> Tensor {
+        Exp::default().forward(&[self])
+    }
+
+    /// 逐元素自然对数
+    pub fn log(&self) -> Tensor {
+        Log::default().forward(&[self])
+    }
+
+    /// 逐元素计算 `ln(1 + x)`
+    pub fn log1p(&self) -> Tensor {
+        Log1p::default().forward(&[self])
+    }
+
+    /// 逐元素平方根
+    pub fn sqrt(&self) -> Tensor {
+        Sqrt::default().forward(&[self])
+    }
+
+    /// 逐元素平方根倒数
+    pub fn rsqrt(&self) -> Tensor {
+        Rsqrt::default().forward(&[self])
+    }
+
+    /// 逐元素绝对值
+    pub fn abs(&self) -> Tensor {
+        Abs::default().forward(&[self])
+    }
+
+    /// 逐元素符号函数
+    pub fn sign(&self) -> Tensor {
+        Sign::default().forward(&[self])
+    }
+
+    /// 逐元素正弦
+    pub fn sin(&self) -> Tensor {
+        Sin::default().forward(&[self])
+    }
+
+    /// 逐元素余弦
+    pub fn cos(&self) -> Tensor {
+        Cos::default().forward(&[self])
+    }
+
+    /// 逐元素双曲正切
+    pub fn tanh(&self) -> Tensor {
+        Tanh::default().forward(&[self])
+    }
+
+    /// 逐元素sigmoid
+    pub fn sigmoid(&self) -> Tensor {
+        Sigmoid::default().forward(&[self])
+    }
+
+    /// 逐元素softplus
+    pub fn softplus(&self) -> Tensor {
+        Softplus::default().forward(&[self])
+    }
+
+    /// 将元素限制在 `[min, max]` 区间内
+    pub fn clamp(&self, min: Option<f32>, max: Option<f32>) -> Tensor {
+        Clamp::new(min, max).forward(&[self])
+    }
+}
@@ -33,12 +33,20 @@ impl Optimizer for SGD {
             let grad = param.0.borrow().grad.clone().unwrap_or_else(|| {
                 panic!("Gradient not found! param: {:?}", param);
             });
+            // 复数参数的梯度按 view_as_real 的形状保存，更新时也按该形状修改
             assert!(
-                grad.shape() == param.0.borrow().data.shape(),
+                grad.shape() == param.0.borrow().grad_shape(),
                 "Gradient shape mismatch! {:?}",
                 param
             );
-            param.0.borrow_mut().data -= &((self.lr) * grad);
+            let data = param.0.borrow();
+            if data.needs_f64_update() {
+                // f64 与 complex128 参数以 f64 更新，不经过 f32 损失精度
+                let step = grad.mapv(|g| self.lr as f64 * g as f64);
+                data.update_data_f64(|mut data| data -= &step);
+            } else {
+                data.update_data(|mut data| data -= &((self.lr) * grad));
+            }
         }
     }
 
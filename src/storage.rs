@@ -0,0 +1,484 @@
+//! 张量的底层存储与内存布局。
+//!
+//! 多个张量可以共享同一块 [`Storage`]，各自用 [`Layout`]（形状、步长、偏移）描述如何解释这段内存，
+//! 因此 view、转置、切片等操作不需要复制数据。
+//! 存储按自己的元素类型保存数据，读出时转换为 f32 或 f64。
+//! 存储上的版本号在每次原地写入时加一，用于检测反向传播所需的张量是否被修改过。
+
+use crate::autograd::is_inference_mode_enabled;
+use crate::dtype::{DType, Element, pairs_to_complex};
+use crate::tensor::Tensor;
+use half::{bf16, f16};
+use ndarray::{ArrayD, ArrayViewD, ArrayViewMutD, IxDyn, ShapeBuilder};
+use num_complex::Complex64;
+use parking_lot::RwLock;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// 按元素类型保存的缓冲区
+#[derive(Debug)]
+enum Buffer {
+    F32(Vec<f32>),
+    F64(Vec<f64>),
+    F16(Vec<f16>),
+    BF16(Vec<bf16>),
+    I32(Vec<i32>),
+    I64(Vec<i64>),
+    U8(Vec<u8>),
+    Bool(Vec<bool>),
+}
+
+impl Default for Buffer {
+    fn default() -> Self {
+        Buffer::F32(Vec::new())
+    }
+}
+
+/// 对缓冲区中的数据执行与元素类型无关的代码
+macro_rules! with_buffer {
+    ($buffer:expr, $data:ident => $body:expr) => {
+        match $buffer {
+            Buffer::F32($data) => $body,
+            Buffer::F64($data) => $body,
+            Buffer::F16($data) => $body,
+            Buffer::BF16($data) => $body,
+            Buffer::I32($data) => $body,
+            Buffer::I64($data) => $body,
+            Buffer::U8($data) => $body,
+            Buffer::Bool($data) => $body,
+        }
+    };
+}
+
+impl Buffer {
+    fn from_f64(values: impl Iterator<Item = f64>, dtype: DType) -> Self {
+        fn cast<T: Element>(values: impl Iterator<Item = f64>) -> Vec<T> {
+            values.map(T::from_f64).collect()
+        }
+        match dtype {
+            DType::F32 => Buffer::F32(cast(values)),
+            DType::F64 => Buffer::F64(cast(values)),
+            DType::F16 => Buffer::F16(cast(values)),
+            DType::BF16 => Buffer::BF16(cast(values)),
+            DType::I32 => Buffer::I32(cast(values)),
+            DType::I64 => Buffer::I64(cast(values)),
+            DType::U8 => Buffer::U8(cast(values)),
+            DType::Bool => Buffer::Bool(cast(values)),
+            DType::Complex64 | DType::Complex128 => {
+                panic!("Complex values are stored as pairs of {}", dtype.to_real())
+            }
+        }
+    }
+
+    fn dtype(&self) -> DType {
+        match self {
+            Buffer::F32(_) => DType::F32,
+            Buffer::F64(_) => DType::F64,
+            Buffer::F16(_) => DType::F16,
+            Buffer::BF16(_) => DType::BF16,
+            Buffer::I32(_) => DType::I32,
+            Buffer::I64(_) => DType::I64,
+            Buffer::U8(_) => DType::U8,
+            Buffer::Bool(_) => DType::Bool,
+        }
+    }
+}
+
+/// 一段连续的缓冲区，带有元素类型和原地修改的版本号
+#[derive(Debug, Default)]
+pub struct Storage {
+    buffer: RwLock<Buffer>,
+    version: AtomicUsize,
+    /// 是否在推理模式下创建，推理存储不跟踪版本号
+    inference: bool,
+}
+
+impl Storage {
+    pub fn new(data: Vec<f32>) -> Self {
+        Storage::from_buffer(Buffer::F32(data))
+    }
+
+    /// 把 f64 数据转换为 `dtype` 保存
+    pub fn from_f64(data: impl IntoIterator<Item = f64>, dtype: DType) -> Self {
+        Storage::from_buffer(Buffer::from_f64(data.into_iter(), dtype))
+    }
+
+    fn from_buffer(buffer: Buffer) -> Self {
+        Storage {
+            buffer: RwLock::new(buffer),
+            version: AtomicUsize::new(0),
+            inference: is_inference_mode_enabled(),
+        }
+    }
+
+    /// 元素类型
+    pub fn dtype(&self) -> DType {
+        self.buffer.read().dtype()
+    }
+
+    /// 是否为推理模式下创建的存储
+    pub fn is_inference(&self) -> bool {
+        self.inference
+    }
+
+    /// 缓冲区中的元素个数
+    pub fn len(&self) -> usize {
+        with_buffer!(&*self.buffer.read(), data => data.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 当前版本号，每次原地写入后加一
+    pub fn version(&self) -> usize {
+        self.version.load(Ordering::SeqCst)
+    }
+
+    /// 按布局读出转换为 f32 的数据副本，结果总是行主序的标准布局
+    pub fn read(&self, layout: &Layout) -> ArrayD<f32> {
+        match &*self.buffer.read() {
+            Buffer::F32(data) => read_strided(data, layout),
+            buffer => {
+                with_buffer!(buffer, data => read_strided(data, layout).mapv(Element::to_f32))
+            }
+        }
+    }
+
+    /// 按布局读出转换为 f64 的数据副本，f64 与整数类型不会损失精度
+    pub fn read_f64(&self, layout: &Layout) -> ArrayD<f64> {
+        with_buffer!(&*self.buffer.read(), data => read_strided(data, layout).mapv(Element::to_f64))
+    }
+
+    /// 按布局原地修改数据，并增加版本号（推理存储除外）。
+    ///
+    /// 修改以 f32 进行，其他类型的数据先转换为 f32，修改后再转换回来；
+    /// f64、i32、i64 等 f32 无法精确表示的类型请使用 [`Storage::write_f64`]。
+    pub fn write(&self, layout: &Layout, f: impl FnOnce(ArrayViewMutD<'_, f32>)) {
+        check_writable(layout);
+        if layout.numel() > 0 {
+            match &mut *self.buffer.write() {
+                Buffer::F32(data) => f(strided_view_mut(data, layout)),
+                buffer => with_buffer!(buffer, data => {
+                    let mut view = strided_view_mut(data, layout);
+                    let mut values = view.mapv(Element::to_f32);
+                    f(values.view_mut());
+                    view.zip_mut_with(&values, |x, &value| *x = Element::from_f64(value as f64));
+                }),
+            }
+        }
+        self.bump_version();
+    }
+
+    /// 与 [`Storage::write`] 相同，但修改以 f64 进行：f64 数据直接修改，
+    /// 其他类型先转换为 f64，修改后再转换回来，因此不会损失精度。
+    pub fn write_f64(&self, layout: &Layout, f: impl FnOnce(ArrayViewMutD<'_, f64>)) {
+        check_writable(layout);
+        if layout.numel() > 0 {
+            match &mut *self.buffer.write() {
+                Buffer::F64(data) => f(strided_view_mut(data, layout)),
+                buffer => with_buffer!(buffer, data => {
+                    let mut view = strided_view_mut(data, layout);
+                    let mut values = view.mapv(Element::to_f64);
+                    f(values.view_mut());
+                    view.zip_mut_with(&values, |x, &value| *x = Element::from_f64(value));
+                }),
+            }
+        }
+        self.bump_version();
+    }
+
+    fn bump_version(&self) {
+        if !self.inference {
+            self.version.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+}
+
+/// 原地写入不能经过 expand 出来的步长为 0 的维度，否则多个元素会写到同一位置
+fn check_writable(layout: &Layout) {
+    if layout
+        .shape
+        .iter()
+        .zip(&layout.strides)
+        .any(|(&size, &stride)| stride == 0 && size > 1)
+    {
+        panic!(
+            "unsupported operation: more than one element of the written-to tensor refers to a single memory location"
+        );
+    }
+}
+
+/// 按布局读出数据副本
+fn read_strided<T: Clone>(data: &[T], layout: &Layout) -> ArrayD<T> {
+    if layout.numel() == 0 {
+        return ArrayD::from_shape_vec(IxDyn(&layout.shape), Vec::new()).unwrap();
+    }
+    // 步长为 0 的维度（expand 出来的）先按长度 1 取视图，再广播回去
+    let base_shape: Vec<usize> = layout
+        .shape
+        .iter()
+        .zip(&layout.strides)
+        .map(|(&size, &stride)| if stride == 0 { 1 } else { size })
+        .collect();
+    let view = ArrayViewD::from_shape(
+        IxDyn(&base_shape).strides(IxDyn(&layout.strides)),
+        &data[layout.offset..],
+    )
+    .expect("Layout is out of bounds of the storage");
+    view.broadcast(IxDyn(&layout.shape))
+        .expect("Failed to broadcast strided view")
+        .as_standard_layout()
+        .into_owned()
+}
+
+/// 按布局取可写的视图
+fn strided_view_mut<'a, T>(data: &'a mut [T], layout: &Layout) -> ArrayViewMutD<'a, T> {
+    ArrayViewMutD::from_shape(
+        IxDyn(&layout.shape).strides(IxDyn(&layout.strides)),
+        &mut data[layout.offset..],
+    )
+    .expect("Layout is out of bounds of the storage")
+}
+
+/// 张量在存储中的布局：`data[i0, i1, ...]` 位于 `offset + Σ ik * strides[k]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub shape: Vec<usize>,
+    pub strides: Vec<usize>,
+    pub offset: usize,
+}
+
+impl Layout {
+    /// 行优先的连续布局
+    pub fn contiguous(shape: &[usize]) -> Self {
+        let mut strides = vec![1; shape.len()];
+        for d in (0..shape.len().saturating_sub(1)).rev() {
+            strides[d] = strides[d + 1] * shape[d + 1].max(1);
+        }
+        Layout {
+            shape: shape.to_vec(),
+            strides,
+            offset: 0,
+        }
+    }
+
+    pub fn ndim(&self) -> usize {
+        self.shape.len()
+    }
+
+    pub fn numel(&self) -> usize {
+        self.shape.iter().product()
+    }
+
+    /// 是否按行优先连续存放（长度为 1 的维度的步长不影响）
+    pub fn is_contiguous(&self) -> bool {
+        let mut expected = 1;
+        for (&size, &stride) in self.shape.iter().zip(&self.strides).rev() {
+            if size == 1 {
+                continue;
+            }
+            if stride != expected {
+                return self.numel() == 0;
+            }
+            expected *= size;
+        }
+        true
+    }
+
+    /// 不复制数据地把布局解释为新形状，无法做到时返回 None。
+    ///
+    /// 与 PyTorch 的 `view` 一致：只要被合并或拆分的维度在内存中是连续的即可，不要求整体连续。
+    pub fn view(&self, shape: &[usize]) -> Option<Layout> {
+        if self.ndim() == 0 || self.numel() == 0 {
+            return Some(Layout {
+                offset: self.offset,
+                ..Layout::contiguous(shape)
+            });
+        }
+        let mut strides = vec![0; shape.len()];
+        let mut view_d = shape.len() as isize - 1;
+        let mut chunk_base_stride = *self.strides.last().unwrap();
+        let mut tensor_numel = 1;
+        let mut view_numel = 1;
+        for tensor_d in (0..self.ndim()).rev() {
+            tensor_numel *= self.shape[tensor_d];
+            // 到达一段连续内存的开头时，为新形状中对应的维度分配步长
+            if tensor_d == 0
+                || (self.shape[tensor_d - 1] != 1
+                    && self.strides[tensor_d - 1] != tensor_numel * chunk_base_stride)
+            {
+                while view_d >= 0 && (view_numel < tensor_numel || shape[view_d as usize] == 1) {
+                    strides[view_d as usize] = view_numel * chunk_base_stride;
+                    view_numel *= shape[view_d as usize];
+                    view_d -= 1;
+                }
+                if view_numel != tensor_numel {
+                    return None;
+                }
+                if tensor_d > 0 {
+                    chunk_base_stride = self.strides[tensor_d - 1];
+                    tensor_numel = 1;
+                    view_numel = 1;
+                }
+            }
+        }
+        if view_d != -1 {
+            return None;
+        }
+        Some(Layout {
+            shape: shape.to_vec(),
+            strides,
+            offset: self.offset,
+        })
+    }
+
+    /// 复数张量的布局按实数元素计，每个复数占相邻的两个实数（实部在前）；
+    /// 在末尾加上长度为 2、步长为 1 的维度即得到实部与虚部的布局
+    pub fn view_as_real(&self) -> Layout {
+        let mut layout = self.clone();
+        layout.shape.push(2);
+        layout.strides.push(1);
+        layout
+    }
+
+    /// `view_as_real` 的逆操作，末尾一维不是长度为 2、步长为 1 时返回 None
+    pub fn view_as_complex(&self) -> Option<Layout> {
+        if self.shape.last() != Some(&2) || self.strides.last() != Some(&1) {
+            return None;
+        }
+        let mut layout = self.clone();
+        layout.shape.pop();
+        layout.strides.pop();
+        Some(layout)
+    }
+
+    /// 按 `axes` 重新排列维度
+    pub fn permute(&self, axes: &[usize]) -> Layout {
+        Layout {
+            shape: axes.iter().map(|&a| self.shape[a]).collect(),
+            strides: axes.iter().map(|&a| self.strides[a]).collect(),
+            offset: self.offset,
+        }
+    }
+
+    /// 沿 `dim` 取 `[start, start + length)`，每隔 `step` 个取一个
+    pub fn slice(&self, dim: usize, start: usize, length: usize, step: usize) -> Layout {
+        let mut layout = self.clone();
+        if length > 0 {
+            layout.offset += start * self.strides[dim];
+        }
+        layout.shape[dim] = length;
+        layout.strides[dim] *= step;
+        layout
+    }
+
+    /// 沿 `dim` 取第 `index` 个，并去掉该维度
+    pub fn select(&self, dim: usize, index: usize) -> Layout {
+        let mut layout = self.clone();
+        layout.offset += index * self.strides[dim];
+        layout.shape.remove(dim);
+        layout.strides.remove(dim);
+        layout
+    }
+
+    /// 把长度为 1 的维度广播到 `shape`，必要时在前面补维度，广播的维度步长为 0
+    pub fn expand(&self, shape: &[usize]) -> Layout {
+        let lead = shape.len() - self.ndim();
+        let strides = shape
+            .iter()
+            .enumerate()
+            .map(|(d, &size)| match d.checked_sub(lead) {
+                Some(i) if self.shape[i] == size => self.strides[i],
+                _ => 0,
+            })
+            .collect();
+        Layout {
+            shape: shape.to_vec(),
+            strides,
+            offset: self.offset,
+        }
+    }
+}
+
+/// 为反向传播保存的张量。
+///
+/// 与张量共享存储而不复制数据，同时记下保存时的版本号；
+/// 如果之后存储被原地修改，取出数据时会 panic，而不是悄悄算出错误的梯度。
+#[derive(Debug, Clone)]
+pub struct SavedTensor {
+    storage: Arc<Storage>,
+    layout: Layout,
+    version: usize,
+    complex: bool,
+}
+
+impl SavedTensor {
+    pub fn new(tensor: &Tensor) -> Self {
+        let data = tensor.0.borrow();
+        if data.storage().is_inference() {
+            panic!(
+                "Inference tensors cannot be saved for backward. Make a clone outside of inference mode to use it in autograd"
+            );
+        }
+        SavedTensor {
+            storage: Arc::clone(data.storage()),
+            layout: data.layout().clone(),
+            version: data.storage().version(),
+            complex: data.is_complex(),
+        }
+    }
+
+    /// 保存时的形状
+    pub fn shape(&self) -> &[usize] {
+        &self.layout.shape
+    }
+
+    /// 保存的是否为复数张量
+    pub fn is_complex(&self) -> bool {
+        self.complex
+    }
+
+    /// 取出保存的数据，存储被原地修改过时 panic；复数张量取出的是 `view_as_real` 的数据
+    pub fn unpack(&self) -> ArrayD<f32> {
+        self.check_version();
+        if self.complex {
+            self.storage.read(&self.layout.view_as_real())
+        } else {
+            self.storage.read(&self.layout)
+        }
+    }
+
+    /// 以张量取出保存的数据，保持保存时的元素类型（包括复数），结果不记录计算图
+    pub fn unpack_tensor(&self) -> Tensor {
+        self.check_version();
+        if self.complex {
+            let dtype = self.storage.dtype().to_complex();
+            Tensor::from_complex(self.unpack_complex(), dtype)
+        } else {
+            Tensor::with_dtype(self.storage.read_f64(&self.layout), self.storage.dtype())
+        }
+    }
+
+    /// 以复数取出保存的数据，实数张量的虚部为 0
+    pub fn unpack_complex(&self) -> ArrayD<Complex64> {
+        self.check_version();
+        if self.complex {
+            pairs_to_complex(&self.storage.read_f64(&self.layout.view_as_real()))
+        } else {
+            self.storage
+                .read_f64(&self.layout)
+                .mapv(|x| Complex64::new(x, 0.0))
+        }
+    }
+
+    fn check_version(&self) {
+        let current = self.storage.version();
+        if current != self.version {
+            panic!(
+                "one of the variables needed for gradient computation has been modified by an inplace operation: expected version {}, got version {}",
+                self.version, current
+            );
+        }
+    }
+}
@@ -1,24 +1,55 @@
+use crate::autograd::anomaly_mode::capture_trace;
+use crate::autograd::hooks::{GradHook, PostAccumulateGradHook};
+use crate::autograd::is_grad_enabled;
+use crate::dtype::{DType, complex_to_pairs, pairs_to_complex};
 use crate::ops::Op;
-use ndarray::{Array, ArrayD, Axis, IxDyn};
+use crate::ops::cast::ToDtype;
+use crate::ops::shape::{Contiguous, Reshape};
+use crate::storage::{Layout, Storage};
+use ndarray::{Array, ArrayD, ArrayViewMutD, IxDyn};
 use ndarray_rand::RandomExt;
 use ndarray_rand::rand_distr::StandardNormal;
+use num_complex::Complex64;
+use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::backtrace::Backtrace;
 use std::cell::RefCell;
+use std::collections::HashMap;
 use std::fmt::{self, Debug};
-use std::rc::Rc;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
 
 /// 张量数据结构，包含数据、梯度、依赖关系等。
+///
+/// 数据保存在可被多个张量共享的存储中，由布局决定如何解释，
+/// 因此 view、转置、切片得到的张量与原张量共享内存。
 #[derive(Clone)]
 pub struct TensorData {
-    /// 张量的实际数据
-    pub data: ArrayD<f32>,
+    /// 底层存储
+    storage: Arc<Storage>,
+    /// 在存储中的形状、步长与偏移
+    layout: Layout,
+    /// 是否为复数张量，复数的实部与虚部相邻保存在实数存储中
+    complex: bool,
     /// 梯度
     pub grad: Option<ArrayD<f32>>,
     /// 是否需要计算梯度
     pub requires_grad: bool,
     /// 创建该张量的操作
-    pub creator: Option<Rc<dyn Op>>,
+    pub creator: Option<Arc<dyn Op>>,
     /// 父节点（依赖的张量）
-    pub parents: Vec<Rc<RefCell<TensorData>>>,
+    pub parents: Vec<Arc<TensorCell>>,
+    /// 创建该张量的计算图是否已在反向传播后释放
+    pub graph_freed: bool,
+    /// 前向模式求导的切线及其所属的对偶层级
+    pub(crate) tangent: Option<(usize, ArrayD<f32>)>,
+    /// 反向传播结束后是否保留非叶子节点的梯度
+    pub(crate) retains_grad: bool,
+    /// 梯度钩子及其编号
+    pub(crate) hooks: Vec<(usize, GradHook)>,
+    /// 叶子节点梯度累加后调用的钩子及其编号
+    pub(crate) post_accumulate_hooks: Vec<(usize, PostAccumulateGradHook)>,
+    /// 开启异常检测时记录的创建位置
+    pub(crate) creation_trace: Option<Arc<Backtrace>>,
 }
 
 // 实现Debug trait以便于调试输出
@@ -26,14 +57,22 @@ pub struct TensorData {
 impl Debug for TensorData {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "tensor(")?;
-        if self.data.len() <= 64 {
-            write!(f, "{:?}", self.data)?;
+        if self.layout.numel() <= 64 {
+            match self.dtype() {
+                DType::Complex64 | DType::Complex128 => write!(f, "{}", self.data_complex())?,
+                DType::F64 => write!(f, "{:?}", self.data_f64())?,
+                DType::I32 | DType::I64 | DType::U8 => {
+                    write!(f, "{:?}", self.data_f64().mapv(|x| x as i64))?
+                }
+                DType::Bool => write!(f, "{:?}", self.data_f64().mapv(|x| x != 0.0))?,
+                DType::F32 | DType::F16 | DType::BF16 => write!(f, "{:?}", self.data())?,
+            }
         } else {
             write!(
                 f,
                 "[...tensor of size {}]",
-                self.data
-                    .shape()
+                self.layout
+                    .shape
                     .iter()
                     .map(|d| d.to_string())
                     .collect::<Vec<_>>()
@@ -41,6 +80,10 @@ impl Debug for TensorData {
             )?;
         }
 
+        if self.dtype() != DType::F32 {
+            write!(f, ", dtype={}", self.dtype())?;
+        }
+
         // 显示梯度状态
         if self.requires_grad {
             write!(f, ", requires_grad=true")?;
@@ -67,38 +110,369 @@ impl Debug for TensorData {
 impl TensorData {
     /// 创建新的张量数据
     pub fn new(data: ArrayD<f32>) -> Self {
+        let layout = Layout::contiguous(data.shape());
+        let data = data.as_standard_layout().iter().copied().collect();
+        TensorData::from_storage(Arc::new(Storage::new(data)), layout)
+    }
+
+    /// 把 f64 数据转换为 `dtype` 创建张量数据，复数类型的虚部为 0
+    pub fn with_dtype(data: ArrayD<f64>, dtype: DType) -> Self {
+        if dtype.is_complex() {
+            return TensorData::from_complex(data.mapv(|x| Complex64::new(x, 0.0)), dtype);
+        }
+        let layout = Layout::contiguous(data.shape());
+        let data = data
+            .as_standard_layout()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        TensorData::from_storage(Arc::new(Storage::from_f64(data, dtype)), layout)
+    }
+
+    /// 把复数数据转换为复数类型 `dtype` 创建张量数据
+    pub fn from_complex(data: ArrayD<Complex64>, dtype: DType) -> Self {
+        assert!(
+            dtype.is_complex(),
+            "Expected a complex dtype, got {}",
+            dtype
+        );
+        let layout = Layout::contiguous(data.shape());
+        let pairs = complex_to_pairs(&data);
+        let pairs = pairs
+            .as_standard_layout()
+            .iter()
+            .copied()
+            .collect::<Vec<_>>();
+        let storage = Storage::from_f64(pairs, dtype.to_real());
+        let layout = Layout {
+            strides: layout.strides.iter().map(|&stride| stride * 2).collect(),
+            ..layout
+        };
+        TensorData::from_parts(Arc::new(storage), layout, true)
+    }
+
+    /// 用已有的存储和布局创建张量数据，不复制数据
+    pub fn from_storage(storage: Arc<Storage>, layout: Layout) -> Self {
+        TensorData::from_parts(storage, layout, false)
+    }
+
+    /// 把已有的实数存储按复数布局解释为复数张量数据，不复制数据
+    pub fn from_complex_storage(storage: Arc<Storage>, layout: Layout) -> Self {
+        TensorData::from_parts(storage, layout, true)
+    }
+
+    /// 与自身共享存储、按 `layout` 解释数据的张量数据，保持是否为复数
+    pub(crate) fn share_storage(&self, layout: Layout) -> Self {
+        TensorData::from_parts(Arc::clone(&self.storage), layout, self.complex)
+    }
+
+    fn from_parts(storage: Arc<Storage>, layout: Layout, complex: bool) -> Self {
+        if complex && !matches!(storage.dtype(), DType::F32 | DType::F64) {
+            panic!(
+                "Complex tensors need f32 or f64 storage, got {}",
+                storage.dtype()
+            );
+        }
+        // 复数的虚部在实部之后
+        let end = layout
+            .shape
+            .iter()
+            .zip(&layout.strides)
+            .map(|(&size, &stride)| size.saturating_sub(1) * stride)
+            .sum::<usize>()
+            + layout.offset
+            + complex as usize;
+        if layout.numel() > 0 && end >= storage.len() {
+            panic!(
+                "Layout {:?} is out of bounds for storage of size {}",
+                layout,
+                storage.len()
+            );
+        }
         TensorData {
+            storage,
+            layout,
+            complex,
             grad: None,
-            data,
             requires_grad: false,
             creator: None,
             parents: Vec::new(),
+            graph_freed: false,
+            tangent: None,
+            retains_grad: false,
+            hooks: Vec::new(),
+            post_accumulate_hooks: Vec::new(),
+            creation_trace: None,
+        }
+    }
+
+    /// 底层存储
+    pub fn storage(&self) -> &Arc<Storage> {
+        &self.storage
+    }
+
+    /// 在存储中的布局
+    pub fn layout(&self) -> &Layout {
+        &self.layout
+    }
+
+    /// 张量形状
+    pub fn shape(&self) -> &[usize] {
+        &self.layout.shape
+    }
+
+    /// 元素类型
+    pub fn dtype(&self) -> DType {
+        if self.complex {
+            self.storage.dtype().to_complex()
+        } else {
+            self.storage.dtype()
+        }
+    }
+
+    /// 是否为复数张量
+    pub fn is_complex(&self) -> bool {
+        self.complex
+    }
+
+    /// 梯度的形状：复数张量的梯度按 `view_as_real` 保存，末尾多一维长度 2
+    pub fn grad_shape(&self) -> Vec<usize> {
+        if self.complex {
+            self.layout.view_as_real().shape
+        } else {
+            self.layout.shape.clone()
+        }
+    }
+
+    /// 按布局读出的数据副本，转换为 f32
+    pub fn data(&self) -> ArrayD<f32> {
+        self.check_real();
+        self.storage.read(&self.layout)
+    }
+
+    /// 按布局读出的数据副本，转换为 f64
+    pub fn data_f64(&self) -> ArrayD<f64> {
+        self.check_real();
+        self.storage.read_f64(&self.layout)
+    }
+
+    /// 按布局读出的复数数据副本，实数张量的虚部为 0
+    pub fn data_complex(&self) -> ArrayD<Complex64> {
+        if self.complex {
+            pairs_to_complex(&self.storage.read_f64(&self.layout.view_as_real()))
+        } else {
+            self.data_f64().mapv(|x| Complex64::new(x, 0.0))
         }
     }
 
-    /// 设置是否需要梯度
+    /// 按梯度的形状读出 f32 数据：实数张量即 `data()`，复数张量按 `view_as_real` 读出实部与虚部
+    pub fn data_as_real(&self) -> ArrayD<f32> {
+        if self.complex {
+            self.storage.read(&self.layout.view_as_real())
+        } else {
+            self.data()
+        }
+    }
+
+    /// 不支持复数的运算读取数据时 panic，而不是只读到实部
+    fn check_real(&self) {
+        if self.complex {
+            panic!(
+                "Operation does not support complex tensors, use data_complex() or view_as_real() to read them"
+            );
+        }
+    }
+
+    /// 原地修改数据，所有共享存储的张量都会看到修改，存储的版本号加一。
+    ///
+    /// 复数张量按 `view_as_real` 的形状修改，与梯度的形状一致。
+    pub fn update_data(&self, f: impl FnOnce(ArrayViewMutD<'_, f32>)) {
+        self.storage.write(&self.write_layout(), f);
+    }
+
+    /// 与 `update_data` 相同，但以 f64 修改数据，f64、i64 与 complex128 不会损失精度
+    pub fn update_data_f64(&self, f: impl FnOnce(ArrayViewMutD<'_, f64>)) {
+        self.storage.write_f64(&self.write_layout(), f);
+    }
+
+    /// 存储是否只能以 f64 无损修改，此时应使用 `update_data_f64`
+    pub fn needs_f64_update(&self) -> bool {
+        !matches!(
+            self.storage.dtype(),
+            DType::F32 | DType::F16 | DType::BF16 | DType::U8 | DType::Bool
+        )
+    }
+
+    /// 原地修改时使用的布局，复数张量按 `view_as_real` 的形状修改
+    fn write_layout(&self) -> Layout {
+        if self.complex {
+            self.layout.view_as_real()
+        } else {
+            self.layout.clone()
+        }
+    }
+
+    /// 设置是否需要梯度，只有浮点与复数类型可以求梯度
     pub fn requires_grad(mut self, requires_grad: bool) -> Self {
+        check_grad_dtype(self.dtype(), requires_grad);
         self.requires_grad = requires_grad;
         if requires_grad && self.grad.is_none() {
-            self.grad = Some(ndarray::Array::zeros(self.data.raw_dim()));
+            self.grad = Some(ndarray::Array::zeros(self.grad_shape()));
         }
         self
     }
 
     /// 设置创建该张量的操作
-    pub fn set_creator(&mut self, op: Rc<dyn Op>) {
+    pub fn set_creator(&mut self, op: Arc<dyn Op>) {
         self.creator = Some(op);
+        self.creation_trace = capture_trace().map(Arc::new);
     }
 
     /// 添加父节点
     pub fn add_parent(&mut self, parent: &Tensor) {
-        self.parents.push(Rc::clone(&parent.0));
+        self.parents.push(Arc::clone(&parent.0));
+    }
+}
+
+/// 浮点与复数以外类型的张量不能求梯度
+fn check_grad_dtype(dtype: DType, requires_grad: bool) {
+    if requires_grad && !dtype.is_floating_point() && !dtype.is_complex() {
+        panic!(
+            "Only Tensors of floating point and complex dtype can require gradients, got {}",
+            dtype
+        );
+    }
+}
+
+/// 张量数据外的读写锁，提供与 `RefCell` 相同的 `borrow`/`borrow_mut` 接口，可以在线程间共享。
+///
+/// 同一线程内可以嵌套读取；不同线程之间的读写互相等待。与 `RefCell` 一样，
+/// 同一线程在持有读取时写入、或在持有写入时再次读写会 panic，而不是永远等待自己释放锁。
+pub struct TensorCell(RwLock<TensorData>);
+
+thread_local! {
+    /// 当前线程持有的借用：正数为读取的层数，`-1` 为写入，按 `TensorCell` 的地址索引
+    static BORROWS: RefCell<HashMap<usize, isize>> = RefCell::new(HashMap::new());
+}
+
+/// 在当前线程登记一次借用，与已有的借用冲突时 panic
+fn register_borrow(cell: usize, write: bool) {
+    BORROWS.with(|borrows| {
+        let mut borrows = borrows.borrow_mut();
+        let state = borrows.entry(cell).or_insert(0);
+        match (*state, write) {
+            (-1, false) => panic!("Tensor is already mutably borrowed on this thread"),
+            (0, true) => *state = -1,
+            (_, true) => panic!("Tensor is already borrowed on this thread"),
+            (_, false) => *state += 1,
+        }
+    });
+}
+
+/// 释放 `register_borrow` 登记的借用
+fn release_borrow(cell: usize) {
+    BORROWS.with(|borrows| {
+        let mut borrows = borrows.borrow_mut();
+        if let Some(state) = borrows.get_mut(&cell) {
+            if *state > 1 {
+                *state -= 1;
+            } else {
+                borrows.remove(&cell);
+            }
+        }
+    });
+}
+
+/// `TensorCell::borrow` 返回的读取守卫
+pub struct TensorRef<'a> {
+    guard: RwLockReadGuard<'a, TensorData>,
+    cell: usize,
+}
+
+impl Deref for TensorRef<'_> {
+    type Target = TensorData;
+
+    fn deref(&self) -> &TensorData {
+        &self.guard
+    }
+}
+
+impl Drop for TensorRef<'_> {
+    fn drop(&mut self) {
+        release_borrow(self.cell);
+    }
+}
+
+/// `TensorCell::borrow_mut` 返回的写入守卫
+pub struct TensorRefMut<'a> {
+    guard: RwLockWriteGuard<'a, TensorData>,
+    cell: usize,
+}
+
+impl Deref for TensorRefMut<'_> {
+    type Target = TensorData;
+
+    fn deref(&self) -> &TensorData {
+        &self.guard
+    }
+}
+
+impl DerefMut for TensorRefMut<'_> {
+    fn deref_mut(&mut self) -> &mut TensorData {
+        &mut self.guard
+    }
+}
+
+impl Drop for TensorRefMut<'_> {
+    fn drop(&mut self) {
+        release_borrow(self.cell);
+    }
+}
+
+impl TensorCell {
+    pub fn new(data: TensorData) -> Self {
+        TensorCell(RwLock::new(data))
+    }
+
+    /// 读取张量数据
+    ///
+    /// # Panics
+    /// 当前线程正持有该张量的 `borrow_mut` 时 panic。
+    pub fn borrow(&self) -> TensorRef<'_> {
+        let cell = self as *const TensorCell as usize;
+        register_borrow(cell, false);
+        TensorRef {
+            guard: self.0.read_recursive(),
+            cell,
+        }
+    }
+
+    /// 修改张量数据
+    ///
+    /// # Panics
+    /// 当前线程正持有该张量的 `borrow` 或 `borrow_mut` 时 panic。
+    pub fn borrow_mut(&self) -> TensorRefMut<'_> {
+        let cell = self as *const TensorCell as usize;
+        register_borrow(cell, true);
+        TensorRefMut {
+            guard: self.0.write(),
+            cell,
+        }
+    }
+}
+
+impl Debug for TensorCell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.borrow().fmt(f)
     }
 }
 
 /// 张量类型，自动微分的核心对象。
+///
+/// 张量是 `Send + Sync` 的，可以在线程间共享用于多线程推理；
+/// 各线程分别构建计算图并反向传播时，梯度会累加到共享的叶子节点上。
 #[derive(Clone)]
-pub struct Tensor(pub Rc<RefCell<TensorData>>);
+pub struct Tensor(pub Arc<TensorCell>);
 
 // 标准Debug实现
 impl Debug for Tensor {
@@ -110,23 +484,64 @@ impl Debug for Tensor {
 impl Tensor {
     /// 用数据创建新张量
     pub fn new(data: ArrayD<f32>) -> Self {
-        Tensor(Rc::new(RefCell::new(TensorData::new(data))))
+        Tensor(Arc::new(TensorCell::new(TensorData::new(data))))
+    }
+
+    /// 把 f64 数据转换为 `dtype` 创建新张量
+    pub fn with_dtype(data: ArrayD<f64>, dtype: DType) -> Self {
+        Tensor(Arc::new(TensorCell::new(TensorData::with_dtype(
+            data, dtype,
+        ))))
+    }
+
+    /// 由复数数据创建复数类型 `dtype` 的新张量
+    pub fn from_complex(data: ArrayD<Complex64>, dtype: DType) -> Self {
+        Tensor(Arc::new(TensorCell::new(TensorData::from_complex(
+            data, dtype,
+        ))))
+    }
+
+    /// 元素类型
+    pub fn dtype(&self) -> DType {
+        self.0.borrow().dtype()
+    }
+
+    /// 是否为浮点类型
+    pub fn is_floating_point(&self) -> bool {
+        self.dtype().is_floating_point()
+    }
+
+    /// 是否为复数类型
+    pub fn is_complex(&self) -> bool {
+        self.0.borrow().is_complex()
+    }
+
+    /// 转换为 `dtype`，类型相同时返回自身。
+    ///
+    /// 浮点与复数类型之间的转换可以求导，实数转换为复数时梯度取实部传回；
+    /// 转换为整数或布尔类型的结果不记录计算图。复数不能直接转换为实数，请使用 `real()` 或 `abs()`。
+    pub fn to_dtype(&self, dtype: DType) -> Tensor {
+        if self.dtype() == dtype {
+            self.clone()
+        } else {
+            ToDtype::new(dtype).forward(&[self])
+        }
     }
 
     /// 获取张量形状
     pub fn shape(&self) -> Vec<usize> {
-        self.0.borrow().data.shape().to_vec()
+        self.0.borrow().shape().to_vec()
     }
 
     /// 获取张量维度数
     pub fn dim(&self) -> usize {
-        self.0.borrow().data.ndim()
+        self.0.borrow().layout.ndim()
     }
 
     /// 获取指定维度的长度
     pub fn size(&self, dim: usize) -> Option<usize> {
         if dim < self.dim() {
-            Some(self.0.borrow().data.shape()[dim])
+            Some(self.0.borrow().shape()[dim])
         } else {
             None
         }
@@ -134,7 +549,7 @@ impl Tensor {
 
     /// 获取元素总数
     pub fn numel(&self) -> usize {
-        self.0.borrow().data.len()
+        self.0.borrow().layout.numel()
     }
 
     /// 沿第0维堆叠张量
@@ -150,52 +565,160 @@ impl Tensor {
             }
         }
 
-        // 先clone所有数据，避免生命周期问题
-        let arrays: Vec<_> = tensors.iter().map(|t| t.0.borrow().data.clone()).collect();
+        // 先在第0维插入新轴，再沿第0维拼接
+        let unsqueezed = tensors
+            .iter()
+            .map(|t| t.unsqueeze(0))
+            .collect::<Result<Vec<_>, _>>()?;
+        Tensor::cat(&unsqueezed, 0)
+    }
+
+    /// 视图变换，与原张量共享存储；内存布局无法直接解释为新形状时返回错误
+    pub fn view(&self, shape: &[usize]) -> Result<Tensor, &'static str> {
+        let total_elements = self.numel();
+        let new_total = shape.iter().product();
 
-        let views: Vec<_> = arrays.iter().map(|a| a.view()).collect();
+        if total_elements != new_total {
+            return Err("新形状的元素数量必须与原形状相同");
+        }
+        if self.0.borrow().layout.view(shape).is_none() {
+            return Err("张量的内存布局与新形状不兼容，请使用reshape");
+        }
 
-        let stacked_data = ndarray::stack(Axis(0), &views).map_err(|_| "无法堆叠张量")?;
-        Ok(Tensor::new(stacked_data))
+        Ok(Reshape::new(shape).forward(&[self]))
     }
 
-    /// 视图变换
-    pub fn view(&self, shape: &[usize]) -> Result<Tensor, &'static str> {
-        let borrowed = self.0.borrow();
-        let total_elements = borrowed.data.len();
+    /// 重塑形状，能作为视图时不复制数据，否则复制
+    pub fn reshape(&self, shape: &[usize]) -> Result<Tensor, &'static str> {
+        let total_elements = self.numel();
         let new_total = shape.iter().product();
 
         if total_elements != new_total {
             return Err("新形状的元素数量必须与原形状相同");
         }
 
-        let reshaped_data = borrowed
-            .data
-            .clone()
-            .into_shape(IxDyn(shape))
-            .map_err(|_| "无法重新调整形状")?;
+        Ok(Reshape::new(shape).forward(&[self]))
+    }
+
+    /// 是否按行优先连续存放，复数张量按 `view_as_real` 的布局判断
+    pub fn is_contiguous(&self) -> bool {
+        let data = self.0.borrow();
+        if data.complex {
+            data.layout.view_as_real().is_contiguous()
+        } else {
+            data.layout.is_contiguous()
+        }
+    }
+
+    /// 返回连续存放的张量，已经连续时返回自身
+    pub fn contiguous(&self) -> Tensor {
+        if self.is_contiguous() {
+            self.clone()
+        } else {
+            Contiguous.forward(&[self])
+        }
+    }
+
+    /// 各维度的步长（以元素计）
+    pub fn stride(&self) -> Vec<usize> {
+        self.0.borrow().layout.strides.clone()
+    }
 
-        Ok(Tensor::new(reshaped_data))
+    /// 在存储中的起始偏移
+    pub fn storage_offset(&self) -> usize {
+        self.0.borrow().layout.offset
     }
 
-    /// 重塑形状
-    pub fn reshape(&self, shape: &[usize]) -> Result<Tensor, &'static str> {
-        self.view(shape)
+    /// 是否与另一个张量共享存储
+    pub fn shares_storage(&self, other: &Tensor) -> bool {
+        Arc::ptr_eq(&self.0.borrow().storage, &other.0.borrow().storage)
     }
 
-    /// 设置是否需要梯度（链式调用）
+    /// 存储的版本号，每次原地修改后加一
+    pub fn version(&self) -> usize {
+        self.0.borrow().storage.version()
+    }
+
+    /// 是否为推理模式下创建的张量
+    pub fn is_inference(&self) -> bool {
+        self.0.borrow().storage.is_inference()
+    }
+
+    /// 原地修改数据，梯度模式开启时需要梯度的张量不允许原地修改。
+    ///
+    /// f32 无法精确表示的元素类型用 `f64_op` 修改，其余用 `f32_op`。
+    fn modify_inplace(
+        &self,
+        f32_op: impl FnOnce(ArrayViewMutD<'_, f32>),
+        f64_op: impl FnOnce(ArrayViewMutD<'_, f64>),
+    ) -> Result<(), &'static str> {
+        let borrowed = self.0.borrow();
+        if borrowed.requires_grad && is_grad_enabled() {
+            return Err("需要梯度的张量不能进行原地操作");
+        }
+        if borrowed.complex {
+            return Err("复数张量不支持原地操作");
+        }
+        if borrowed.needs_f64_update() {
+            borrowed.update_data_f64(f64_op);
+        } else {
+            borrowed.update_data(f32_op);
+        }
+        Ok(())
+    }
+
+    /// 原地用 `value` 填充
+    pub fn fill_(&self, value: f32) -> Result<(), &'static str> {
+        self.modify_inplace(
+            |mut view| view.fill(value),
+            |mut view| view.fill(value as f64),
+        )
+    }
+
+    /// 原地置零
+    pub fn zero_(&self) -> Result<(), &'static str> {
+        self.fill_(0.0)
+    }
+
+    /// 原地复制 `src` 的数据，`src` 可以广播到自身形状
+    pub fn copy_(&self, src: &Tensor) -> Result<(), &'static str> {
+        let src = src.data_f64();
+        if src.broadcast(self.shape()).is_none() {
+            return Err("源张量的形状无法广播到目标张量");
+        }
+        self.modify_inplace(
+            |mut view| view.assign(&src.mapv(|x| x as f32)),
+            |mut view| view.assign(&src),
+        )
+    }
+
+    /// 原地加上 `other`，`other` 可以广播到自身形状
+    pub fn add_(&self, other: &Tensor) -> Result<(), &'static str> {
+        let other = other.data_f64();
+        if other.broadcast(self.shape()).is_none() {
+            return Err("源张量的形状无法广播到目标张量");
+        }
+        self.modify_inplace(
+            |mut view| view += &other.mapv(|x| x as f32),
+            |mut view| view += &other,
+        )
+    }
+
+    /// 设置是否需要梯度（链式调用），只有浮点类型可以求梯度
     pub fn require_grad(&self, requires_grad: bool) -> Self {
         let mut borrowed = self.0.borrow_mut();
+        check_grad_dtype(borrowed.dtype(), requires_grad);
         borrowed.requires_grad = requires_grad;
         if requires_grad && borrowed.grad.is_none() {
-            borrowed.grad = Some(ndarray::Array::zeros(borrowed.data.raw_dim()));
+            borrowed.grad = Some(ndarray::Array::zeros(borrowed.grad_shape()));
         }
         self.clone()
     }
 
     /// 是否为叶子节点
     pub fn is_leaf(&self) -> bool {
-        self.0.borrow().creator.is_none()
+        let data = self.0.borrow();
+        data.creator.is_none() && !data.graph_freed
     }
 
     /// 创建全0张量
@@ -228,18 +751,33 @@ impl Tensor {
         Tensor::ones(&self.shape())
     }
 
-    /// 获取数据副本
+    /// 获取转换为 f32 的数据副本
     pub fn data(&self) -> ArrayD<f32> {
-        self.0.borrow().data.clone()
+        self.0.borrow().data()
+    }
+
+    /// 获取转换为 f64 的数据副本，f64 与整数类型不会损失精度
+    pub fn data_f64(&self) -> ArrayD<f64> {
+        self.0.borrow().data_f64()
+    }
+
+    /// 获取复数数据副本，实数张量的虚部为 0
+    pub fn data_complex(&self) -> ArrayD<Complex64> {
+        self.0.borrow().data_complex()
     }
 
     /// 获取标量值
     pub fn item(&self) -> Result<Self, &'static str> {
         let borrowed = self.0.borrow();
-        if borrowed.data.ndim() != 0 {
+        if borrowed.layout.ndim() != 0 {
             return Err("只能对标量张量调用item");
         }
-        Ok(Tensor::new(borrowed.data.clone()))
+        let data = if borrowed.complex {
+            TensorData::from_complex(borrowed.data_complex(), borrowed.dtype())
+        } else {
+            TensorData::with_dtype(borrowed.data_f64(), borrowed.dtype())
+        };
+        Ok(Tensor(Arc::new(TensorCell::new(data))))
     }
 
     /// 按索引取子张量
@@ -248,59 +786,49 @@ impl Tensor {
             return Err("索引维度必须匹配张量维度");
         }
 
-        let borrowed = self.0.borrow();
-        let mut indices = Vec::new();
-
-        for (i, &idx) in index.iter().enumerate() {
-            if idx >= borrowed.data.shape()[i] {
+        for (&idx, size) in index.iter().zip(self.shape()) {
+            if idx >= size {
                 return Err("索引超出范围");
             }
-            indices.push(idx);
         }
 
-        // 简单实现，后续可以扩展
-        let mut result = borrowed.data.clone();
-        for (i, &idx) in indices.iter().enumerate().rev() {
-            result = result.index_axis(Axis(i), idx).to_owned();
+        // 从最后一维开始逐维选取，前面维度的下标不受影响
+        let mut result = self.clone();
+        for (i, &idx) in index.iter().enumerate().rev() {
+            result = result.select(i, idx)?;
         }
 
-        Ok(Tensor::new(result))
+        Ok(result)
     }
 
     /// 返回不带梯度的新张量
     pub fn detach(&self) -> Self {
-        // 创建一个新的TensorData，但共享原始数据（不进行深拷贝）
+        // 创建一个新的TensorData，共享原始存储（不进行深拷贝）
         let borrowed = self.0.borrow();
 
-        // 创建一个新的TensorData对象，只共享数据
-        let new_data = TensorData {
-            data: borrowed.data.clone(), // 只克隆数据数组
-            grad: None,                  // 不需要梯度
-            requires_grad: false,        // 不需要计算梯度
-            creator: None,               // 没有创建者
-            parents: Vec::new(),         // 没有父节点
-        };
+        // 新的TensorData没有梯度、创建者和父节点
+        let new_data = borrowed.share_storage(borrowed.layout.clone());
 
         // 创建一个新的Tensor，包装新的TensorData
-        Tensor(Rc::new(RefCell::new(new_data)))
+        Tensor(Arc::new(TensorCell::new(new_data)))
     }
 
     /// 挤压指定或所有为1的维度
     pub fn squeeze(&self, dim: Option<usize>) -> Result<Tensor, &'static str> {
-        let borrowed = self.0.borrow();
+        let shape = self.shape();
         let mut new_shape: Vec<usize> = Vec::new();
 
         match dim {
             Some(d) => {
-                if d >= borrowed.data.ndim() {
+                if d >= shape.len() {
                     return Err("维度超出范围");
                 }
 
-                if borrowed.data.shape()[d] != 1 {
+                if shape[d] != 1 {
                     return Err("只能挤压大小为1的维度");
                 }
 
-                for (i, &size) in borrowed.data.shape().iter().enumerate() {
+                for (i, &size) in shape.iter().enumerate() {
                     if i != d {
                         new_shape.push(size);
                     }
@@ -308,7 +836,7 @@ impl Tensor {
             }
             None => {
                 // 挤压所有大小为1的维度
-                for &size in borrowed.data.shape() {
+                for &size in &shape {
                     if size != 1 {
                         new_shape.push(size);
                     }
@@ -316,19 +844,12 @@ impl Tensor {
             }
         }
 
-        let reshaped = borrowed
-            .data
-            .clone()
-            .into_shape(IxDyn(&new_shape))
-            .map_err(|_| "重塑失败")?;
-
-        Ok(Tensor::new(reshaped))
+        self.view(&new_shape)
     }
 
     /// 在指定维度插入新轴
     pub fn unsqueeze(&self, dim: usize) -> Result<Tensor, &'static str> {
-        let borrowed = self.0.borrow();
-        let mut new_shape = borrowed.data.shape().to_vec();
+        let mut new_shape = self.shape();
 
         if dim > new_shape.len() {
             return Err("维度超出范围");
@@ -336,13 +857,7 @@ impl Tensor {
 
         new_shape.insert(dim, 1);
 
-        let reshaped = borrowed
-            .data
-            .clone()
-            .into_shape(IxDyn(&new_shape))
-            .map_err(|_| "重塑失败")?;
-
-        Ok(Tensor::new(reshaped))
+        self.view(&new_shape)
     }
 }
 
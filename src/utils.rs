@@ -1,11 +1,15 @@
 use crate::tensor::Tensor;
+use rand::rng;
 use rand::seq::SliceRandom;
-use rand::thread_rng;
 
 /// 数据集 trait，类似 PyTorch 的 Dataset。
 pub trait Dataset {
     /// 返回数据集长度
     fn len(&self) -> usize;
+    /// 数据集是否为空
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
     /// 获取指定索引的数据和目标
     fn get(&self, idx: usize) -> (Tensor, Tensor);
 }
@@ -53,7 +57,7 @@ impl<'a, D: Dataset> DataLoader<'a, D> {
     pub fn new(dataset: &'a D, batch_size: usize, shuffle: bool) -> Self {
         let mut indices: Vec<usize> = (0..dataset.len()).collect();
         if shuffle {
-            indices.shuffle(&mut thread_rng());
+            indices.shuffle(&mut rng());
         }
         DataLoader {
             dataset,
@@ -68,7 +72,7 @@ impl<'a, D: Dataset> DataLoader<'a, D> {
     pub fn reset(&mut self) {
         self.idx = 0;
         if self.shuffle {
-            self.indices.shuffle(&mut thread_rng());
+            self.indices.shuffle(&mut rng());
         }
     }
 }
@@ -89,10 +93,10 @@ impl<'a, D: Dataset> Iterator for DataLoader<'a, D> {
             batch_data.push(data);
             batch_targets.push(target);
         }
-        let batch_data = Tensor::stack(&batch_data.as_slice())
+        let batch_data = Tensor::stack(batch_data.as_slice())
             .unwrap()
             .require_grad(false);
-        let batch_targets = Tensor::stack(&batch_targets.as_slice())
+        let batch_targets = Tensor::stack(batch_targets.as_slice())
             .unwrap()
             .require_grad(false);
         self.idx = end;
@@ -11,7 +11,7 @@ mod tests {
         let result = &a + &b;
 
         let expected = array![[6.0, 8.0], [10.0, 12.0]].into_dyn();
-        assert_eq!(result.0.borrow().data, expected);
+        assert_eq!(result.data(), expected);
     }
 
     #[test]
@@ -60,7 +60,7 @@ mod tests {
         ]
         .into_dyn();
 
-        assert_eq!(result.0.borrow().data, expected);
+        assert_eq!(result.data(), expected);
     }
 
     #[test]
@@ -128,7 +128,7 @@ mod tests {
         .into_dyn();
 
         assert_eq!(result.shape(), vec![3, 2, 2]);
-        assert_eq!(result.0.borrow().data, expected);
+        assert_eq!(result.data(), expected);
     }
 
     #[test]
@@ -171,6 +171,14 @@ mod tests {
         let result = &a + &b; // b 应该被广播到 a 的形状
 
         let expected = array![[6.0, 8.0], [8.0, 10.0]].into_dyn();
-        assert_eq!(result.0.borrow().data, expected);
+        assert_eq!(result.data(), expected);
+    }
+
+    #[test]
+    fn test_tensor_add_scalar() {
+        let a = Tensor::new(array![1.0, 2.0].into_dyn());
+        assert_eq!((&a + 1.0_f32).data(), array![2.0, 3.0].into_dyn());
+        assert_eq!((&a + 1.0_f64).data(), array![2.0, 3.0].into_dyn());
+        assert_eq!((0.5_f64 + &a).data(), array![1.5, 2.5].into_dyn());
     }
 }
@@ -0,0 +1,71 @@
+use ndarray::array;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use torch_rs::autograd::{detect_anomaly, is_anomaly_enabled, set_detect_anomaly};
+use torch_rs::tensor::Tensor;
+
+/// 运行 `f` 并取出 panic 信息
+fn panic_message(f: impl FnOnce()) -> String {
+    let payload = catch_unwind(AssertUnwindSafe(f)).expect_err("expected a panic");
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast::<&str>().unwrap().to_string(),
+    }
+}
+
+fn sqrt_at_zero(x: &Tensor) -> Tensor {
+    // d sqrt(x) / dx 在 0 处为无穷
+    (&x.sqrt() * 2.0_f32).sum()
+}
+
+fn input() -> Tensor {
+    Tensor::new(array![0.0, 4.0].into_dyn()).require_grad(true)
+}
+
+#[test]
+fn test_anomaly_reports_op_and_forward_trace() {
+    let _guard = detect_anomaly();
+    let loss = sqrt_at_zero(&input());
+    let message = panic_message(|| loss.backward());
+    assert!(message.contains("Sqrt returned inf values in its gradient for input 0"));
+    assert!(message.contains("Traceback of forward call"));
+    assert!(message.contains("sqrt_at_zero"), "{}", message);
+}
+
+#[test]
+fn test_anomaly_without_forward_trace() {
+    // 前向计算时没有开启，只能报告运算
+    let loss = sqrt_at_zero(&input());
+    let _guard = detect_anomaly();
+    let message = panic_message(|| loss.backward());
+    assert!(message.contains("Sqrt returned inf values"));
+    assert!(message.contains("No forward traceback was recorded"));
+}
+
+#[test]
+fn test_anomaly_detects_nan_in_grad() {
+    let _guard = detect_anomaly();
+    let x = Tensor::new(array![-1.0, 4.0].into_dyn()).require_grad(true);
+    let y = x.sqrt().sum();
+    let message = panic_message(|| {
+        torch_rs::autograd::grad(&[&y], &[&x], None, false).unwrap();
+    });
+    assert!(message.contains("returned nan values"), "{}", message);
+}
+
+#[test]
+fn test_anomaly_mode_is_scoped() {
+    assert!(!is_anomaly_enabled());
+    {
+        let _guard = detect_anomaly();
+        assert!(is_anomaly_enabled());
+        let _inner = set_detect_anomaly(false);
+        assert!(!is_anomaly_enabled());
+    }
+    assert!(!is_anomaly_enabled());
+
+    // 不开启时不做检查，梯度中直接出现无穷
+    let x = input();
+    sqrt_at_zero(&x).backward();
+    let grad = x.0.borrow().grad.clone().unwrap();
+    assert!(grad[[0]].is_infinite());
+}
@@ -85,4 +85,99 @@ mod tests {
         println!("Bias  :{:?}", bias);
         println!("Output:{:?}", output);
     }
+
+    #[test]
+    fn test_backward_with_output_grad() {
+        // 非标量输出：以 v 为输出梯度得到 vᵀ · J
+        let x = Tensor::new(array![1.0, 2.0, 3.0].into_dyn()).require_grad(true);
+        let y = &x * &x;
+        let v = Tensor::new(array![1.0, 0.5, -1.0].into_dyn());
+        y.backward_with(&v, false).unwrap();
+        assert_eq!(
+            x.0.borrow().grad.clone().unwrap(),
+            array![2.0, 2.0, -6.0].into_dyn()
+        );
+
+        let z = &x * 2.0_f32;
+        assert!(z.backward_with(&Tensor::ones(&[2]), false).is_err());
+    }
+
+    #[test]
+    fn test_retain_graph() {
+        let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+        let y = (&(&x * &x) * 3.0_f32).sum();
+        let ones = Tensor::ones(&[]);
+        y.backward_with(&ones, true).unwrap();
+        y.backward_with(&ones, true).unwrap();
+        // 两次反向传播只在叶子节点上累加，中间节点的梯度每次重新计算
+        assert_eq!(
+            x.0.borrow().grad.clone().unwrap(),
+            array![12.0, 24.0].into_dyn()
+        );
+        assert!(!y.is_leaf());
+
+        y.backward_with(&ones, false).unwrap();
+        assert_eq!(
+            x.0.borrow().grad.clone().unwrap(),
+            array![18.0, 36.0].into_dyn()
+        );
+        // 计算图已释放，保存的张量与父节点都被丢弃
+        assert!(y.0.borrow().creator.is_none());
+        assert!(y.0.borrow().parents.is_empty());
+        assert!(y.backward_with(&ones, false).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "retain_graph")]
+    fn test_backward_through_freed_graph() {
+        let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+        let h = x.exp();
+        let a = h.sum();
+        let b = (&h * &h).sum();
+        a.backward();
+        // b 经过已释放的 h
+        b.backward();
+    }
+
+    #[test]
+    fn test_backward_with_reports_freed_graph() {
+        let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+        let h = x.exp();
+        h.sum().backward();
+        let b = (&h * &h).sum();
+        let err = b.backward_with(&Tensor::ones(&[]), false).unwrap_err();
+        assert!(err.contains("retain_graph"), "{}", err);
+        // 出错时不会写入任何梯度
+        assert_eq!(
+            x.0.borrow().grad.clone().unwrap(),
+            array![1.0_f32.exp(), 2.0_f32.exp()].into_dyn()
+        );
+    }
+    #[test]
+    fn test_add_with_constant_first_input() {
+        // 只有第二个输入需要梯度，且两者形状不同，梯度必须按位置分给父节点
+        let a = Tensor::new(array![1.0, 2.0, 3.0].into_dyn());
+        let b = Tensor::new(array![[1.0, 1.0, 1.0], [2.0, 2.0, 2.0]].into_dyn()).require_grad(true);
+        let y = (&(&a + &b) * &b).sum();
+        let expected = array![[3.0, 4.0, 5.0], [5.0, 6.0, 7.0]].into_dyn();
+
+        let g = torch_rs::autograd::grad(&[&y], &[&b], None, false).unwrap();
+        assert_eq!(g[0].data(), expected);
+        y.backward();
+        assert_eq!(b.0.borrow().grad.clone().unwrap(), expected);
+        // 不需要梯度的输入不参与反向传播
+        assert!(a.0.borrow().grad.is_none());
+    }
+
+    #[test]
+    fn test_deep_graph_backward() {
+        // 很深的计算图不会在拓扑排序时栈溢出
+        let x = Tensor::new(array![1.0].into_dyn()).require_grad(true);
+        let mut h = x.clone();
+        for _ in 0..20_000 {
+            h = &h + 1.0_f32;
+        }
+        h.sum().backward();
+        assert_eq!(x.0.borrow().grad.clone().unwrap(), array![1.0].into_dyn());
+    }
 }
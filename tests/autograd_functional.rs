@@ -0,0 +1,119 @@
+use ndarray::{ArrayD, array};
+use torch_rs::autograd::functional::{Strategy, hessian, hvp, jacobian, jvp, vjp};
+use torch_rs::autograd::grad;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::assert_rel_close;
+
+/// 沿最后一维做 `x ↦ A x` 再取 tanh，对第 0 维的批量逐行独立
+fn layer(x: &Tensor) -> Tensor {
+    let a = Tensor::new(array![[1.0, -2.0, 0.5], [0.3, 0.0, 1.5]].into_dyn());
+    x.matmul(&a.transpose(0, 1).unwrap()).tanh()
+}
+
+#[test]
+fn test_jacobian_strategies_agree() {
+    let x = Tensor::new(array![0.2, -0.4, 0.9].into_dyn());
+    let loop_jac = jacobian(layer, &x, false, Strategy::Loop).unwrap();
+    let vec_jac = jacobian(layer, &x, false, Strategy::Vectorized).unwrap();
+    assert_eq!(loop_jac.shape(), vec![2, 3]);
+    assert_rel_close(&vec_jac.data(), &loop_jac.data(), 1e-4);
+    assert!(!loop_jac.0.borrow().requires_grad);
+
+    // J = diag(1 - y²) · A
+    let y = layer(&x).data();
+    let a = array![[1.0, -2.0, 0.5], [0.3, 0.0, 1.5]];
+    let expected =
+        ArrayD::from_shape_fn(vec![2, 3], |i| (1.0 - y[i[0]] * y[i[0]]) * a[[i[0], i[1]]]);
+    assert_rel_close(&loop_jac.data(), &expected, 1e-4);
+}
+
+#[test]
+fn test_jacobian_of_elementwise_function() {
+    let x = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+    let jac = jacobian(|x| x.powf(2.0), &x, false, Strategy::Loop).unwrap();
+    assert_eq!(jac.shape(), vec![2, 2, 2, 2]);
+    let data = jac.data();
+    for (index, &value) in data.indexed_iter() {
+        let expected = if index[0] == index[2] && index[1] == index[3] {
+            2.0 * x.data()[[index[0], index[1]]]
+        } else {
+            0.0
+        };
+        assert_eq!(value, expected);
+    }
+
+    // 输出与输入无关时雅可比矩阵为 0
+    let constant = jacobian(|x| x.detach(), &x, false, Strategy::Loop).unwrap();
+    assert_eq!(constant.data(), ArrayD::zeros(vec![2, 2, 2, 2]));
+}
+
+#[test]
+fn test_vectorized_requires_batch_dim() {
+    let x = Tensor::new(array![1.0, 2.0].into_dyn());
+    assert!(jacobian(|x| x.sum(), &x, false, Strategy::Vectorized).is_err());
+    assert!(hessian(|x| x.powf(3.0).sum(), &x, false, Strategy::Vectorized).is_err());
+}
+
+#[test]
+fn test_hessian_strategies_agree() {
+    // f(x) = Σ x_i³ + x_0 x_1，H = diag(6x) + [[0, 1], [1, 0]]
+    let x = Tensor::new(array![1.0, -2.0].into_dyn());
+    let f = |x: &Tensor| {
+        let cubes = x.powf(3.0).sum_dims(&[x.dim() - 1], false);
+        let first = x.select(x.dim() - 1, 0).unwrap();
+        let second = x.select(x.dim() - 1, 1).unwrap();
+        &cubes + &(&first * &second)
+    };
+    let expected = array![[6.0, 1.0], [1.0, -12.0]].into_dyn();
+    let loop_hess = hessian(f, &x, false, Strategy::Loop).unwrap();
+    let vec_hess = hessian(f, &x, false, Strategy::Vectorized).unwrap();
+    assert_rel_close(&loop_hess.data(), &expected, 1e-4);
+    assert_rel_close(&vec_hess.data(), &expected, 1e-4);
+}
+
+#[test]
+fn test_vector_products_match_jacobian() {
+    let x = Tensor::new(array![0.2, -0.4, 0.9].into_dyn());
+    let jac = jacobian(layer, &x, false, Strategy::Loop).unwrap().data();
+    let jac = jac.into_dimensionality::<ndarray::Ix2>().unwrap();
+
+    let u = array![1.5, -0.5];
+    let (output, product) = vjp(layer, &x, &Tensor::new(u.clone().into_dyn()), false).unwrap();
+    assert_rel_close(&output.data(), &layer(&x).data(), 1e-4);
+    assert_rel_close(&product.data(), &u.dot(&jac).into_dyn(), 1e-4);
+
+    let v = array![0.3, 1.0, -2.0];
+    let (_, product) = jvp(layer, &x, &Tensor::new(v.clone().into_dyn()), false).unwrap();
+    assert_rel_close(&product.data(), &jac.dot(&v).into_dyn(), 1e-4);
+
+    assert!(vjp(layer, &x, &x, false).is_err());
+    assert!(jvp(layer, &x, &Tensor::ones(&[2]), false).is_err());
+}
+
+#[test]
+fn test_hvp_matches_hessian() {
+    let f = |x: &Tensor| (&x.exp() * &x.sin()).sum();
+    let x = Tensor::new(array![0.5, -1.0, 2.0].into_dyn());
+    let v = array![1.0, 2.0, -1.0];
+    let (output, product) = hvp(f, &x, &Tensor::new(v.clone().into_dyn()), false).unwrap();
+    assert_rel_close(&output.data(), &f(&x).data(), 1e-4);
+
+    let hess = hessian(f, &x, false, Strategy::Loop).unwrap().data();
+    let hess = hess.into_dimensionality::<ndarray::Ix2>().unwrap();
+    assert_rel_close(&product.data(), &hess.dot(&v).into_dyn(), 1e-4);
+    assert!(hvp(|x| x.exp(), &x, &x, false).is_err());
+}
+
+#[test]
+fn test_create_graph_keeps_results_differentiable() {
+    // d/dx trace(H(x)) 对 f = Σ x³ 为 6 · 1
+    let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let hess = hessian(|x| x.powf(3.0).sum(), &x, true, Strategy::Loop).unwrap();
+    assert!(hess.0.borrow().requires_grad);
+    let trace = &hess.select(0, 0).unwrap().select(0, 0).unwrap()
+        + &hess.select(0, 1).unwrap().select(0, 1).unwrap();
+    let d = grad(&[&trace], &[&x], None, false).unwrap().remove(0);
+    assert_rel_close(&d.data(), &array![6.0, 6.0].into_dyn(), 1e-4);
+}
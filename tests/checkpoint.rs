@@ -0,0 +1,184 @@
+use ndarray::{ArrayD, array};
+use num_complex::Complex64;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use torch_rs::autograd::forward_ad::{dual_level, make_dual, unpack_dual};
+use torch_rs::autograd::{checkpoint, grad, gradcheck, gradgradcheck};
+use torch_rs::dtype::DType;
+use torch_rs::functional;
+use torch_rs::nn::Module;
+use torch_rs::nn::checkpoint::Checkpoint;
+use torch_rs::nn::linear::Linear;
+use torch_rs::nn::relu::ReLU;
+use torch_rs::nn::sequential::Sequential;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{assert_rel_close, take_grad};
+
+#[test]
+fn test_checkpoint_recomputes_in_backward() {
+    let x = Tensor::new(array![[0.5, -1.0], [2.0, 0.3]].into_dyn()).require_grad(true);
+    let w = Tensor::new(array![[1.0, -0.5], [0.2, 0.8]].into_dyn()).require_grad(true);
+    let calls = Arc::new(AtomicUsize::new(0));
+    let segment = {
+        let (w, calls) = (w.clone(), Arc::clone(&calls));
+        move |inputs: &[Tensor]| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            inputs[0].matmul(&w).tanh().exp()
+        }
+    };
+
+    let expected = segment(std::slice::from_ref(&x));
+    expected.sum().backward();
+    let (x_grad, w_grad) = (take_grad(&x), take_grad(&w));
+
+    calls.store(0, Ordering::SeqCst);
+    let y = checkpoint(segment, &[&x]);
+    assert_rel_close(&y.data(), &expected.data(), 1e-5);
+    // 整段计算只留下一个节点，父节点只有输入
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(y.0.borrow().parents.len(), 1);
+    assert!(w.0.borrow().grad.is_none());
+
+    y.sum().backward();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_rel_close(&take_grad(&x), &x_grad, 1e-5);
+    assert_rel_close(&take_grad(&w), &w_grad, 1e-5);
+}
+
+#[test]
+fn test_checkpoint_keeps_input_dtype() {
+    // 1e-10 在 f32 下会被舍入掉，重新计算时必须仍是 f64
+    let x = Tensor::with_dtype(array![1.0 + 1e-10, -2.0].into_dyn(), DType::F64).require_grad(true);
+    let y = checkpoint(
+        |x: &[Tensor]| {
+            assert_eq!(x[0].dtype(), DType::F64);
+            (&(&x[0] - 1.0_f64) * 1e10_f64).sum()
+        },
+        &[&x],
+    );
+    y.backward();
+    assert_rel_close(&take_grad(&x), &array![1e10, 1e10].into_dyn(), 1e-6);
+
+    // 复数输入重新计算时仍是复数：L = Re(z²) 时 G = conj(2z)
+    let segment = |x: &[Tensor]| (&x[0] * &x[0]).real().sum();
+    let z = Tensor::from_complex(
+        array![Complex64::new(1.0, 2.0)].into_dyn(),
+        DType::Complex64,
+    )
+    .require_grad(true);
+    segment(std::slice::from_ref(&z)).backward();
+    let expected = take_grad(&z);
+    assert_rel_close(&expected, &array![[2.0, -4.0]].into_dyn(), 1e-6);
+    checkpoint(segment, &[&z]).backward();
+    assert_rel_close(&take_grad(&z), &expected, 1e-6);
+}
+
+#[test]
+fn test_checkpoint_grad_does_not_touch_param_grads() {
+    let x = Tensor::from(vec![0.5, -1.0]).require_grad(true);
+    let w = Tensor::from(vec![2.0, 3.0]).require_grad(true);
+    let y = {
+        let w = w.clone();
+        checkpoint(move |x: &[Tensor]| (&x[0] * &w).sum(), &[&x])
+    };
+    let dx = grad(&[&y], &[&x], None, false).unwrap();
+    assert_rel_close(&dx[0].data(), &array![2.0, 3.0].into_dyn(), 1e-6);
+    assert!(
+        w.0.borrow()
+            .grad
+            .iter()
+            .all(|g| g.iter().all(|&g| g == 0.0))
+    );
+    assert!(
+        x.0.borrow()
+            .grad
+            .iter()
+            .all(|g| g.iter().all(|&g| g == 0.0))
+    );
+}
+
+#[test]
+fn test_checkpoint_gradcheck() {
+    let a = Tensor::new(array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn());
+    let b = Tensor::new(array![0.5, -0.8, 1.2].into_dyn());
+    let f = |x: &[Tensor]| {
+        checkpoint(
+            |x: &[Tensor]| (&x[0] * &x[1]).sigmoid().sum_dims(&[1], false),
+            &[&x[0], &x[1]],
+        )
+    };
+    assert!(gradcheck(f, &[&a, &b], 1e-3, 1e-2, 1e-2).is_ok());
+    assert!(gradgradcheck(f, &[&a, &b], 1e-3, 1e-2, 1e-2).is_ok());
+}
+
+#[test]
+fn test_checkpoint_create_graph() {
+    // y = Σ x³ 的二阶导数为 6x，一阶导数的计算图要经过重新计算的这一段
+    let x = Tensor::from(vec![0.5, -1.0, 2.0]).require_grad(true);
+    let y = checkpoint(|x: &[Tensor]| (&(&x[0] * &x[0]) * &x[0]).sum(), &[&x]);
+    let dx = grad(&[&y], &[&x], None, true).unwrap();
+    assert_rel_close(&dx[0].data(), &array![0.75, 3.0, 12.0].into_dyn(), 1e-5);
+    let ddx = grad(&[&dx[0].sum()], &[&x], None, false).unwrap();
+    assert_rel_close(&ddx[0].data(), &array![3.0, -6.0, 12.0].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_checkpoint_forward_ad() {
+    let x = Tensor::from(vec![0.5, -1.0, 2.0]);
+    let v = Tensor::from(vec![1.0, 0.0, 2.0]);
+    let _level = dual_level();
+    let dual = make_dual(&x, &v).unwrap();
+    let y = checkpoint(|x: &[Tensor]| x[0].sin(), &[&dual]);
+    let (primal, tangent) = unpack_dual(&y);
+    assert_rel_close(&primal.data(), &x.data().mapv(f32::sin), 1e-5);
+    assert_rel_close(
+        &tangent.unwrap().data(),
+        &(x.data().mapv(f32::cos) * v.data()),
+        1e-5,
+    );
+}
+
+/// 与 `layers` 共享参数的线性层
+fn shared(layer: &Linear) -> Linear {
+    Linear {
+        w: layer.w.clone(),
+        b: layer.b.clone(),
+        in_features: layer.in_features,
+        out_features: layer.out_features,
+        training: true,
+    }
+}
+
+#[test]
+fn test_checkpoint_module_matches_plain_sequential() {
+    let first = Linear::new(3, 4);
+    let second = Linear::new(4, 2);
+    let plain = Sequential::new(vec![
+        Box::new(shared(&first)),
+        Box::new(ReLU::new()),
+        Box::new(shared(&second)),
+    ]);
+    // 输入不需要梯度，参数的梯度仍然要通过检查点传回
+    let wrapped = Sequential::new(vec![
+        Box::new(Checkpoint::new(Box::new(Sequential::new(vec![
+            Box::new(shared(&first)),
+            Box::new(ReLU::new()),
+        ])))),
+        Box::new(Checkpoint::new(Box::new(shared(&second)))),
+    ]);
+    assert_eq!(wrapped.parameters().len(), 4);
+
+    let x = Tensor::new(array![[1.0, -2.0, 0.5], [0.3, 0.7, -1.1]].into_dyn());
+    let target = Tensor::new(array![[1.0, 0.0], [0.0, 1.0]].into_dyn());
+    functional::mse_loss(&plain.forward(&x), &target).backward();
+    let expected: Vec<ArrayD<f32>> = plain.parameters().iter().map(take_grad).collect();
+
+    let output = wrapped.forward(&x);
+    assert_rel_close(&output.data(), &plain.forward(&x).data(), 1e-5);
+    functional::mse_loss(&output, &target).backward();
+    for (param, expected) in wrapped.parameters().iter().zip(&expected) {
+        assert_rel_close(&take_grad(param), expected, 1e-5);
+    }
+}
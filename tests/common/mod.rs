@@ -0,0 +1,58 @@
+//! 集成测试共用的断言与梯度辅助函数，各测试文件通过 `mod common;` 引入
+#![allow(dead_code)]
+
+use ndarray::ArrayD;
+use torch_rs::tensor::Tensor;
+
+/// 逐元素比较，要求绝对误差小于 `tol`
+pub fn assert_close(actual: &ArrayD<f32>, expected: &ArrayD<f32>, tol: f32) {
+    assert_eq!(actual.shape(), expected.shape());
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!((a - e).abs() < tol, "{:?} != {:?}", actual, expected);
+    }
+}
+
+/// 逐元素比较，允许的误差随期望值的大小按 `tol * (1 + |expected|)` 放宽
+pub fn assert_rel_close(actual: &ArrayD<f32>, expected: &ArrayD<f32>, tol: f32) {
+    assert_eq!(actual.shape(), expected.shape());
+    for (a, e) in actual.iter().zip(expected.iter()) {
+        assert!(
+            (a - e).abs() <= tol * (1.0 + e.abs()),
+            "actual {:?}, expected {:?}",
+            actual,
+            expected
+        );
+    }
+}
+
+/// 读取张量当前累积的梯度
+pub fn grad_of(t: &Tensor) -> ArrayD<f32> {
+    t.0.borrow().grad.clone().unwrap()
+}
+
+/// 取出张量累积的梯度并清空，供同一批参数再次反向传播前使用
+pub fn take_grad(t: &Tensor) -> ArrayD<f32> {
+    t.0.borrow_mut().grad.take().unwrap()
+}
+
+/// 用中心差分估计标量函数 `f` 在 `x` 处的梯度
+pub fn numeric_grad(x: &ArrayD<f32>, f: impl Fn(&Tensor) -> Tensor) -> ArrayD<f32> {
+    let eps = 1e-2;
+    let mut grad = ArrayD::zeros(x.raw_dim());
+    for i in 0..x.len() {
+        let mut plus = x.clone();
+        let mut minus = x.clone();
+        plus.as_slice_mut().unwrap()[i] += eps;
+        minus.as_slice_mut().unwrap()[i] -= eps;
+        let fp = f(&Tensor::new(plus)).data().sum();
+        let fm = f(&Tensor::new(minus)).data().sum();
+        grad.as_slice_mut().unwrap()[i] = (fp - fm) / (2.0 * eps);
+    }
+    grad
+}
+
+/// 按行优先顺序填充 `0, 1, 2, ...` 的数组
+pub fn arange(shape: &[usize]) -> ArrayD<f32> {
+    let n: usize = shape.iter().product();
+    ArrayD::from_shape_vec(shape.to_vec(), (0..n).map(|i| i as f32).collect()).unwrap()
+}
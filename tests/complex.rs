@@ -0,0 +1,417 @@
+use ndarray::{ArrayD, array};
+use num_complex::Complex64;
+use torch_rs::autograd::forward_ad::{dual_level, make_dual, unpack_dual};
+use torch_rs::autograd::grad;
+use torch_rs::dtype::DType;
+use torch_rs::optimizer::Optimizer;
+use torch_rs::optimizer::SGD::SGD;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{assert_close, grad_of};
+
+fn c(re: f64, im: f64) -> Complex64 {
+    Complex64::new(re, im)
+}
+
+fn complex(data: Vec<Complex64>) -> Tensor {
+    let len = data.len();
+    Tensor::from_complex(
+        ArrayD::from_shape_vec(vec![len], data).unwrap(),
+        DType::Complex64,
+    )
+}
+
+fn complex128(data: ArrayD<Complex64>) -> Tensor {
+    Tensor::from_complex(data, DType::Complex128)
+}
+
+fn assert_complex_close(actual: &ArrayD<Complex64>, expected: &ArrayD<Complex64>, tol: f64) {
+    assert_eq!(actual.shape(), expected.shape());
+    for (a, e) in actual.iter().zip(expected) {
+        assert!((a - e).norm() <= tol, "{} != {}", actual, expected);
+    }
+}
+
+/// |z|² = Re(z · conj(z))，用作实数损失
+fn squared_norm(z: &Tensor) -> Tensor {
+    (z * &z.conj()).real().sum()
+}
+
+#[test]
+fn test_construction_and_views() {
+    let re = Tensor::from(vec![1.0, 2.0]);
+    let im = Tensor::from(vec![-1.0, 0.5]);
+    let z = Tensor::complex(&re, &im).unwrap();
+    assert_eq!(z.dtype(), DType::Complex64);
+    assert_eq!(z.shape(), vec![2]);
+    assert_eq!(
+        z.data_complex(),
+        array![c(1.0, -1.0), c(2.0, 0.5)].into_dyn()
+    );
+
+    let pairs = z.view_as_real().unwrap();
+    assert_eq!(pairs.dtype(), DType::F32);
+    assert!(pairs.shares_storage(&z));
+    assert_eq!(pairs.data(), array![[1.0, -1.0], [2.0, 0.5]].into_dyn());
+
+    // real 与 imag 是共享存储的视图，修改后复数张量也随之改变
+    let imag = z.imag().unwrap();
+    assert_eq!(imag.data(), array![-1.0, 0.5].into_dyn());
+    imag.fill_(3.0).unwrap();
+    assert_eq!(
+        z.data_complex(),
+        array![c(1.0, 3.0), c(2.0, 3.0)].into_dyn()
+    );
+    assert_eq!(z.real().data(), array![1.0, 2.0].into_dyn());
+
+    let back = pairs.view_as_complex().unwrap();
+    assert!(back.is_complex());
+    assert_eq!(back.data_complex(), z.data_complex());
+    assert!(re.imag().is_err());
+}
+
+#[test]
+fn test_arithmetic_and_promotion() {
+    let a = complex(vec![c(1.0, 2.0), c(0.0, 1.0)]);
+    let b = complex(vec![c(3.0, -1.0), c(2.0, 0.0)]);
+    assert_eq!(
+        (&a + &b).data_complex(),
+        array![c(4.0, 1.0), c(2.0, 1.0)].into_dyn()
+    );
+    assert_eq!(
+        (&a - &b).data_complex(),
+        array![c(-2.0, 3.0), c(-2.0, 1.0)].into_dyn()
+    );
+    assert_eq!(
+        (&a * &b).data_complex(),
+        array![c(5.0, 5.0), c(0.0, 2.0)].into_dyn()
+    );
+    assert_eq!((&(&a * &b) / &b).data_complex(), a.data_complex());
+
+    // 与实数运算：f32 标量不改变复数类型，f64 张量提升为 complex128
+    let scaled = &a * 2.0_f32;
+    assert_eq!(scaled.dtype(), DType::Complex64);
+    assert_eq!(
+        scaled.data_complex(),
+        array![c(2.0, 4.0), c(0.0, 2.0)].into_dyn()
+    );
+    let wide = &a + &Tensor::with_dtype(array![0.5, 0.5].into_dyn(), DType::F64);
+    assert_eq!(wide.dtype(), DType::Complex128);
+    assert_eq!(wide.real().dtype(), DType::F64);
+
+    let r = Tensor::from(vec![1.0, -2.0]).to_dtype(DType::Complex128);
+    assert_eq!(
+        r.data_complex(),
+        array![c(1.0, 0.0), c(-2.0, 0.0)].into_dyn()
+    );
+}
+
+#[test]
+fn test_abs_angle_conj() {
+    let z = complex(vec![c(3.0, 4.0), c(0.0, -2.0), c(-1.0, 0.0)]);
+    assert_eq!(z.abs().dtype(), DType::F32);
+    assert_close(&z.abs().data(), &array![5.0, 2.0, 1.0].into_dyn(), 1e-5);
+    assert_close(
+        &z.angle().data(),
+        &array![
+            4.0_f32.atan2(3.0),
+            -std::f32::consts::FRAC_PI_2,
+            std::f32::consts::PI
+        ]
+        .into_dyn(),
+        1e-5,
+    );
+    assert_eq!(
+        z.conj().data_complex(),
+        array![c(3.0, -4.0), c(0.0, 2.0), c(-1.0, 0.0)].into_dyn()
+    );
+}
+
+#[test]
+fn test_wirtinger_gradients() {
+    // ∂|z|²：梯度为 2z
+    let z = complex(vec![c(1.0, 2.0), c(-3.0, 0.5)]).require_grad(true);
+    squared_norm(&z).backward();
+    assert_close(
+        &grad_of(&z),
+        &array![[2.0, 4.0], [-6.0, 1.0]].into_dyn(),
+        1e-5,
+    );
+
+    // ∂|z|：梯度为 z / |z|
+    let z = complex(vec![c(3.0, 4.0), c(0.0, 0.0)]).require_grad(true);
+    z.abs().sum().backward();
+    assert_close(
+        &grad_of(&z),
+        &array![[0.6, 0.8], [0.0, 0.0]].into_dyn(),
+        1e-5,
+    );
+
+    // ∂angle(z)：梯度为 i·z / |z|²
+    let z = complex(vec![c(1.0, 1.0)]).require_grad(true);
+    z.angle().sum().backward();
+    assert_close(&grad_of(&z), &array![[-0.5, 0.5]].into_dyn(), 1e-5);
+
+    // 实数参与复数运算时梯度取实部：∂Re(z·x)/∂x = Re(z)
+    let x = Tensor::from(vec![2.0, 3.0]).require_grad(true);
+    let z = complex(vec![c(1.0, 5.0), c(-2.0, 1.0)]).require_grad(true);
+    (&z * &x).real().sum().backward();
+    assert_close(&grad_of(&x), &array![1.0, -2.0].into_dyn(), 1e-5);
+    assert_close(
+        &grad_of(&z),
+        &array![[2.0, 0.0], [3.0, 0.0]].into_dyn(),
+        1e-5,
+    );
+
+    // 除法：∂Im(1/z)，z = i 处 1/z = -i
+    let z = complex(vec![c(0.0, 1.0)]).require_grad(true);
+    let one = complex(vec![c(1.0, 0.0)]);
+    (&one / &z).imag().unwrap().sum().backward();
+    // Im(1/z) = -y/(x²+y²)，在 (0, 1) 处 ∂/∂x = 0，∂/∂y = 1
+    assert_close(&grad_of(&z), &array![[0.0, 1.0]].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_matmul_forward_and_backward() {
+    let a = Tensor::from_complex(
+        array![[c(1.0, 1.0), c(0.0, 2.0)], [c(3.0, 0.0), c(1.0, -1.0)]].into_dyn(),
+        DType::Complex64,
+    )
+    .require_grad(true);
+    let b = complex(vec![c(2.0, 0.0), c(0.0, 1.0)]).require_grad(true);
+    let y = a.matmul(&b);
+    assert_eq!(y.shape(), vec![2]);
+    assert_eq!(
+        y.data_complex(),
+        array![c(0.0, 2.0), c(7.0, 1.0)].into_dyn()
+    );
+
+    // L = Re(Σy) 时 G_y = 1，G_a = G_y · bᴴ，G_b = aᴴ · G_y
+    y.real().sum().backward();
+    assert_close(
+        &grad_of(&a),
+        &array![[[2.0, 0.0], [0.0, -1.0]], [[2.0, 0.0], [0.0, -1.0]]].into_dyn(),
+        1e-5,
+    );
+    assert_close(
+        &grad_of(&b),
+        &array![[4.0, -1.0], [1.0, -1.0]].into_dyn(),
+        1e-5,
+    );
+}
+
+#[test]
+fn test_sgd_on_complex_parameters() {
+    let target = complex(vec![c(1.0, -2.0), c(0.5, 3.0)]);
+    let z = complex(vec![c(0.0, 0.0), c(0.0, 0.0)]).require_grad(true);
+    let mut optimizer = SGD::new(vec![z.clone()], 0.1);
+    for _ in 0..100 {
+        optimizer.zero_grad();
+        squared_norm(&(&z - &target)).backward();
+        optimizer.step();
+    }
+    let error = (&z - &target).abs().data();
+    assert!(error.iter().all(|&e| e < 1e-4), "{:?}", z);
+}
+
+#[test]
+fn test_sgd_keeps_complex128_precision() {
+    // 1e-10 在 f32 下会被舍入掉
+    let z = complex128(array![c(1.0 + 1e-10, 2.0 - 1e-10)].into_dyn()).require_grad(true);
+    let k = complex128(array![c(0.5, 0.0)].into_dyn());
+    let mut optimizer = SGD::new(vec![z.clone()], 1.0);
+    // L = Re(z · k)，G = conj(k) = 0.5
+    (&z * &k).real().sum().backward();
+    optimizer.step();
+    assert_eq!(z.dtype(), DType::Complex128);
+    assert_complex_close(
+        &z.data_complex(),
+        &array![c(0.5 + 1e-10, 2.0 - 1e-10)].into_dyn(),
+        1e-15,
+    );
+}
+
+#[test]
+fn test_views_of_complex_tensors() {
+    let z = Tensor::from_complex(
+        array![[c(1.0, 2.0), c(3.0, -1.0)], [c(0.0, 1.0), c(-2.0, 0.5)]].into_dyn(),
+        DType::Complex64,
+    )
+    .require_grad(true);
+    let flat = z.reshape(&[4]).unwrap();
+    assert!(flat.is_complex());
+    assert!(flat.shares_storage(&z));
+    assert_eq!(
+        flat.data_complex(),
+        array![c(1.0, 2.0), c(3.0, -1.0), c(0.0, 1.0), c(-2.0, 0.5)].into_dyn()
+    );
+
+    let transposed = z.transpose(0, 1).unwrap();
+    assert!(transposed.shares_storage(&z));
+    assert!(!transposed.is_contiguous());
+    let copied = transposed.contiguous();
+    assert!(copied.is_contiguous());
+    assert_eq!(copied.dtype(), DType::Complex64);
+    assert_eq!(
+        copied.data_complex(),
+        array![[c(1.0, 2.0), c(0.0, 1.0)], [c(3.0, -1.0), c(-2.0, 0.5)]].into_dyn()
+    );
+    // 转置后按行主序展平需要复制
+    let copied_flat = transposed.reshape(&[4]).unwrap();
+    assert!(!copied_flat.shares_storage(&z));
+
+    // |z|² 的梯度为 2z，经过视图后仍然如此；expand 的梯度按广播的维度求和
+    let row = z.narrow(0, 1, 1).unwrap().expand(&[3, 2]).unwrap();
+    let loss = &(&squared_norm(&flat) + &squared_norm(&copied_flat)) + &squared_norm(&row);
+    loss.backward();
+    let pairs = z.view_as_real().unwrap().data();
+    let mut expected = &pairs * 4.0;
+    expected
+        .index_axis_mut(ndarray::Axis(0), 1)
+        .zip_mut_with(&pairs.index_axis(ndarray::Axis(0), 1), |g, &x| {
+            *g += 6.0 * x
+        });
+    assert_close(&grad_of(&z), &expected, 1e-5);
+}
+
+/// 用于检查复数求导的函数：包含矩阵乘法、除法、共轭、视图与实部虚部的合成
+fn complex_fn(z: &Tensor) -> Tensor {
+    let w = complex128(array![[c(0.5, -1.0), c(2.0, 0.0)], [c(0.0, 1.5), c(-1.0, 0.3)]].into_dyn());
+    let shift = Tensor::complex(
+        &Tensor::with_dtype(array![3.0, -2.0].into_dyn(), DType::F64),
+        &Tensor::with_dtype(array![0.5, 0.25].into_dyn(), DType::F64),
+    )
+    .unwrap();
+    let y = z.transpose(0, 1).unwrap().matmul(&w);
+    let q = &(&y * &z.conj()) / &(z - &shift);
+    q.reshape(&[4]).unwrap()
+}
+
+/// 复数函数的实数损失，包含 abs、angle 与 view_as_real
+fn complex_loss(z: &Tensor) -> Tensor {
+    let q = complex_fn(z);
+    let pairs = q.view_as_real().unwrap();
+    &(&q.abs().sum() + &q.angle().sum()) + &(&pairs * &pairs).sum()
+}
+
+fn complex_input() -> ArrayD<Complex64> {
+    array![[c(1.0, 0.5), c(-0.5, 2.0)], [c(0.3, -1.0), c(2.0, 1.0)]].into_dyn()
+}
+
+#[test]
+fn test_forward_ad_through_complex_ops() {
+    let z = complex_input();
+    let v = array![[c(0.5, -1.0), c(1.0, 0.0)], [c(0.0, 0.3), c(-0.7, 0.2)]].into_dyn();
+
+    let (primal, tangent, loss_tangent) = {
+        let _level = dual_level();
+        let dual = make_dual(&complex128(z.clone()), &complex128(v.clone())).unwrap();
+        let (primal, tangent) = unpack_dual(&complex_fn(&dual));
+        let (_, loss_tangent) = unpack_dual(&complex_loss(&dual));
+        (primal, tangent.unwrap(), loss_tangent.unwrap())
+    };
+    assert!(tangent.is_complex());
+    assert_complex_close(
+        &primal.data_complex(),
+        &complex_fn(&complex128(z.clone())).data_complex(),
+        1e-12,
+    );
+
+    // 与中心差分比较
+    let eps = 1e-6;
+    let at = |t: f64| complex128(&z + &v.mapv(|v| v * t));
+    let expected = (&complex_fn(&at(eps)).data_complex() - &complex_fn(&at(-eps)).data_complex())
+        / c(2.0 * eps, 0.0);
+    assert_complex_close(&tangent.data_complex(), &expected, 1e-4);
+    let expected_loss =
+        (complex_loss(&at(eps)).data_f64() - complex_loss(&at(-eps)).data_f64()) / (2.0 * eps);
+    let actual_loss = loss_tangent.data_f64();
+    assert!(
+        (&actual_loss - &expected_loss)
+            .iter()
+            .all(|d| d.abs() < 1e-3),
+        "{} != {}",
+        actual_loss,
+        expected_loss
+    );
+
+    // 切线与原张量必须同为实数或同为复数
+    let _level = dual_level();
+    assert!(make_dual(&complex128(z), &Tensor::ones(&[2, 2])).is_err());
+}
+
+#[test]
+fn test_create_graph_through_complex_ops() {
+    // create_graph 的一阶梯度与普通反向传播相同
+    let z = complex128(complex_input()).require_grad(true);
+    let first = grad(&[&complex_loss(&z)], &[&z], None, true)
+        .unwrap()
+        .remove(0);
+    assert!(first.is_complex());
+    complex_loss(&z).backward();
+    assert_close(&first.view_as_real().unwrap().data(), &grad_of(&z), 1e-3);
+
+    // L = Σ|z|⁴ 时 G = 4|z|²·z，Hessian-向量积为 4|z|²·v + 8·Re(conj(z)·v)·z
+    let z0 = array![c(1.0, 2.0), c(-0.5, 0.0), c(0.0, 0.0)].into_dyn();
+    let v0 = array![c(0.5, 1.0), c(2.0, -1.0), c(1.0, 1.0)].into_dyn();
+    let z = complex128(z0.clone()).require_grad(true);
+    let v = complex128(v0.clone());
+    let r2 = (&z * &z.conj()).real();
+    let loss = (&r2 * &r2).sum();
+    let g = grad(&[&loss], &[&z], None, true).unwrap().remove(0);
+    let expected_g = z0.mapv(|z| z * (4.0 * z.norm_sqr()));
+    assert_complex_close(&g.data_complex(), &expected_g, 1e-5);
+
+    let directional = (&g * &v.conj()).real().sum();
+    let hvp = grad(&[&directional], &[&z], None, false).unwrap().remove(0);
+    let expected_hvp = ndarray::Zip::from(&z0)
+        .and(&v0)
+        .map_collect(|&z, &v| v * (4.0 * z.norm_sqr()) + z * (8.0 * (z.conj() * v).re));
+    assert_complex_close(&hvp.data_complex(), &expected_hvp, 1e-5);
+}
+
+#[test]
+fn test_create_graph_through_real_to_complex_cast() {
+    // L = Re(x²·k)，k = 1 + 2i 时 ∂L/∂x = 2x，create_graph 的梯度同样是实数
+    let x = Tensor::from(vec![0.5, -1.5]).require_grad(true);
+    let k = complex(vec![c(1.0, 2.0)]);
+    let z = x.to_dtype(DType::Complex64);
+    let loss = (&(&z * &z) * &k).real().sum();
+    let g = grad(&[&loss], &[&x], None, true).unwrap().remove(0);
+    assert!(!g.is_complex());
+    assert_close(&g.data(), &array![1.0, -3.0].into_dyn(), 1e-6);
+    let gg = grad(&[&g.sum()], &[&x], None, false).unwrap().remove(0);
+    assert_close(&gg.data(), &array![2.0, 2.0].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_grad_of_complex_outputs() {
+    let z = complex(vec![c(1.0, 2.0), c(-1.0, 0.5)]).require_grad(true);
+    let b = complex(vec![c(0.0, 1.0), c(2.0, -1.0)]);
+    let w = &z * &b;
+
+    // 复数输出必须给出同为复数的输出梯度
+    assert!(grad(&[&w], &[&z], None, false).is_err());
+    assert!(grad(&[&w], &[&z], Some(&[&Tensor::ones(&[2])]), false).is_err());
+
+    // G_z = G_w · conj(b)
+    let g = complex(vec![c(1.0, 0.0), c(0.0, 1.0)]);
+    for create_graph in [false, true] {
+        let gz = grad(&[&w], &[&z], Some(&[&g]), create_graph)
+            .unwrap()
+            .remove(0);
+        assert!(gz.is_complex());
+        assert_complex_close(
+            &gz.data_complex(),
+            &array![c(0.0, -1.0), c(-1.0, 2.0)].into_dyn(),
+            1e-6,
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "does not support complex tensors")]
+fn test_unsupported_ops_panic_on_complex() {
+    complex(vec![c(1.0, 1.0)]).exp();
+}
@@ -0,0 +1,191 @@
+use ndarray::array;
+use torch_rs::functional::{self, Reduction};
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{assert_close, grad_of, numeric_grad};
+
+#[test]
+fn test_softmax() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0], [1000.0, 1000.0, 1000.0]].into_dyn())
+        .require_grad(true);
+    let y = functional::softmax(&x, 1);
+    let e: Vec<f32> = [1.0f32, 2.0, 3.0].iter().map(|v| v.exp()).collect();
+    let s: f32 = e.iter().sum();
+    assert_close(
+        &y.data(),
+        &array![
+            [e[0] / s, e[1] / s, e[2] / s],
+            [1.0 / 3.0, 1.0 / 3.0, 1.0 / 3.0]
+        ]
+        .into_dyn(),
+        1e-6,
+    );
+
+    // 对softmax加权求和后反向传播，与数值梯度比较
+    let w = Tensor::new(array![[1.0, -2.0, 0.5], [0.0, 1.0, 3.0]].into_dyn());
+    (&y * &w).sum().backward();
+    let expected = numeric_grad(&array![[1.0, 2.0, 3.0], [0.0, 0.0, 0.0]].into_dyn(), |t| {
+        &functional::softmax(t, 1) * &w
+    });
+    let grad = grad_of(&x);
+    assert_close(
+        &grad.slice(ndarray::s![0..1, ..]).to_owned().into_dyn(),
+        &expected.slice(ndarray::s![0..1, ..]).to_owned().into_dyn(),
+        1e-3,
+    );
+}
+
+#[test]
+fn test_log_softmax() {
+    let data = array![[0.5, -1.0, 2.0], [3.0, 0.0, -3.0]].into_dyn();
+    let x = Tensor::new(data.clone()).require_grad(true);
+    let y = functional::log_softmax(&x, 0);
+    let expected = functional::softmax(&Tensor::new(data.clone()), 0)
+        .data()
+        .mapv(f32::ln);
+    assert_close(&y.data(), &expected, 1e-6);
+
+    let w = Tensor::new(array![[1.0, 2.0, 3.0], [-1.0, 0.5, 0.0]].into_dyn());
+    (&y * &w).sum().backward();
+    let expected = numeric_grad(&data, |t| &functional::log_softmax(t, 0) * &w);
+    assert_close(&grad_of(&x), &expected, 1e-2);
+}
+
+#[test]
+fn test_cross_entropy_basic() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0], [1.0, 1.0, 1.0]].into_dyn()).require_grad(true);
+    let target = Tensor::from(vec![2.0, 0.0]);
+    let loss = functional::cross_entropy(&x, &target, None, None, 0.0, Reduction::Mean);
+
+    let s: f32 = [1.0f32, 2.0, 3.0].iter().map(|v| v.exp()).sum();
+    let p0 = [1.0f32.exp() / s, 2.0f32.exp() / s, 3.0f32.exp() / s];
+    let expected = (-p0[2].ln() + 3.0f32.ln()) / 2.0;
+    assert_close(&loss.data(), &ndarray::arr0(expected).into_dyn(), 1e-6);
+
+    loss.backward();
+    // ∂loss/∂z = (softmax - onehot) / N
+    let third = 1.0 / 3.0;
+    assert_close(
+        &grad_of(&x),
+        &array![
+            [p0[0] / 2.0, p0[1] / 2.0, (p0[2] - 1.0) / 2.0],
+            [(third - 1.0) / 2.0, third / 2.0, third / 2.0]
+        ]
+        .into_dyn(),
+        1e-6,
+    );
+}
+
+#[test]
+fn test_cross_entropy_matches_log_softmax_nll() {
+    let data = array![
+        [0.2, -1.0, 0.7, 2.0],
+        [1.5, 0.3, -0.4, 0.0],
+        [0.0, 0.1, 0.2, 0.3]
+    ]
+    .into_dyn();
+    let target = Tensor::from(vec![3.0, 0.0, 1.0]);
+    let weight = Tensor::from(vec![1.0, 2.0, 0.5, 1.5]);
+
+    let a = Tensor::new(data.clone()).require_grad(true);
+    let fused = functional::cross_entropy(&a, &target, Some(&weight), None, 0.0, Reduction::Mean);
+    fused.backward();
+
+    let b = Tensor::new(data).require_grad(true);
+    let composed = functional::nll_loss(
+        &functional::log_softmax(&b, 1),
+        &target,
+        Some(&weight),
+        None,
+        Reduction::Mean,
+    );
+    composed.backward();
+
+    assert_close(&fused.data(), &composed.data(), 1e-6);
+    assert_close(&grad_of(&a), &grad_of(&b), 1e-6);
+}
+
+#[test]
+fn test_nll_loss_weight_and_ignore_index() {
+    let x =
+        Tensor::new(array![[-0.5, -1.0], [-2.0, -0.1], [-0.3, -0.7]].into_dyn()).require_grad(true);
+    let target = Tensor::from(vec![0.0, 1.0, 1.0]);
+    let weight = Tensor::from(vec![2.0, 3.0]);
+    let loss = functional::nll_loss(&x, &target, Some(&weight), Some(1), Reduction::Mean);
+    // 类别1被忽略，只剩第一个样本：2 * 0.5 / 2
+    assert_close(&loss.data(), &ndarray::arr0(0.5).into_dyn(), 1e-6);
+    loss.backward();
+    assert_close(
+        &grad_of(&x),
+        &array![[-1.0, 0.0], [0.0, 0.0], [0.0, 0.0]].into_dyn(),
+        1e-6,
+    );
+
+    let loss = functional::nll_loss(&x, &target, Some(&weight), None, Reduction::Sum);
+    assert_close(
+        &loss.data(),
+        &ndarray::arr0(1.0 + 0.3 + 2.1).into_dyn(),
+        1e-5,
+    );
+    let loss = functional::nll_loss(&x, &target, None, None, Reduction::None);
+    assert_close(&loss.data(), &array![0.5, 0.1, 0.7].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_cross_entropy_label_smoothing() {
+    let data = array![[0.2, -1.0, 0.7], [1.5, 0.3, -0.4]].into_dyn();
+    let target = Tensor::from(vec![2.0, 0.0]);
+    let weight = Tensor::from(vec![1.0, 2.0, 0.5]);
+    let eps = 0.2;
+
+    let x = Tensor::new(data.clone()).require_grad(true);
+    let loss = functional::cross_entropy(&x, &target, Some(&weight), None, eps, Reduction::Mean);
+
+    // (1 - ε) * nll + ε / C * Σ_c w_c * (-log p_c)，均以目标权重之和归一化
+    let log_p = functional::log_softmax(&Tensor::new(data.clone()), 1).data();
+    let nll = -(0.5 * log_p[[0, 2]] + 1.0 * log_p[[1, 0]]);
+    let smooth: f32 = (0..2)
+        .map(|i| -(log_p[[i, 0]] + 2.0 * log_p[[i, 1]] + 0.5 * log_p[[i, 2]]))
+        .sum();
+    let expected = ((1.0 - eps) * nll + eps / 3.0 * smooth) / 1.5;
+    assert_close(&loss.data(), &ndarray::arr0(expected).into_dyn(), 1e-5);
+
+    loss.backward();
+    let numeric = numeric_grad(&data, |t| {
+        functional::cross_entropy(t, &target, Some(&weight), None, eps, Reduction::Mean)
+    });
+    assert_close(&grad_of(&x), &numeric, 1e-2);
+}
+
+#[test]
+fn test_cross_entropy_spatial_none_reduction() {
+    // 输入 [N, C, L]，目标 [N, L]
+    let data = array![[[1.0, 0.0], [0.0, 2.0], [0.5, 0.5]]].into_dyn();
+    let target = Tensor::new(array![[0.0, 1.0]].into_dyn());
+    let x = Tensor::new(data.clone()).require_grad(true);
+    let loss = functional::cross_entropy(&x, &target, None, None, 0.0, Reduction::None);
+    assert_eq!(loss.shape(), vec![1, 2]);
+
+    let log_p = functional::log_softmax(&Tensor::new(data.clone()), 1).data();
+    assert_close(
+        &loss.data(),
+        &array![[-log_p[[0, 0, 0]], -log_p[[0, 1, 1]]]].into_dyn(),
+        1e-6,
+    );
+
+    let w = Tensor::new(array![[1.0, 3.0]].into_dyn());
+    (&loss * &w).sum().backward();
+    let numeric = numeric_grad(&data, |t| {
+        &functional::cross_entropy(t, &target, None, None, 0.0, Reduction::None) * &w
+    });
+    assert_close(&grad_of(&x), &numeric, 1e-2);
+}
+
+#[test]
+#[should_panic]
+fn test_cross_entropy_target_out_of_range() {
+    let x = Tensor::new(array![[1.0, 2.0]].into_dyn());
+    let target = Tensor::from(vec![2.0]);
+    functional::cross_entropy(&x, &target, None, None, 0.0, Reduction::Mean);
+}
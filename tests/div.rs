@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use torch_rs::tensor::Tensor;
+
+    #[test]
+    fn test_div() {
+        let a = Tensor::from(vec![4.0, 10.0, 18.0])
+            .reshape(&[3, 1])
+            .unwrap();
+        let b = Tensor::from(vec![4.0, 5.0, 6.0]).reshape(&[3, 1]).unwrap();
+
+        let result = &a / &b;
+
+        let expected = Tensor::from(vec![1.0, 2.0, 3.0]).reshape(&[3, 1]).unwrap();
+        assert_eq!(result.data(), expected.data());
+    }
+
+    #[test]
+    fn test_div_grad() {
+        let a = Tensor::new(array![1.0, 2.0, 3.0].into_dyn()).require_grad(true);
+        let b = Tensor::new(array![2.0, 4.0, 0.5].into_dyn()).require_grad(true);
+
+        let result = &a / &b;
+        result.backward();
+
+        // ∂(a/b)/∂a = 1/b，∂(a/b)/∂b = -a/b²
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![0.5, 0.25, 2.0].into_dyn()
+        );
+        assert_eq!(
+            b.0.borrow().grad.clone().unwrap(),
+            array![-0.25, -0.125, -12.0].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_div_broadcasting_grad() {
+        let a = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn()).require_grad(true);
+        let b = Tensor::new(array![2.0].into_dyn()).require_grad(true);
+
+        let result = &a / &b;
+        assert_eq!(result.data(), array![[0.5, 1.0], [1.5, 2.0]].into_dyn());
+        result.backward();
+
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![[0.5, 0.5], [0.5, 0.5]].into_dyn()
+        );
+        // -(1 + 2 + 3 + 4) / 4
+        assert_eq!(b.0.borrow().grad.clone().unwrap(), array![-2.5].into_dyn());
+    }
+
+    #[test]
+    fn test_div_scalar() {
+        let a = Tensor::from(vec![1.0, 2.0, 4.0]).require_grad(true);
+
+        let result: Tensor = &a / 2.0;
+        assert_eq!(result.data(), array![0.5, 1.0, 2.0].into_dyn());
+
+        let result: Tensor = 4.0 / &a;
+        assert_eq!(result.data(), array![4.0, 2.0, 1.0].into_dyn());
+        result.backward();
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![-4.0, -1.0, -0.25].into_dyn()
+        );
+    }
+}
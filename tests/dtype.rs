@@ -0,0 +1,259 @@
+use ndarray::{ArrayD, array};
+use torch_rs::dtype::{DType, promote_types};
+use torch_rs::functional::{self, Reduction};
+use torch_rs::optimizer::Optimizer;
+use torch_rs::optimizer::SGD::SGD;
+use torch_rs::tensor::Tensor;
+
+fn typed(data: ArrayD<f64>, dtype: DType) -> Tensor {
+    Tensor::with_dtype(data, dtype)
+}
+
+#[test]
+fn test_promote_types() {
+    assert_eq!(promote_types(DType::Bool, DType::U8), DType::U8);
+    assert_eq!(promote_types(DType::U8, DType::I32), DType::I32);
+    assert_eq!(promote_types(DType::I32, DType::I64), DType::I64);
+    assert_eq!(promote_types(DType::I64, DType::F16), DType::F16);
+    assert_eq!(promote_types(DType::F16, DType::BF16), DType::F32);
+    assert_eq!(promote_types(DType::F32, DType::F64), DType::F64);
+    assert_eq!(promote_types(DType::BF16, DType::F32), DType::F32);
+}
+
+#[test]
+fn test_to_dtype_rounds_and_truncates() {
+    let x = Tensor::from(vec![1.7, -2.5, 300.0, 0.0]);
+    assert_eq!(x.dtype(), DType::F32);
+
+    let i = x.to_dtype(DType::I32);
+    assert_eq!(i.dtype(), DType::I32);
+    assert_eq!(i.data(), array![1.0, -2.0, 300.0, 0.0].into_dyn());
+
+    // u8 饱和到 [0, 255]
+    let u = x.to_dtype(DType::U8);
+    assert_eq!(u.data(), array![1.0, 0.0, 255.0, 0.0].into_dyn());
+
+    let b = x.to_dtype(DType::Bool);
+    assert_eq!(b.data(), array![1.0, 1.0, 1.0, 0.0].into_dyn());
+
+    // f16 只有 11 位有效数字
+    let h = Tensor::from(vec![2049.0]).to_dtype(DType::F16);
+    assert_eq!(h.data(), array![2048.0].into_dyn());
+
+    // 类型相同时返回自身
+    assert!(x.to_dtype(DType::F32).shares_storage(&x));
+}
+
+#[test]
+fn test_f64_and_i64_keep_precision() {
+    let x = typed(array![0.1, 1e-10].into_dyn(), DType::F64);
+    let y = &x + &typed(array![0.2, 1.0].into_dyn(), DType::F64);
+    assert_eq!(y.dtype(), DType::F64);
+    assert_eq!(y.data_f64(), array![0.1 + 0.2, 1.0 + 1e-10].into_dyn());
+
+    let big = typed(array![4_503_599_627_370_000.0].into_dyn(), DType::I64);
+    let sum = &big + &typed(array![3.0].into_dyn(), DType::I64);
+    assert_eq!(sum.dtype(), DType::I64);
+    assert_eq!(sum.data_f64()[[0]], 4_503_599_627_370_003.0);
+}
+
+#[test]
+fn test_binary_op_promotion() {
+    let i = typed(array![1.0, 2.0].into_dyn(), DType::I32);
+    let l = typed(array![3.0, 4.0].into_dyn(), DType::I64);
+    let h = typed(array![0.5, 0.25].into_dyn(), DType::F16);
+    let bf = typed(array![1.0, 1.0].into_dyn(), DType::BF16);
+
+    assert_eq!((&i + &l).dtype(), DType::I64);
+    assert_eq!((&i * &h).dtype(), DType::F16);
+    assert_eq!((&h - &bf).dtype(), DType::F32);
+    assert_eq!((&h + &Tensor::from(vec![1.0, 2.0])).dtype(), DType::F32);
+
+    // 零维的 f32 标量不会把 f16 提升为 f32，但会把整数提升为浮点数
+    assert_eq!((&h + 1.0_f32).dtype(), DType::F16);
+    assert_eq!((&h * 2.0_f32).dtype(), DType::F16);
+    assert_eq!((&h * 2.0_f64).dtype(), DType::F16);
+    assert_eq!((&i + 1.0_f32).dtype(), DType::F32);
+
+    // 整数相除得到 f32
+    let q = &typed(array![1.0, 3.0].into_dyn(), DType::I32) / &i;
+    assert_eq!(q.dtype(), DType::F32);
+    assert_eq!(q.data(), array![1.0, 1.5].into_dyn());
+}
+
+#[test]
+fn test_views_keep_dtype() {
+    let x = typed(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), DType::I64);
+    let t = x.transpose(0, 1).unwrap();
+    assert_eq!(t.dtype(), DType::I64);
+    let c = t.contiguous();
+    assert_eq!(c.dtype(), DType::I64);
+    assert_eq!(c.data(), array![[1.0, 3.0], [2.0, 4.0]].into_dyn());
+    let printed = format!("{:?}", x.reshape(&[4]).unwrap());
+    assert!(printed.starts_with("tensor([1, 2, 3, 4]"), "{}", printed);
+    assert!(printed.contains("dtype=int64"), "{}", printed);
+}
+
+#[test]
+fn test_gradient_through_float_casts() {
+    let x = Tensor::from(vec![1.0, 2.0, 3.0]).require_grad(true);
+    let y = x.to_dtype(DType::F64);
+    assert!(y.0.borrow().requires_grad);
+    let z = &(&y * &y) * 0.5_f32;
+    assert_eq!(z.dtype(), DType::F64);
+    z.sum().backward();
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![1.0, 2.0, 3.0].into_dyn()
+    );
+
+    // 转换为整数类型不记录计算图
+    let n = x.to_dtype(DType::I32);
+    assert!(!n.0.borrow().requires_grad);
+}
+
+#[test]
+#[should_panic(expected = "Only Tensors of floating point and complex dtype can require gradients")]
+fn test_integer_tensors_cannot_require_grad() {
+    typed(array![1.0, 2.0].into_dyn(), DType::I64).require_grad(true);
+}
+
+#[test]
+fn test_inplace_updates_keep_precision() {
+    let w = typed(array![1.000000000001, 0.1].into_dyn(), DType::F64).require_grad(true);
+    w.0.borrow_mut().grad = Some(ArrayD::zeros(vec![2]));
+    let mut optimizer = SGD::new(vec![w.clone()], 0.1);
+    optimizer.step();
+    assert_eq!(w.data_f64(), array![1.000000000001, 0.1].into_dyn());
+
+    w.0.borrow_mut().grad = Some(array![1.0, -1.0].into_dyn());
+    optimizer.step();
+    assert_eq!(
+        w.data_f64(),
+        array![1.000000000001 - 0.1_f32 as f64, 0.1 + 0.1_f32 as f64].into_dyn()
+    );
+
+    // 原地操作同样不经过 f32
+    let big = typed(array![4_503_599_627_370_000.0].into_dyn(), DType::I64);
+    big.add_(&typed(array![3.0].into_dyn(), DType::I64))
+        .unwrap();
+    assert_eq!(big.data_f64()[[0]], 4_503_599_627_370_003.0);
+    big.copy_(&typed(array![0.1].into_dyn(), DType::F64))
+        .unwrap();
+    assert_eq!(big.data_f64()[[0]], 0.0);
+    let x = typed(array![0.0].into_dyn(), DType::F64);
+    x.copy_(&typed(array![0.1].into_dyn(), DType::F64)).unwrap();
+    assert_eq!(x.data_f64()[[0]], 0.1);
+}
+
+#[test]
+fn test_cat_and_stack_keep_dtype() {
+    let labels = [
+        typed(array![3.0, 4_503_599_627_370_003.0].into_dyn(), DType::I64),
+        typed(array![0.0, 1.0].into_dyn(), DType::I64),
+    ];
+    let batch = Tensor::stack(&labels).unwrap();
+    assert_eq!(batch.dtype(), DType::I64);
+    assert_eq!(batch.shape(), vec![2, 2]);
+    assert_eq!(
+        batch.data_f64(),
+        array![[3.0, 4_503_599_627_370_003.0], [0.0, 1.0]].into_dyn()
+    );
+
+    let masks = [
+        typed(array![1.0].into_dyn(), DType::Bool),
+        typed(array![0.0].into_dyn(), DType::Bool),
+    ];
+    assert_eq!(Tensor::cat(&masks, 0).unwrap().dtype(), DType::Bool);
+
+    // 类型不同时按提升规则取公共类型
+    let mixed = [
+        typed(array![1.0].into_dyn(), DType::I32),
+        typed(array![2.0].into_dyn(), DType::I64),
+    ];
+    assert_eq!(Tensor::cat(&mixed, 0).unwrap().dtype(), DType::I64);
+    let mixed = [labels[1].clone(), Tensor::from(vec![0.5])];
+    let promoted = Tensor::cat(&mixed, 0).unwrap();
+    assert_eq!(promoted.dtype(), DType::F32);
+    assert_eq!(promoted.data(), array![0.0, 1.0, 0.5].into_dyn());
+}
+
+#[test]
+fn test_float_ops_keep_f64_precision() {
+    let x = typed(array![1e-10, 1.0 + 1e-12].into_dyn(), DType::F64);
+    let y = x.exp();
+    assert_eq!(y.dtype(), DType::F64);
+    assert_eq!(
+        y.data_f64(),
+        array![1e-10_f64.exp(), (1.0 + 1e-12_f64).exp()].into_dyn()
+    );
+    assert_eq!(x.sigmoid().dtype(), DType::F64);
+    assert_eq!((-&x).data_f64(), array![-1e-10, -1.0 - 1e-12].into_dyn());
+    assert_eq!(x.powf(2.0).data_f64()[[1]], (1.0 + 1e-12_f64).powf(2.0));
+
+    assert_eq!(x.sum().data_f64()[[]], 1e-10 + 1.0 + 1e-12);
+    assert_eq!(x.mean().data_f64()[[]], (1e-10 + 1.0 + 1e-12) / 2.0);
+    assert_eq!(x.max().data_f64()[[]], 1.0 + 1e-12);
+    assert_eq!(x.softmax(0).dtype(), DType::F64);
+    assert_eq!(x.logsumexp(&[0], false).dtype(), DType::F64);
+
+    let m = typed(
+        array![[1.0 + 1e-12, 0.0], [0.0, 1.0]].into_dyn(),
+        DType::F64,
+    );
+    let product = m.matmul(&m);
+    assert_eq!(product.dtype(), DType::F64);
+    assert_eq!(product.data_f64()[[0, 0]], (1.0 + 1e-12) * (1.0 + 1e-12));
+
+    // 梯度仍以 f32 保存
+    let w = typed(array![0.0, 1.0].into_dyn(), DType::F64).require_grad(true);
+    w.exp().sum().backward();
+    let grad = w.0.borrow().grad.clone().unwrap();
+    assert!((grad[[1]] - std::f32::consts::E).abs() < 1e-6);
+}
+
+#[test]
+fn test_integer_reductions_and_data_movement_keep_dtype() {
+    let n = typed(
+        array![4_503_599_627_370_000.0, 3.0, 5.0].into_dyn(),
+        DType::I64,
+    );
+    assert_eq!(n.sum().dtype(), DType::I64);
+    assert_eq!(n.sum().data_f64()[[]], 4_503_599_627_370_008.0);
+    assert_eq!(n.max().dtype(), DType::I64);
+    assert_eq!(n.max().data_f64()[[]], 4_503_599_627_370_000.0);
+    assert_eq!(
+        n.flip(&[0]).unwrap().data_f64()[[2]],
+        4_503_599_627_370_000.0
+    );
+    assert_eq!(n.flip(&[0]).unwrap().dtype(), DType::I64);
+    let flags = typed(array![1.0, 0.0, 1.0].into_dyn(), DType::Bool);
+    assert_eq!(flags.sum().dtype(), DType::I64);
+    assert_eq!(flags.sum().data_f64()[[]], 2.0);
+    // 整数的浮点运算得到 f32
+    assert_eq!(n.exp().dtype(), DType::F32);
+    assert_eq!(n.mean().dtype(), DType::F32);
+}
+
+#[test]
+#[should_panic(expected = "einsum only supports float32 tensors, got float64")]
+fn test_f32_only_ops_reject_other_dtypes() {
+    let x = typed(array![[1.0, 2.0], [3.0, 4.0]].into_dyn(), DType::F64);
+    functional::einsum("ij,jk->ik", &[&x, &x]);
+}
+
+#[test]
+#[should_panic(expected = "NllLoss only supports float32 tensors, got float64")]
+fn test_nll_loss_rejects_f64_input() {
+    let x = typed(array![[-0.5, -1.0]].into_dyn(), DType::F64);
+    let target = Tensor::from(vec![1.0]);
+    functional::nll_loss(&x, &target, None, None, Reduction::Mean);
+}
+
+#[test]
+#[should_panic(expected = "CrossEntropy only supports float32 tensors, got float64")]
+fn test_cross_entropy_rejects_f64_input() {
+    let x = typed(array![[0.5, 1.0]].into_dyn(), DType::F64);
+    let target = Tensor::from(vec![1.0]);
+    functional::cross_entropy(&x, &target, None, None, 0.0, Reduction::Mean);
+}
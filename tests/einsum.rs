@@ -0,0 +1,119 @@
+use ndarray::{ArrayD, array};
+use torch_rs::functional;
+use torch_rs::ops::Op;
+use torch_rs::ops::matmul::{MatMul, matmul};
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{arange, assert_close, grad_of, numeric_grad};
+
+#[test]
+fn test_einsum_matches_matmul() {
+    let a = Tensor::new(arange(&[2, 3]) * 0.5 - 1.0).require_grad(true);
+    let b = Tensor::new(arange(&[3, 4]) * 0.25 - 1.0).require_grad(true);
+    let c = functional::einsum("ij,jk->ik", &[&a, &b]);
+    // 省略输出时按字母序保留只出现一次的下标
+    let implicit = functional::einsum("ij, jk", &[&a, &b]);
+    assert_close(&c.data(), &matmul(&a, &b).data(), 1e-6);
+    assert_close(&implicit.data(), &c.data(), 1e-6);
+
+    c.sum().backward();
+    let a2 = Tensor::new(a.data()).require_grad(true);
+    let b2 = Tensor::new(b.data()).require_grad(true);
+    matmul(&a2, &b2).sum().backward();
+    assert_close(&grad_of(&a), &grad_of(&a2), 1e-6);
+    assert_close(&grad_of(&b), &grad_of(&b2), 1e-6);
+}
+
+#[test]
+fn test_einsum_attention_scores() {
+    let q0 = arange(&[2, 3, 4]) * 0.1 - 1.0;
+    let k0 = arange(&[2, 5, 4]) * -0.05 - 1.0;
+    let q = Tensor::new(q0.clone()).require_grad(true);
+    let k = Tensor::new(k0.clone()).require_grad(true);
+    let scores = functional::einsum("bqd,bkd->bqk", &[&q, &k]);
+    assert_eq!(scores.shape(), vec![2, 3, 5]);
+    assert_close(
+        &scores.data(),
+        &MatMul::with_transpose(false, true)
+            .forward(&[&q, &k])
+            .data(),
+        1e-5,
+    );
+
+    let w = Tensor::new(arange(&[2, 3, 5]) * 0.3 - 1.0);
+    (&scores * &w).sum().backward();
+    let expected_q = numeric_grad(&q0, |q| {
+        &functional::einsum("bqd,bkd->bqk", &[q, &Tensor::new(k0.clone())]) * &w
+    });
+    let expected_k = numeric_grad(&k0, |k| {
+        &functional::einsum("bqd,bkd->bqk", &[&Tensor::new(q0.clone()), k]) * &w
+    });
+    assert_close(&grad_of(&q), &expected_q, 1e-2);
+    assert_close(&grad_of(&k), &expected_k, 1e-2);
+}
+
+#[test]
+fn test_einsum_bilinear_form() {
+    // x_b^T W y_b，三个操作数同时求导
+    let x0 = arange(&[2, 3]) * 0.4 - 1.0;
+    let w0 = arange(&[3, 2]) * 0.3 - 1.0;
+    let y0 = arange(&[2, 2]) * 0.7 - 1.0;
+    let x = Tensor::new(x0.clone()).require_grad(true);
+    let w = Tensor::new(w0.clone()).require_grad(true);
+    let y = Tensor::new(y0.clone()).require_grad(true);
+    let out = functional::einsum("bi,ij,bj->b", &[&x, &w, &y]);
+    assert_eq!(out.shape(), vec![2]);
+    out.sum().backward();
+
+    let f = |x: &Tensor, w: &Tensor, y: &Tensor| functional::einsum("bi,ij,bj->b", &[x, w, y]);
+    let (tx, tw, ty) = (
+        Tensor::new(x0.clone()),
+        Tensor::new(w0.clone()),
+        Tensor::new(y0.clone()),
+    );
+    assert_close(&grad_of(&x), &numeric_grad(&x0, |x| f(x, &tw, &ty)), 1e-2);
+    assert_close(&grad_of(&w), &numeric_grad(&w0, |w| f(&tx, w, &ty)), 1e-2);
+    assert_close(&grad_of(&y), &numeric_grad(&y0, |y| f(&tx, &tw, y)), 1e-2);
+}
+
+#[test]
+fn test_einsum_trace_diagonal_and_sum() {
+    let m = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn()).require_grad(true);
+    let trace = functional::einsum("ii->", &[&m]);
+    assert_close(&trace.data(), &ArrayD::from_elem(vec![], 5.0), 1e-6);
+    (&trace * 2.0_f32).backward();
+    assert_close(
+        &grad_of(&m),
+        &array![[2.0, 0.0], [0.0, 2.0]].into_dyn(),
+        1e-6,
+    );
+
+    let m = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn()).require_grad(true);
+    let diag = functional::einsum("ii->i", &[&m]);
+    assert_close(&diag.data(), &array![1.0, 4.0].into_dyn(), 1e-6);
+    let w = Tensor::new(array![3.0, 5.0].into_dyn());
+    (&diag * &w).sum().backward();
+    assert_close(
+        &grad_of(&m),
+        &array![[3.0, 0.0], [0.0, 5.0]].into_dyn(),
+        1e-6,
+    );
+
+    // 只在一个操作数内部求和的下标，梯度按广播还原
+    let m = Tensor::new(arange(&[2, 3]) - 1.0).require_grad(true);
+    let rows = functional::einsum("ij->i", &[&m]);
+    let w = Tensor::new(array![1.0, -2.0].into_dyn());
+    (&rows * &w).sum().backward();
+    assert_close(
+        &grad_of(&m),
+        &array![[1.0, 1.0, 1.0], [-2.0, -2.0, -2.0]].into_dyn(),
+        1e-6,
+    );
+}
+
+#[test]
+#[should_panic(expected = "has 2 operands, but 1 tensors were given")]
+fn test_einsum_operand_count() {
+    functional::einsum("ij,jk->ik", &[&Tensor::ones(&[2, 2])]);
+}
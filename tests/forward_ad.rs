@@ -0,0 +1,185 @@
+use ndarray::{ArrayD, Dimension, array};
+use torch_rs::autograd::forward_ad::{dual_level, make_dual, unpack_dual};
+use torch_rs::autograd::functional::jvp;
+use torch_rs::autograd::no_grad;
+use torch_rs::functional::{self, Reduction};
+use torch_rs::ops::index::TensorIndex;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::assert_rel_close;
+
+type TensorFn<'a> = dyn Fn(&Tensor) -> Tensor + 'a;
+
+/// 一次前向计算得到切线，与两次反向传播得到的雅可比-向量积比较
+fn check_tangent(f: &TensorFn, x: ArrayD<f32>) {
+    let v = ArrayD::from_shape_fn(x.raw_dim(), |i| {
+        let k: usize = i.as_array_view().iter().sum();
+        0.5 - 0.3 * (k % 3) as f32
+    });
+    let (output, expected) =
+        jvp(f, &Tensor::new(x.clone()), &Tensor::new(v.clone()), false).unwrap();
+
+    let _level = dual_level();
+    let dual = make_dual(&Tensor::new(x), &Tensor::new(v)).unwrap();
+    let (primal, tangent) = unpack_dual(&f(&dual));
+    assert_rel_close(&primal.data(), &output.data(), 1e-4);
+    assert_rel_close(
+        &tangent.expect("tangent was not propagated").data(),
+        &expected.data(),
+        1e-4,
+    );
+}
+
+#[test]
+fn test_tangents_of_basic_ops() {
+    // 前向模式对 AddOp、Multiply、MatMul、Mean、ReLU 的方向导数
+    let w = Tensor::new(array![[1.0, -0.5], [0.3, 2.0], [-1.2, 0.7]].into_dyn());
+    let x = Tensor::new(array![[0.2, -0.4, 0.9], [1.5, 0.1, -0.3]].into_dyn());
+    let v = Tensor::new(array![[1.0, 0.0, -1.0], [0.5, 2.0, 0.0]].into_dyn());
+    let f = |x: &Tensor| {
+        let h = functional::relu(&(&x.matmul(&w) + 0.1_f32));
+        (&h * &h).mean()
+    };
+
+    let _level = dual_level();
+    let dual = make_dual(&x, &v).unwrap();
+    let (primal, tangent) = unpack_dual(&f(&dual));
+    assert_rel_close(&primal.data(), &f(&x).data(), 1e-4);
+
+    // d mean(h²) = mean(2h · 1[h > 0] · (v·W))
+    let h = (&x.matmul(&w) + 0.1_f32).data().mapv(|v| v.max(0.0));
+    let dh = v.matmul(&w).data() * h.mapv(|v| if v > 0.0 { 1.0 } else { 0.0 });
+    let expected = (&h * &dh * 2.0).mean().unwrap();
+    assert!((tangent.unwrap().data().sum() - expected).abs() < 1e-5);
+}
+
+#[test]
+fn test_tangents_of_elementwise_ops() {
+    let x = array![[0.4, 1.3, 0.7], [2.1, 0.9, 1.6]].into_dyn();
+    let functions: Vec<Box<TensorFn>> = vec![
+        Box::new(|x| x.exp()),
+        Box::new(|x| x.log()),
+        Box::new(|x| x.log1p()),
+        Box::new(|x| x.sqrt()),
+        Box::new(|x| x.rsqrt()),
+        Box::new(|x| &x.sin() * &x.cos()),
+        Box::new(|x| x.tanh()),
+        Box::new(|x| x.sigmoid()),
+        Box::new(|x| x.softplus()),
+        Box::new(|x| &(&x.abs() + &x.sign()) - x),
+        Box::new(|x| x.clamp(Some(0.5), Some(2.0))),
+        Box::new(|x| &(1.0_f32 / x) - &x.pow(x)),
+        Box::new(|x| x.powf(3.0)),
+        Box::new(|x| &(x - 1.0_f32) / &x.sum_dims(&[1], true)),
+    ];
+    for f in &functions {
+        check_tangent(f.as_ref(), x.clone());
+    }
+}
+
+#[test]
+fn test_tangents_of_reductions_and_shape_ops() {
+    let x = array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn();
+    let functions: Vec<Box<TensorFn>> = vec![
+        Box::new(|x| x.softmax(1)),
+        Box::new(|x| x.log_softmax(0)),
+        Box::new(|x| x.logsumexp(&[1], false)),
+        Box::new(|x| x.max_dims(&[1], true)),
+        Box::new(|x| x.min()),
+        Box::new(|x| x.prod_dims(&[0], false)),
+        Box::new(|x| x.transpose(0, 1).unwrap().matmul(x)),
+        Box::new(|x| x.select(0, 0).unwrap().outer(&x.select(0, 1).unwrap())),
+        Box::new(|x| {
+            let cols = x.slice(&[TensorIndex::full(), TensorIndex::slice(None, None, 2)]);
+            let rolled = x.roll(&[1], &[1]).unwrap().flip(&[0]).unwrap();
+            Tensor::cat(&[cols.unwrap(), rolled], 1).unwrap()
+        }),
+        Box::new(|x| {
+            let index = Tensor::new(array![[2.0, 0.0], [1.0, 1.0]].into_dyn());
+            let picked = x.gather(1, &index).unwrap();
+            let rows = x.index_select(1, &Tensor::from(vec![0.0, 2.0])).unwrap();
+            &picked * &rows
+        }),
+        Box::new(|x| {
+            let expanded = x.reshape(&[2, 1, 3]).unwrap().expand(&[2, 2, 3]).unwrap();
+            let repeated = x.repeat(&[2, 1]).unwrap().reshape(&[2, 2, 3]).unwrap();
+            &expanded * &repeated.narrow(2, 0, 3).unwrap().contiguous()
+        }),
+        Box::new(|x| {
+            let index = Tensor::new(array![[1.0, 0.0], [0.0, 2.0]].into_dyn());
+            let src = x.narrow(1, 0, 2).unwrap().exp();
+            x.scatter(1, &index, &src).unwrap()
+        }),
+        Box::new(|x| {
+            let y = Tensor::new(array![[1.0, 0.5], [0.0, 2.0], [1.5, -1.0]].into_dyn());
+            functional::einsum("ij,jk->ik", &[x, &y])
+        }),
+    ];
+    for f in &functions {
+        check_tangent(f.as_ref(), x.clone());
+    }
+}
+
+#[test]
+fn test_tangents_of_classification_losses() {
+    let x = array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn();
+    let target = Tensor::new(array![2.0, 0.0].into_dyn());
+    let weight = Tensor::new(array![1.0, 2.0, 0.5].into_dyn());
+    let ce = |x: &Tensor| {
+        functional::cross_entropy(x, &target, Some(&weight), None, 0.1, Reduction::Mean)
+    };
+    check_tangent(&ce, x.clone());
+    let ce_none =
+        |x: &Tensor| functional::cross_entropy(x, &target, None, None, 0.0, Reduction::None);
+    check_tangent(&ce_none, x.clone());
+    let nll =
+        |x: &Tensor| functional::nll_loss(&x.log_softmax(1), &target, None, None, Reduction::Sum);
+    check_tangent(&nll, x);
+}
+
+#[test]
+fn test_dual_level_scope() {
+    let x = Tensor::from(vec![1.0, 2.0]);
+    let v = Tensor::from(vec![1.0, 1.0]);
+    assert!(make_dual(&x, &v).is_err());
+
+    let y = {
+        let _level = dual_level();
+        assert!(make_dual(&x, &Tensor::ones(&[3])).is_err());
+        let dual = make_dual(&x, &v).unwrap();
+        // 切线与梯度模式无关
+        let y = {
+            let _guard = no_grad();
+            dual.exp()
+        };
+        assert!(unpack_dual(&y).1.is_some());
+        assert!(unpack_dual(&x).1.is_none());
+        y
+    };
+    // 离开层级后切线失效，新的层级也看不到旧的切线
+    assert!(unpack_dual(&y).1.is_none());
+    let _level = dual_level();
+    assert!(unpack_dual(&(&y * 2.0_f32)).1.is_none());
+}
+
+#[test]
+fn test_dual_keeps_graph_for_backward() {
+    let x = Tensor::from(vec![1.0, 2.0]).require_grad(true);
+    let _level = dual_level();
+    let dual = make_dual(&x, &Tensor::from(vec![1.0, 0.0])).unwrap();
+    let y = (&dual * &dual).sum();
+    assert_eq!(unpack_dual(&y).1.unwrap().data().sum(), 2.0);
+    y.backward();
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![2.0, 4.0].into_dyn()
+    );
+}
+
+#[test]
+#[should_panic(expected = "Nested forward AD levels are not supported")]
+fn test_nested_dual_level_panics() {
+    let _outer = dual_level();
+    let _inner = dual_level();
+}
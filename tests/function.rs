@@ -0,0 +1,263 @@
+use ndarray::array;
+use num_complex::Complex64;
+use std::sync::Barrier;
+use std::thread;
+use std::time::Duration;
+use torch_rs::autograd::forward_ad::{dual_level, make_dual, unpack_dual};
+use torch_rs::autograd::{Function, FunctionCtx, grad, gradcheck, gradgradcheck, no_grad};
+use torch_rs::dtype::DType;
+use torch_rs::tensor::Tensor;
+
+/// `y = a · b^power`，非张量参数 `power` 作为字段
+struct ScaledPow {
+    power: f32,
+}
+
+impl Function for ScaledPow {
+    fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+        let (a, b) = (inputs[0], inputs[1]);
+        ctx.save_for_backward(&[a, b]);
+        a * &b.powf(self.power)
+    }
+
+    fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        let saved = ctx.saved_tensors();
+        let (a, b) = (&saved[0], &saved[1]);
+        let grad_a = ctx
+            .needs_input_grad(0)
+            .then(|| grad_output * &b.powf(self.power));
+        let grad_b = ctx
+            .needs_input_grad(1)
+            .then(|| &(grad_output * a) * &(&b.powf(self.power - 1.0) * self.power));
+        vec![grad_a, grad_b]
+    }
+
+    fn jvp(&self, ctx: &FunctionCtx, tangents: &[Option<Tensor>]) -> Tensor {
+        let saved = ctx.saved_tensors();
+        let (a, b) = (&saved[0], &saved[1]);
+        let mut tangent = a.zeros_like();
+        if let Some(da) = &tangents[0] {
+            tangent = &tangent + &(da * &b.powf(self.power));
+        }
+        if let Some(db) = &tangents[1] {
+            tangent = &tangent + &(&(db * a) * &(&b.powf(self.power - 1.0) * self.power));
+        }
+        tangent
+    }
+}
+
+/// 取最大的元素，把它的位置作为非张量的中间结果保存
+struct ArgmaxPick;
+
+impl Function for ArgmaxPick {
+    fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+        let data = inputs[0].data();
+        let (index, value) =
+            data.iter()
+                .enumerate()
+                .fold((0, f32::NEG_INFINITY), |best, (i, &v)| {
+                    if v > best.1 { (i, v) } else { best }
+                });
+        ctx.save_value("index", index);
+        ctx.save_value("len", data.len());
+        Tensor::from(vec![value])
+    }
+
+    fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        let index = *ctx.saved_value::<usize>("index").unwrap();
+        assert!(ctx.saved_value::<f32>("index").is_none());
+        let mut grad = vec![0.0; *ctx.saved_value::<usize>("len").unwrap()];
+        grad[index] = grad_output.data()[[0]];
+        vec![Some(Tensor::from(grad))]
+    }
+}
+
+/// `y = e^x`，保存的是输出而不是输入
+struct SavedExp;
+
+impl Function for SavedExp {
+    fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+        let y = inputs[0].exp();
+        ctx.save_for_backward(&[&y]);
+        ctx.save_value("dtype", y.dtype());
+        y
+    }
+
+    fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        let y = ctx.saved_tensors().remove(0);
+        assert_eq!(Some(&y.dtype()), ctx.saved_value::<DType>("dtype"));
+        vec![Some(grad_output * &y)]
+    }
+}
+
+#[test]
+fn test_function_gradients() {
+    let f = |x: &[Tensor]| ScaledPow { power: 3.0 }.apply(&[&x[0], &x[1]]);
+    let a = Tensor::new(array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn());
+    let b = Tensor::new(array![[0.5, 1.2, -0.8], [1.1, -0.3, 0.6]].into_dyn());
+    assert!(gradcheck(f, &[&a, &b], 1e-3, 1e-2, 1e-2).is_ok());
+    assert!(gradgradcheck(f, &[&a, &b], 1e-3, 1e-2, 1e-2).is_ok());
+}
+
+#[test]
+fn test_function_graph_wiring() {
+    let a = Tensor::from(vec![1.0, 2.0, 3.0]);
+    let b = Tensor::from(vec![2.0, 1.0, 0.5]).require_grad(true);
+
+    // 所有输入按顺序成为父节点，不需要梯度的输入得到 None
+    let y = ScaledPow { power: 2.0 }.apply(&[&a, &b]);
+    assert!(y.0.borrow().requires_grad);
+    assert_eq!(y.0.borrow().parents.len(), 2);
+    y.sum().backward();
+    assert_eq!(
+        b.0.borrow().grad.clone().unwrap(),
+        array![4.0, 4.0, 3.0].into_dyn()
+    );
+
+    // 梯度模式关闭时不记录计算图
+    let y = {
+        let _guard = no_grad();
+        ScaledPow { power: 2.0 }.apply(&[&a, &b])
+    };
+    assert!(!y.0.borrow().requires_grad);
+    assert!(y.0.borrow().creator.is_none());
+}
+
+#[test]
+fn test_function_saves_values() {
+    let x = Tensor::from(vec![0.5, 3.0, -1.0]).require_grad(true);
+    let y = ArgmaxPick.apply(&[&x]);
+    assert_eq!(y.data(), array![3.0].into_dyn());
+    (&y * 2.0_f32).sum().backward();
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![0.0, 2.0, 0.0].into_dyn()
+    );
+}
+
+#[test]
+fn test_function_forward_ad() {
+    let a = Tensor::from(vec![1.0, 2.0]);
+    let b = Tensor::from(vec![3.0, -1.0]);
+    let _level = dual_level();
+    let dual = make_dual(&b, &Tensor::from(vec![1.0, 1.0])).unwrap();
+    let y = ScaledPow { power: 2.0 }.apply(&[&a, &dual]);
+    let (_, tangent) = unpack_dual(&y);
+    assert_eq!(tangent.unwrap().data(), array![6.0, -4.0].into_dyn());
+}
+
+#[test]
+#[should_panic(expected = "inplace operation")]
+fn test_function_detects_inplace_modification() {
+    let a = Tensor::from(vec![1.0, 2.0]).require_grad(true);
+    let b = Tensor::from(vec![3.0, 4.0]);
+    let y = ScaledPow { power: 2.0 }.apply(&[&a, &b]);
+    b.add_(&Tensor::from(vec![1.0, 1.0])).unwrap();
+    y.sum().backward();
+}
+
+#[test]
+#[should_panic(expected = "returned 1 gradients for 2 inputs")]
+fn test_function_checks_gradient_count() {
+    struct Broken;
+    impl Function for Broken {
+        fn forward(&self, _ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+            inputs[0] + inputs[1]
+        }
+        fn backward(&self, _ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+            vec![Some(grad_output.clone())]
+        }
+    }
+    let a = Tensor::from(vec![1.0]).require_grad(true);
+    Broken.apply(&[&a, &a]).sum().backward();
+}
+
+#[test]
+fn test_function_saved_tensors_keep_dtype() {
+    let x = Tensor::with_dtype(array![0.0, 1.0].into_dyn(), DType::F64).require_grad(true);
+    SavedExp.apply(&[&x]).sum().backward();
+    let g = x.0.borrow().grad.clone().unwrap();
+    assert!((g[[1]] - std::f32::consts::E).abs() < 1e-6);
+}
+
+#[test]
+fn test_function_create_graph_from_several_threads() {
+    /// `y = x³`，两个线程都进入反向传播后，一个线程稍晚才取出保存的张量
+    struct SlowCube {
+        barrier: Barrier,
+    }
+
+    impl Function for SlowCube {
+        fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+            ctx.save_for_backward(&[inputs[0]]);
+            inputs[0].powf(3.0)
+        }
+
+        fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+            if self.barrier.wait().is_leader() {
+                thread::sleep(Duration::from_millis(50));
+            }
+            let x = ctx.saved_tensors().remove(0);
+            vec![Some(&(grad_output * &(&x * &x)) * 3.0_f32)]
+        }
+    }
+
+    let x = Tensor::from(vec![1.5, -0.5]).require_grad(true);
+    let function = SlowCube {
+        barrier: Barrier::new(2),
+    };
+    let y = function.apply(&[&x]).sum();
+
+    // 每次反向传播使用各自的输入，另一个线程先结束也不会让保存的输入变成常量
+    thread::scope(|scope| {
+        for _ in 0..2 {
+            scope.spawn(|| {
+                let g = grad(&[&y], &[&x], None, true).unwrap().remove(0);
+                let gg = grad(&[&g.sum()], &[&x], None, false).unwrap().remove(0);
+                assert_eq!(gg.data(), array![9.0, -3.0].into_dyn());
+            });
+        }
+    });
+}
+
+#[test]
+fn test_function_on_complex_inputs() {
+    /// `w = z²`，梯度为 `G · conj(2z)`
+    struct ComplexSquare;
+
+    impl Function for ComplexSquare {
+        fn forward(&self, ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+            ctx.save_for_backward(&[inputs[0]]);
+            inputs[0] * inputs[0]
+        }
+
+        fn backward(&self, ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+            let z = ctx.saved_tensors().remove(0);
+            assert!(grad_output.is_complex());
+            vec![Some(&(grad_output * &z.conj()) * 2.0_f32)]
+        }
+    }
+
+    let z = Tensor::from_complex(
+        array![Complex64::new(1.0, 2.0), Complex64::new(-0.5, 1.0)].into_dyn(),
+        DType::Complex64,
+    )
+    .require_grad(true);
+    let loss = |w: Tensor| (&w * &w.conj()).real().sum();
+    loss(&z * &z).backward();
+    let expected = z.0.borrow_mut().grad.take().unwrap();
+
+    loss(ComplexSquare.apply(&[&z])).backward();
+    assert_eq!(z.0.borrow().grad.clone().unwrap(), expected);
+    for create_graph in [false, true] {
+        let g = grad(
+            &[&loss(ComplexSquare.apply(&[&z]))],
+            &[&z],
+            None,
+            create_graph,
+        )
+        .unwrap();
+        assert!(g[0].is_complex());
+        assert_eq!(g[0].view_as_real().unwrap().data(), expected);
+    }
+}
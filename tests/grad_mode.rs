@@ -0,0 +1,104 @@
+use ndarray::array;
+use torch_rs::autograd::{
+    enable_grad, inference_mode, is_grad_enabled, is_inference_mode_enabled, no_grad,
+    set_grad_enabled,
+};
+use torch_rs::functional;
+use torch_rs::nn::Module;
+use torch_rs::nn::linear::Linear;
+use torch_rs::tensor::Tensor;
+
+fn is_tracked(t: &Tensor) -> bool {
+    let data = t.0.borrow();
+    data.requires_grad || data.creator.is_some() || !data.parents.is_empty()
+}
+
+#[test]
+fn test_no_grad_skips_graph() {
+    let w = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    {
+        let _guard = no_grad();
+        assert!(!is_grad_enabled());
+        let y = (&(&w * &w) + &w).exp().sum();
+        assert!(!is_tracked(&y));
+        assert!(!is_tracked(&w.transpose(0, 0).unwrap()));
+        assert!(!is_tracked(&functional::relu(&w)));
+    }
+    assert!(is_grad_enabled());
+    assert!(is_tracked(&(&w * &w)));
+}
+
+#[test]
+fn test_guards_nest_and_restore() {
+    let w = Tensor::ones(&[2]).require_grad(true);
+    let _outer = no_grad();
+    {
+        let _inner = enable_grad();
+        assert!(is_tracked(&w.sum()));
+        {
+            let _off = set_grad_enabled(false);
+            assert!(!is_tracked(&w.sum()));
+        }
+        assert!(is_grad_enabled());
+    }
+    assert!(!is_grad_enabled());
+    assert!(!is_tracked(&w.sum()));
+}
+
+#[test]
+fn test_module_forward_under_no_grad() {
+    let layer = Linear::new(3, 2);
+    let x = Tensor::ones(&[4, 3]);
+    let out = {
+        let _guard = no_grad();
+        layer.forward(&x)
+    };
+    assert_eq!(out.shape(), vec![4, 2]);
+    assert!(!is_tracked(&out));
+    assert!(is_tracked(&layer.forward(&x)));
+}
+
+#[test]
+fn test_inplace_update_under_no_grad() {
+    let w = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    assert!(w.add_(&Tensor::ones(&[2])).is_err());
+    {
+        let _guard = no_grad();
+        w.add_(&Tensor::ones(&[2])).unwrap();
+    }
+    assert_eq!(w.data(), array![2.0, 3.0].into_dyn());
+    assert_eq!(w.version(), 1);
+}
+
+#[test]
+fn test_inference_mode() {
+    let w = Tensor::ones(&[2]).require_grad(true);
+    let y = {
+        let _guard = inference_mode();
+        assert!(is_inference_mode_enabled());
+        // 推理模式下不能用 enable_grad 重新开启梯度
+        let _inner = enable_grad();
+        assert!(!is_grad_enabled());
+        let y = (&w * 2.0_f32).exp();
+        assert!(!is_tracked(&y));
+        y
+    };
+    assert!(!is_inference_mode_enabled());
+    assert!(y.is_inference());
+    assert!(!w.is_inference());
+
+    // 推理张量的原地修改不跟踪版本号
+    y.fill_(0.0).unwrap();
+    assert_eq!(y.version(), 0);
+}
+
+#[test]
+#[should_panic(expected = "Inference tensors cannot be saved for backward")]
+fn test_inference_tensor_cannot_be_saved() {
+    let x = {
+        let _guard = inference_mode();
+        Tensor::ones(&[2])
+    };
+    let w = Tensor::ones(&[2]).require_grad(true);
+    let _ = &w * &x;
+}
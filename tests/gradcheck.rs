@@ -0,0 +1,373 @@
+use ndarray::{ArrayD, array};
+use std::sync::Arc;
+use torch_rs::autograd::{checkpoint, gradcheck, gradgradcheck};
+use torch_rs::dtype::DType;
+use torch_rs::functional::{self, Reduction};
+use torch_rs::ops::index::TensorIndex;
+use torch_rs::ops::{Op, needs_grad};
+use torch_rs::tensor::Tensor;
+
+const EPS: f64 = 1e-3;
+const ATOL: f64 = 1e-2;
+const RTOL: f64 = 1e-2;
+
+type MultiFn = dyn Fn(&[Tensor]) -> Tensor;
+
+struct Case {
+    name: &'static str,
+    f: Box<MultiFn>,
+    inputs: Vec<ArrayD<f32>>,
+}
+
+fn case(
+    name: &'static str,
+    f: impl Fn(&[Tensor]) -> Tensor + 'static,
+    inputs: Vec<ArrayD<f32>>,
+) -> Case {
+    Case {
+        name,
+        f: Box::new(f),
+        inputs,
+    }
+}
+
+fn matrix() -> ArrayD<f32> {
+    array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn()
+}
+
+fn positive() -> ArrayD<f32> {
+    array![[0.4, 1.3, 0.7], [2.1, 0.9, 1.6]].into_dyn()
+}
+
+fn row() -> ArrayD<f32> {
+    array![0.5, -0.8, 1.2].into_dyn()
+}
+
+/// 由前两个输入构造的复数张量
+fn complex(x: &[Tensor]) -> Tensor {
+    Tensor::complex(&x[0], &x[1]).unwrap()
+}
+
+/// 复数结果的实部与虚部，作为实数输出检查
+fn pairs(z: &Tensor) -> Tensor {
+    z.view_as_real().unwrap()
+}
+
+/// 覆盖每个 `Op` 实现的用例，一元运算的输入避开不可导点
+fn cases() -> Vec<Case> {
+    vec![
+        case("add", |x| &x[0] + &x[1], vec![matrix(), row()]),
+        case("sub", |x| &x[0] - &x[1], vec![row(), matrix()]),
+        case("mul", |x| &x[0] * &x[1], vec![matrix(), row()]),
+        case("div", |x| &x[0] / &x[1], vec![matrix(), positive()]),
+        case("neg", |x| -&x[0], vec![matrix()]),
+        case("pow", |x| x[0].pow(&x[1]), vec![positive(), row()]),
+        case("powf", |x| x[0].powf(3.0), vec![matrix()]),
+        case("exp", |x| x[0].exp(), vec![matrix()]),
+        case("log", |x| x[0].log(), vec![positive()]),
+        case("log1p", |x| x[0].log1p(), vec![positive()]),
+        case("sqrt", |x| x[0].sqrt(), vec![positive()]),
+        case("rsqrt", |x| x[0].rsqrt(), vec![positive()]),
+        case("abs", |x| &x[0].abs() * &x[0], vec![matrix()]),
+        case("sign", |x| &x[0].sign() + &x[0], vec![matrix()]),
+        case("sin_cos", |x| &x[0].sin() * &x[0].cos(), vec![matrix()]),
+        case("tanh", |x| x[0].tanh(), vec![matrix()]),
+        case("sigmoid", |x| x[0].sigmoid(), vec![matrix()]),
+        case("softplus", |x| x[0].softplus(), vec![matrix()]),
+        case(
+            "clamp",
+            |x| &x[0].clamp(Some(-1.0), Some(1.0)) * &x[0],
+            vec![matrix()],
+        ),
+        case("relu", |x| &functional::relu(&x[0]) * &x[0], vec![matrix()]),
+        case("sum", |x| x[0].sum_dims(&[1], true), vec![matrix()]),
+        case("mean", |x| x[0].mean_dims(&[0], false), vec![matrix()]),
+        case("prod", |x| x[0].prod_dims(&[1], false), vec![matrix()]),
+        case("max", |x| x[0].max_dims(&[1], false), vec![matrix()]),
+        case("min", |x| x[0].min(), vec![matrix()]),
+        case("logsumexp", |x| x[0].logsumexp(&[1], true), vec![matrix()]),
+        case("softmax", |x| x[0].softmax(1), vec![matrix()]),
+        case("log_softmax", |x| x[0].log_softmax(0), vec![matrix()]),
+        case(
+            "matmul",
+            |x| x[0].matmul(&x[1]),
+            vec![
+                matrix(),
+                array![[1.0, 0.5], [0.0, 2.0], [1.5, -1.0]].into_dyn(),
+            ],
+        ),
+        case("matvec", |x| x[0].matmul(&x[1]), vec![matrix(), row()]),
+        case("outer", |x| x[0].outer(&x[1]), vec![row(), row()]),
+        case(
+            "einsum",
+            |x| functional::einsum("ij,j->i", &[&x[0], &x[1]]),
+            vec![matrix(), row()],
+        ),
+        case(
+            "nll_loss",
+            |x| {
+                let target = Tensor::new(array![2.0, 0.0].into_dyn());
+                functional::nll_loss(&x[0], &target, None, None, Reduction::Mean)
+            },
+            vec![matrix()],
+        ),
+        case(
+            "cross_entropy",
+            |x| {
+                let target = Tensor::new(array![2.0, 0.0].into_dyn());
+                let weight = Tensor::new(array![1.0, 2.0, 0.5].into_dyn());
+                functional::cross_entropy(&x[0], &target, Some(&weight), None, 0.1, Reduction::Sum)
+            },
+            vec![matrix()],
+        ),
+        case(
+            "reshape",
+            |x| x[0].reshape(&[3, 2]).unwrap().exp(),
+            vec![matrix()],
+        ),
+        case(
+            "permute_contiguous",
+            |x| x[0].transpose(0, 1).unwrap().contiguous().sin(),
+            vec![matrix()],
+        ),
+        case(
+            "expand",
+            |x| {
+                x[0].reshape(&[2, 1, 3])
+                    .unwrap()
+                    .expand(&[2, 2, 3])
+                    .unwrap()
+                    .powf(2.0)
+            },
+            vec![matrix()],
+        ),
+        case(
+            "repeat",
+            |x| x[0].repeat(&[2, 1]).unwrap().exp(),
+            vec![matrix()],
+        ),
+        case(
+            "cat",
+            |x| Tensor::cat(&[x[0].clone(), x[1].reshape(&[1, 3]).unwrap()], 0).unwrap(),
+            vec![matrix(), row()],
+        ),
+        case(
+            "narrow",
+            |x| x[0].narrow(1, 1, 2).unwrap().exp(),
+            vec![matrix()],
+        ),
+        case(
+            "select",
+            |x| x[0].select(0, 1).unwrap().exp(),
+            vec![matrix()],
+        ),
+        case(
+            "flip",
+            |x| &x[0].flip(&[1]).unwrap() * &x[0],
+            vec![matrix()],
+        ),
+        case(
+            "roll",
+            |x| &x[0].roll(&[1], &[1]).unwrap() * &x[0],
+            vec![matrix()],
+        ),
+        case(
+            "slice",
+            |x| {
+                let index = [TensorIndex::full(), TensorIndex::slice(None, None, 2)];
+                x[0].slice(&index).unwrap().exp()
+            },
+            vec![matrix()],
+        ),
+        case(
+            "index_select",
+            |x| {
+                let index = Tensor::from(vec![2.0, 0.0, 2.0]);
+                x[0].index_select(1, &index).unwrap().exp()
+            },
+            vec![matrix()],
+        ),
+        case(
+            "gather",
+            |x| {
+                let index = Tensor::new(array![[2.0, 0.0], [1.0, 1.0]].into_dyn());
+                x[0].gather(1, &index).unwrap().exp()
+            },
+            vec![matrix()],
+        ),
+        case(
+            "scatter",
+            |x| {
+                let index = Tensor::new(array![[1.0, 0.0], [0.0, 2.0]].into_dyn());
+                let out = x[0].scatter(1, &index, &x[1]).unwrap();
+                &out * &x[0].scatter_add(1, &index, &x[1].exp()).unwrap()
+            },
+            vec![matrix(), array![[0.5, 1.5], [2.0, -0.5]].into_dyn()],
+        ),
+        case(
+            "to_dtype",
+            |x| x[0].to_dtype(DType::F64).exp().to_dtype(DType::F32),
+            vec![matrix()],
+        ),
+        case(
+            "to_complex",
+            |x| pairs(&(&x[0].to_dtype(DType::Complex64) * &complex(&x[1..]))),
+            vec![row(), row(), array![0.4, 1.3, 0.7].into_dyn()],
+        ),
+        case(
+            "complex",
+            |x| pairs(&complex(x)),
+            vec![matrix(), positive()],
+        ),
+        case(
+            "real_imag",
+            |x| {
+                let z = complex(x);
+                &z.real() * &z.imag().unwrap()
+            },
+            vec![matrix(), positive()],
+        ),
+        case(
+            "view_as_complex",
+            |x| {
+                let z = x[0].view_as_complex().unwrap();
+                pairs(&(&z * &z))
+            },
+            vec![array![[0.4, -1.3], [0.7, 2.1], [0.9, -1.6]].into_dyn()],
+        ),
+        case(
+            "complex_add_sub",
+            |x| pairs(&(&(&complex(x) + &complex(&x[2..])) - &x[0])),
+            vec![matrix(), positive(), row(), row()],
+        ),
+        case(
+            "complex_mul",
+            |x| pairs(&(&complex(x) * &complex(&x[2..]))),
+            vec![matrix(), positive(), row(), row()],
+        ),
+        case(
+            "complex_div",
+            |x| pairs(&(&complex(x) / &complex(&x[2..]))),
+            vec![matrix(), positive(), positive(), matrix()],
+        ),
+        case(
+            "conj",
+            |x| pairs(&(&complex(x).conj() * &complex(x))),
+            vec![matrix(), positive()],
+        ),
+        case(
+            "complex_abs",
+            |x| complex(x).abs(),
+            vec![matrix(), positive()],
+        ),
+        // 实部为正，远离辐角在负实轴上的跳变
+        case("angle", |x| complex(x).angle(), vec![positive(), matrix()]),
+        case(
+            "complex_matmul",
+            |x| pairs(&complex(x).matmul(&complex(&x[2..]))),
+            vec![
+                matrix(),
+                positive(),
+                array![[1.0, 0.5], [0.0, 2.0], [1.5, -1.0]].into_dyn(),
+                array![[-0.3, 0.8], [1.1, 0.2], [0.6, -0.9]].into_dyn(),
+            ],
+        ),
+        case(
+            "complex_view",
+            |x| {
+                let z = complex(x).transpose(0, 1).unwrap().reshape(&[6]).unwrap();
+                pairs(&(&z * &z))
+            },
+            vec![matrix(), positive()],
+        ),
+        case(
+            "checkpoint",
+            |x| checkpoint(|x: &[Tensor]| (&x[0] * &x[1]).tanh(), &[&x[0], &x[1]]),
+            vec![matrix(), row()],
+        ),
+        case(
+            "masked_select",
+            |x| {
+                let mask = Tensor::new(array![[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]].into_dyn());
+                x[0].masked_select(&mask).unwrap().exp()
+            },
+            vec![matrix()],
+        ),
+    ]
+}
+
+fn tensors(data: &[ArrayD<f32>]) -> Vec<Tensor> {
+    data.iter().map(|d| Tensor::new(d.clone())).collect()
+}
+
+#[test]
+fn test_gradcheck_every_op() {
+    for Case { name, f, inputs } in cases() {
+        let inputs = tensors(&inputs);
+        let inputs: Vec<&Tensor> = inputs.iter().collect();
+        if let Err(e) = gradcheck(&f, &inputs, EPS, ATOL, RTOL) {
+            panic!("{}: {}", name, e);
+        }
+    }
+}
+
+#[test]
+fn test_gradgradcheck_every_op() {
+    for Case { name, f, inputs } in cases() {
+        let inputs = tensors(&inputs);
+        let inputs: Vec<&Tensor> = inputs.iter().collect();
+        if let Err(e) = gradgradcheck(&f, &inputs, EPS, ATOL, RTOL) {
+            panic!("{}: {}", name, e);
+        }
+    }
+}
+
+/// 故意写错反向传播的平方运算：梯度少乘了 2
+#[derive(Debug)]
+struct WrongSquare;
+
+impl Op for WrongSquare {
+    fn forward(&self, inputs: &[&Tensor]) -> Tensor {
+        let result = Tensor::new(inputs[0].data().mapv(|x| x * x));
+        if needs_grad(inputs) {
+            let mut result_data = result.0.borrow_mut();
+            result_data.set_creator(Arc::new(WrongSquare));
+            result_data.add_parent(inputs[0]);
+            result_data.requires_grad = true;
+        }
+        result
+    }
+
+    fn backward(&self, parent: &Tensor) -> Vec<ArrayD<f32>> {
+        let grad = parent.0.borrow().grad.clone().unwrap();
+        let input = parent.0.borrow().parents[0].borrow().data();
+        vec![grad * input]
+    }
+}
+
+#[test]
+fn test_gradcheck_reports_worst_element() {
+    let x = Tensor::new(array![[1.0, -3.0], [0.5, 2.0]].into_dyn());
+    let err = gradcheck(|x| WrongSquare.forward(&[&x[0]]), &[&x], EPS, ATOL, RTOL).unwrap_err();
+    assert_eq!(err.input, 0);
+    assert_eq!(err.input_index, vec![0, 1]);
+    assert_eq!(err.output_index, vec![0, 1]);
+    assert!((err.analytical + 3.0).abs() < 1e-6);
+    assert!((err.numerical + 6.0).abs() < 1e-2);
+
+    // 容差足够大时通过
+    assert!(gradcheck(|x| WrongSquare.forward(&[&x[0]]), &[&x], EPS, 10.0, 0.0).is_ok());
+}
+
+#[test]
+fn test_gradcheck_constant_output() {
+    let x = Tensor::from(vec![1.0, 2.0]);
+    let constant = |_: &[Tensor]| Tensor::from(vec![3.0, 4.0]);
+    assert!(gradcheck(constant, &[&x], EPS, ATOL, RTOL).is_ok());
+    assert!(gradgradcheck(constant, &[&x], EPS, ATOL, RTOL).is_ok());
+
+    // 与计算图断开后反向传播得到 0，与差分不一致
+    let err = gradcheck(|x| x[0].detach().exp(), &[&x], EPS, ATOL, RTOL).unwrap_err();
+    assert_eq!(err.analytical, 0.0);
+    assert_eq!(err.input_index, vec![1]);
+}
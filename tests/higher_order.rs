@@ -0,0 +1,226 @@
+use ndarray::{ArrayD, Dimension, IxDyn, array};
+use torch_rs::autograd::grad;
+use torch_rs::functional::{self, Reduction};
+use torch_rs::ops::index::TensorIndex;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::assert_rel_close;
+
+type ScalarFn<'a> = dyn Fn(&Tensor) -> Tensor + 'a;
+
+fn leaf(data: ArrayD<f32>) -> Tensor {
+    Tensor::new(data).require_grad(true)
+}
+
+fn first_grad(f: &ScalarFn, x: &ArrayD<f32>) -> ArrayD<f32> {
+    let x = leaf(x.clone());
+    grad(&[&f(&x)], &[&x], None, false).unwrap()[0].data()
+}
+
+/// 用 create_graph 求 Hessian-向量积，与梯度的中心差分比较
+fn check_hvp(f: &ScalarFn, x: ArrayD<f32>) {
+    let v = ArrayD::from_shape_fn(x.raw_dim(), |i| {
+        let k: usize = i.as_array_view().iter().sum();
+        0.3 + 0.2 * (k % 3) as f32
+    });
+    let xt = leaf(x.clone());
+    let g = grad(&[&f(&xt)], &[&xt], None, true).unwrap().remove(0);
+    assert!(g.0.borrow().requires_grad);
+    let hvp = grad(&[&g], &[&xt], Some(&[&Tensor::new(v.clone())]), false).unwrap()[0].data();
+
+    let eps = 1e-2;
+    let numeric =
+        (first_grad(f, &(&x + &(&v * eps))) - first_grad(f, &(&x - &(&v * eps)))) / (2.0 * eps);
+    assert_rel_close(&hvp, &numeric, 2e-2);
+}
+
+#[test]
+fn test_grad_returns_without_touching_leaves() {
+    let x = leaf(array![1.0, 2.0, 3.0].into_dyn());
+    let unused = leaf(array![1.0, 1.0].into_dyn());
+    let y = (&x * &x).sum();
+    let grads = grad(&[&y], &[&x, &unused], None, false).unwrap();
+    assert_eq!(grads[0].data(), array![2.0, 4.0, 6.0].into_dyn());
+    assert_eq!(grads[1].data(), array![0.0, 0.0].into_dyn());
+    assert!(!grads[0].0.borrow().requires_grad);
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        ArrayD::zeros(IxDyn(&[3]))
+    );
+
+    // 计算图没有被释放，还可以正常反向传播
+    y.backward();
+    assert_eq!(x.0.borrow().grad.clone().unwrap(), grads[0].data());
+}
+
+#[test]
+fn test_grad_outputs() {
+    let x = leaf(array![1.0, 2.0].into_dyn());
+    let y = &x * 3.0_f32;
+    let z = x.exp();
+    let w = Tensor::new(array![1.0, -1.0].into_dyn());
+    let grads = grad(&[&y, &z], &[&x], Some(&[&w, &w]), false).unwrap();
+    let expected = array![3.0 + 1f32.exp(), -3.0 - 2f32.exp()].into_dyn();
+    assert_rel_close(&grads[0].data(), &expected, 1e-6);
+
+    assert!(grad(&[&y], &[&x], Some(&[&Tensor::ones(&[3])]), false).is_err());
+    assert!(grad(&[&Tensor::ones(&[2])], &[&x], None, false).is_err());
+}
+
+#[test]
+fn test_second_and_third_derivative() {
+    let x = leaf(array![0.5, -1.0, 2.0].into_dyn());
+    let y = x.powf(3.0).sum();
+    let dy = grad(&[&y], &[&x], None, true).unwrap().remove(0);
+    assert_rel_close(&dy.data(), &array![0.75, 3.0, 12.0].into_dyn(), 1e-6);
+    let d2y = grad(&[&dy.sum()], &[&x], None, true).unwrap().remove(0);
+    assert_rel_close(&d2y.data(), &array![3.0, -6.0, 12.0].into_dyn(), 1e-6);
+    let d3y = grad(&[&d2y.sum()], &[&x], None, false).unwrap().remove(0);
+    assert_rel_close(&d3y.data(), &array![6.0, 6.0, 6.0].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_hessian_vector_product_of_quadratic_form() {
+    // f(x) = ½ xᵀAx，A 对称时 Hv = Av
+    let a = Tensor::new(array![[2.0, 1.0], [1.0, 3.0]].into_dyn());
+    let x = leaf(array![1.0, -1.0].into_dyn());
+    let f = &x.dot(&a.mv(&x)) * 0.5_f32;
+    let g = grad(&[&f], &[&x], None, true).unwrap().remove(0);
+    assert_rel_close(&g.data(), &array![1.0, -2.0].into_dyn(), 1e-6);
+
+    let v = Tensor::new(array![1.0, 2.0].into_dyn());
+    let hv = grad(&[&g.dot(&v)], &[&x], None, false).unwrap().remove(0);
+    assert_rel_close(&hv.data(), &array![4.0, 7.0].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_gradient_penalty_trains_weights() {
+    // WGAN-GP：惩罚判别器对输入的梯度范数，再对权重求导
+    let w = leaf(array![[0.5, -0.2], [0.1, 0.4]].into_dyn());
+    let penalty = |w: &Tensor| {
+        let x = leaf(array![[1.0, 2.0], [-1.0, 0.5]].into_dyn());
+        let critic = x.matmul(w).tanh().sum();
+        let g = grad(&[&critic], &[&x], None, true).unwrap().remove(0);
+        let norm = (&g * &g).sum_dims(&[1], false).sqrt();
+        (&norm - 1.0_f32).powf(2.0).mean()
+    };
+    let loss = penalty(&w);
+    loss.backward();
+    let analytic = w.0.borrow().grad.clone().unwrap();
+
+    let base = w.data();
+    let eps = 1e-2;
+    let numeric = ArrayD::from_shape_fn(base.raw_dim(), |i| {
+        let mut plus = base.clone();
+        plus[&i] += eps;
+        let mut minus = base.clone();
+        minus[&i] -= eps;
+        let f = |d: ArrayD<f32>| penalty(&Tensor::new(d)).data().sum();
+        (f(plus) - f(minus)) / (2.0 * eps)
+    });
+    assert_rel_close(&analytic, &numeric, 2e-2);
+}
+
+#[test]
+fn test_hvp_of_elementwise_ops() {
+    let x = array![[0.4, 1.3, 0.7], [2.1, 0.9, 1.6]].into_dyn();
+    let functions: Vec<Box<ScalarFn>> = vec![
+        Box::new(|x| x.exp().sum()),
+        Box::new(|x| x.log().sum()),
+        Box::new(|x| x.log1p().sum()),
+        Box::new(|x| x.sqrt().sum()),
+        Box::new(|x| x.rsqrt().sum()),
+        Box::new(|x| (&x.sin() * &x.cos()).sum()),
+        Box::new(|x| x.tanh().sum()),
+        Box::new(|x| x.sigmoid().sum()),
+        Box::new(|x| x.softplus().sum()),
+        Box::new(|x| (&x.abs() * x).sum()),
+        Box::new(|x| (&functional::relu(&(x - 1.0_f32)) * x).sum()),
+        Box::new(|x| (&x.clamp(Some(0.5), Some(2.0)) * x).sum()),
+        Box::new(|x| (&(1.0_f32 / x) - &x.pow(x)).sum()),
+        Box::new(|x| x.prod_dims(&[1], false).sum()),
+    ];
+    for f in &functions {
+        check_hvp(f.as_ref(), x.clone());
+    }
+}
+
+#[test]
+fn test_hvp_of_reductions_and_shape_ops() {
+    let x = array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn();
+    let functions: Vec<Box<ScalarFn>> = vec![
+        Box::new(|x| x.softmax(1).powf(2.0).sum()),
+        Box::new(|x| (&x.log_softmax(0) * x).sum()),
+        Box::new(|x| x.logsumexp(&[1], false).powf(2.0).sum()),
+        Box::new(|x| (&x.mean_dims(&[0], true) * x).sum()),
+        Box::new(|x| (&x.max_dims(&[1], false) * &x.min()).sum()),
+        Box::new(|x| {
+            let t = x.transpose(0, 1).unwrap();
+            t.matmul(x).powf(2.0).sum()
+        }),
+        Box::new(|x| {
+            let v = x.select(0, 1).unwrap();
+            (&x.matmul(&v) * &v.matmul(&x.transpose(0, 1).unwrap())).sum()
+        }),
+        Box::new(|x| {
+            let cols = x.slice(&[TensorIndex::full(), TensorIndex::slice(None, None, 2)]);
+            let rolled = x.roll(&[1], &[1]).unwrap().flip(&[0]).unwrap();
+            let joined = Tensor::cat(&[cols.unwrap(), rolled], 1).unwrap();
+            (&joined * &joined.narrow(1, 1, 1).unwrap()).sum()
+        }),
+        Box::new(|x| {
+            let index = Tensor::new(array![[2.0, 0.0], [1.0, 1.0]].into_dyn());
+            let picked = x.gather(1, &index).unwrap();
+            let rows = x.index_select(1, &Tensor::from(vec![0.0, 2.0])).unwrap();
+            (&picked * &rows.exp()).sum()
+        }),
+        Box::new(|x| {
+            let expanded = x.reshape(&[2, 1, 3]).unwrap().expand(&[2, 2, 3]).unwrap();
+            let repeated = x.repeat(&[2, 1]).unwrap().reshape(&[2, 2, 3]).unwrap();
+            (&expanded * &repeated).powf(2.0).sum()
+        }),
+        Box::new(|x| {
+            let y = Tensor::new(array![[1.0, 0.5], [0.0, 2.0], [1.5, -1.0]].into_dyn());
+            functional::einsum("ij,jk->ik", &[x, &y]).powf(2.0).sum()
+        }),
+        Box::new(|x| functional::einsum("ij,ij->", &[x, &x.sin()])),
+    ];
+    for f in &functions {
+        check_hvp(f.as_ref(), x.clone());
+    }
+}
+
+#[test]
+fn test_hvp_of_classification_losses() {
+    let x = array![[0.4, -1.3, 0.7], [2.1, 0.9, -1.6]].into_dyn();
+    let target = Tensor::new(array![2.0, 0.0].into_dyn());
+    let weight = Tensor::new(array![1.0, 2.0, 0.5].into_dyn());
+    let ce = |x: &Tensor| {
+        functional::cross_entropy(x, &target, Some(&weight), None, 0.1, Reduction::Mean)
+    };
+    check_hvp(&ce, x.clone());
+    let ce_none = |x: &Tensor| {
+        let losses = functional::cross_entropy(x, &target, None, None, 0.0, Reduction::None);
+        (&losses * &losses).sum()
+    };
+    check_hvp(&ce_none, x.clone());
+    let nll = |x: &Tensor| {
+        let log_probs = x.log_softmax(1);
+        functional::nll_loss(&log_probs, &target, None, None, Reduction::Sum)
+    };
+    check_hvp(&nll, x);
+}
+
+#[test]
+fn test_scatter_second_derivative() {
+    let src = array![[0.5, 1.5], [2.0, -0.5]].into_dyn();
+    let index = Tensor::new(array![[1.0, 0.0], [0.0, 2.0]].into_dyn());
+    let f = |s: &Tensor| {
+        let base = Tensor::new(ArrayD::from_elem(IxDyn(&[2, 3]), 0.5));
+        let out = base.scatter(1, &index, s).unwrap();
+        let added = base.scatter_add(1, &index, &s.exp()).unwrap();
+        (&out * &added).sum()
+    };
+    check_hvp(&f, src);
+}
@@ -0,0 +1,122 @@
+use ndarray::{ArrayD, array};
+use std::sync::{Arc, Mutex};
+use torch_rs::tensor::Tensor;
+
+#[test]
+fn test_hook_modifies_gradient() {
+    let x = Tensor::new(array![1.0, 2.0, 3.0].into_dyn()).require_grad(true);
+    let h = &x * 2.0_f32;
+    let seen = Arc::new(Mutex::new(None));
+    let record = Arc::clone(&seen);
+    h.register_hook(move |grad| {
+        *record.lock().unwrap() = Some(grad.clone());
+        None
+    })
+    .unwrap();
+    // 后注册的钩子看到前一个钩子修改后的梯度
+    let handle = h.register_hook(|grad| Some(grad * 10.0)).unwrap();
+
+    (&h * &h).sum().backward();
+    // 流经 h 的梯度为 2h = [4, 8, 12]，放大 10 倍后再乘 2
+    assert_eq!(
+        seen.lock().unwrap().clone().unwrap(),
+        array![4.0, 8.0, 12.0].into_dyn()
+    );
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![80.0, 160.0, 240.0].into_dyn()
+    );
+
+    // 注销后不再修改梯度
+    handle.remove();
+    x.0.borrow_mut().grad = None;
+    let h = &x * 2.0_f32;
+    h.sum().backward();
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![2.0, 2.0, 2.0].into_dyn()
+    );
+}
+
+#[test]
+fn test_hook_on_leaf_runs_before_accumulation() {
+    let x = Tensor::new(array![1.0, -1.0].into_dyn()).require_grad(true);
+    x.register_hook(|grad| Some(grad.mapv(|g| g.clamp(-1.0, 1.0))))
+        .unwrap();
+    (&x * 5.0_f32).sum().backward();
+    (&x * -3.0_f32).sum().backward();
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![0.0, 0.0].into_dyn()
+    );
+
+    let y = Tensor::from(vec![1.0]);
+    assert!(y.register_hook(|_| None).is_err());
+}
+
+#[test]
+fn test_retain_grad() {
+    let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let h = x.exp();
+    let g = &h * 3.0_f32;
+    h.retain_grad().unwrap();
+    assert!(h.retains_grad());
+    assert!(!g.retains_grad());
+    let loss = g.sum();
+    loss.backward_with(&Tensor::ones(&[]), true).unwrap();
+
+    // 只有调用过 retain_grad 的中间节点保留梯度
+    assert_eq!(
+        h.0.borrow().grad.clone().unwrap(),
+        array![3.0, 3.0].into_dyn()
+    );
+    assert!(g.0.borrow().grad.is_none());
+    assert!(loss.0.borrow().grad.is_none());
+
+    // 与叶子节点一样，保留的梯度在多次反向传播间累加
+    loss.backward_with(&Tensor::ones(&[]), true).unwrap();
+    assert_eq!(
+        h.0.borrow().grad.clone().unwrap(),
+        array![6.0, 6.0].into_dyn()
+    );
+    h.0.borrow_mut().grad = None;
+    loss.backward_with(&Tensor::ones(&[]), true).unwrap();
+    assert_eq!(
+        h.0.borrow().grad.clone().unwrap(),
+        array![3.0, 3.0].into_dyn()
+    );
+    assert!(Tensor::from(vec![1.0]).retain_grad().is_err());
+}
+
+#[test]
+fn test_post_accumulate_grad_hook() {
+    // 在钩子中用累加后的梯度更新参数并清空梯度
+    let w = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let calls = Arc::new(Mutex::new(Vec::<ArrayD<f32>>::new()));
+    let record = Arc::clone(&calls);
+    let handle = w
+        .register_post_accumulate_grad_hook(move |param| {
+            let grad = param.0.borrow_mut().grad.take().unwrap();
+            record.lock().unwrap().push(grad.clone());
+            param
+                .0
+                .borrow()
+                .update_data(|mut data| data -= &(grad * 0.5));
+        })
+        .unwrap();
+
+    // w 在图中被用到两次，钩子只在梯度全部累加后调用一次
+    let loss = (&(&w * &w) + &w).sum();
+    loss.backward();
+    assert_eq!(calls.lock().unwrap().len(), 1);
+    assert_eq!(calls.lock().unwrap()[0], array![3.0, 5.0].into_dyn());
+    assert_eq!(w.data(), array![-0.5, -0.5].into_dyn());
+    assert!(w.0.borrow().grad.is_none());
+
+    handle.remove();
+    w.sum().backward();
+    assert_eq!(calls.lock().unwrap().len(), 1);
+
+    let h = &w * 2.0_f32;
+    assert!(h.register_post_accumulate_grad_hook(|_| {}).is_err());
+}
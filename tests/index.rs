@@ -0,0 +1,191 @@
+use ndarray::array;
+use torch_rs::autograd::grad;
+use torch_rs::functional::{self, Reduction};
+use torch_rs::ops::index::TensorIndex;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{arange, grad_of};
+
+#[test]
+fn test_slice_ranges_steps_and_negative_indices() {
+    let x = Tensor::new(arange(&[3, 4])).require_grad(true);
+    // x[1:, ::2]
+    let y = x
+        .slice(&[(1..).into(), TensorIndex::slice(None, None, 2)])
+        .unwrap();
+    assert_eq!(y.data(), array![[4.0, 6.0], [8.0, 10.0]].into_dyn());
+    y.sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![
+            [0.0, 0.0, 0.0, 0.0],
+            [1.0, 0.0, 1.0, 0.0],
+            [1.0, 0.0, 1.0, 0.0]
+        ]
+        .into_dyn()
+    );
+
+    // x[-1, -3:] 与越界截断 x[:10, 2:100]
+    let x = Tensor::new(arange(&[3, 4]));
+    let last = x.slice(&[(-1).into(), (-3..).into()]).unwrap();
+    assert_eq!(last.data(), array![9.0, 10.0, 11.0].into_dyn());
+    let clamped = x.slice(&[(..10).into(), (2..100).into()]).unwrap();
+    assert_eq!(clamped.shape(), vec![3, 2]);
+
+    assert!(x.slice(&[3.into()]).is_err());
+    assert!(x.slice(&[TensorIndex::slice(None, None, 0)]).is_err());
+}
+
+#[test]
+fn test_slice_new_axis_and_ellipsis() {
+    let x = Tensor::new(arange(&[2, 3, 4])).require_grad(true);
+    // x[..., 1] 与 x[:, 1, :] 的区别在于省略号补齐前面的维度
+    let y = x.slice(&[TensorIndex::Ellipsis, 1.into()]).unwrap();
+    assert_eq!(y.shape(), vec![2, 3]);
+    assert_eq!(y.data()[[1, 2]], x.data()[[1, 2, 1]]);
+
+    // x[None, 0, ..., None]
+    let z = x
+        .slice(&[
+            TensorIndex::NewAxis,
+            0.into(),
+            TensorIndex::Ellipsis,
+            TensorIndex::NewAxis,
+        ])
+        .unwrap();
+    assert_eq!(z.shape(), vec![1, 3, 4, 1]);
+
+    y.sum().backward();
+    let grad = grad_of(&x);
+    assert_eq!(grad.sum(), 6.0);
+    assert_eq!(grad[[0, 0, 1]], 1.0);
+    assert_eq!(grad[[0, 0, 0]], 0.0);
+    assert!(
+        x.slice(&[TensorIndex::Ellipsis, TensorIndex::Ellipsis])
+            .is_err()
+    );
+}
+
+#[test]
+fn test_index_select_embedding_lookup() {
+    let table = Tensor::new(arange(&[4, 2])).require_grad(true);
+    let ids = Tensor::from(vec![3.0, 0.0, 3.0]);
+    let embedded = table.index_select(0, &ids).unwrap();
+    assert_eq!(
+        embedded.data(),
+        array![[6.0, 7.0], [0.0, 1.0], [6.0, 7.0]].into_dyn()
+    );
+    embedded.sum().backward();
+    // 被查找两次的行梯度累加
+    assert_eq!(
+        grad_of(&table),
+        array![[1.0, 1.0], [0.0, 0.0], [0.0, 0.0], [2.0, 2.0]].into_dyn()
+    );
+    assert!(table.index_select(0, &Tensor::from(vec![4.0])).is_err());
+    assert!(table.index_select(0, &Tensor::from(vec![0.5])).is_err());
+}
+
+#[test]
+fn test_gather_picks_targets() {
+    let logits = array![[1.0, 2.0, 0.5], [0.0, -1.0, 3.0]].into_dyn();
+    let x = Tensor::new(logits.clone()).require_grad(true);
+    let target = Tensor::new(array![[1.0], [2.0]].into_dyn());
+    let log_probs = x.log_softmax(1);
+    let picked = log_probs.gather(1, &target).unwrap();
+    assert_eq!(picked.shape(), vec![2, 1]);
+    let loss = -&picked.mean();
+    loss.backward();
+
+    // 与 nll_loss 的结果及梯度一致
+    let y = Tensor::new(logits).require_grad(true);
+    let reference = functional::nll_loss(
+        &y.log_softmax(1),
+        &Tensor::from(vec![1.0, 2.0]),
+        None,
+        None,
+        Reduction::Mean,
+    );
+    reference.backward();
+    assert!((loss.data().sum() - reference.data().sum()).abs() < 1e-6);
+    for (a, b) in grad_of(&x).iter().zip(grad_of(&y).iter()) {
+        assert!((a - b).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn test_scatter_and_scatter_add() {
+    let x = Tensor::zeros(&[2, 3]).require_grad(true);
+    let src = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn()).require_grad(true);
+    let index = Tensor::new(array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].into_dyn());
+
+    let added = x.scatter_add(0, &index, &src).unwrap();
+    assert_eq!(
+        added.data(),
+        array![[5.0, 5.0, 3.0], [0.0, 2.0, 6.0]].into_dyn()
+    );
+    let w = Tensor::new(arange(&[2, 3]));
+    (&added * &w).sum().backward();
+    assert_eq!(grad_of(&x), arange(&[2, 3]));
+    // src 的梯度为输出梯度在对应目标位置的值
+    assert_eq!(
+        grad_of(&src),
+        array![[0.0, 4.0, 2.0], [0.0, 1.0, 5.0]].into_dyn()
+    );
+
+    let x = Tensor::ones(&[2, 3]).require_grad(true);
+    let src = Tensor::new(array![[7.0, 8.0]].into_dyn()).require_grad(true);
+    let index = Tensor::new(array![[1.0, 0.0]].into_dyn());
+    let written = x.scatter(0, &index, &src).unwrap();
+    assert_eq!(
+        written.data(),
+        array![[1.0, 8.0, 1.0], [7.0, 1.0, 1.0]].into_dyn()
+    );
+    written.sum().backward();
+    // 被覆盖的位置不再把梯度传给原输入
+    assert_eq!(
+        grad_of(&x),
+        array![[1.0, 0.0, 1.0], [0.0, 1.0, 1.0]].into_dyn()
+    );
+    assert_eq!(grad_of(&src), array![[1.0, 1.0]].into_dyn());
+}
+
+#[test]
+fn test_scatter_from_larger_src() {
+    // src 比 index 大时只使用前部区域，其余元素的梯度为 0
+    let x = Tensor::zeros(&[4]).require_grad(true);
+    let src = Tensor::from(vec![1.0, 2.0, 3.0]).require_grad(true);
+    let index = Tensor::from(vec![3.0, 0.0]);
+    let written = x.scatter(0, &index, &src).unwrap();
+    assert_eq!(written.data(), array![2.0, 0.0, 0.0, 1.0].into_dyn());
+    let w = Tensor::from(vec![10.0, 20.0, 30.0, 40.0]);
+    let loss = (&written * &w).sum();
+    let dsrc = grad(&[&loss], &[&src], None, true).unwrap();
+    assert_eq!(dsrc[0].data(), array![40.0, 10.0, 0.0].into_dyn());
+    loss.backward();
+    assert_eq!(grad_of(&src), array![40.0, 10.0, 0.0].into_dyn());
+}
+
+#[test]
+fn test_masked_select_and_mask_index() {
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let mask = Tensor::new(array![1.0, 0.0, 1.0].into_dyn());
+    let selected = x.masked_select(&mask).unwrap();
+    assert_eq!(selected.data(), array![0.0, 2.0, 3.0, 5.0].into_dyn());
+    selected.sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[1.0, 0.0, 1.0], [1.0, 0.0, 1.0]].into_dyn()
+    );
+
+    // 只覆盖第一维的掩码选出整行
+    let x = Tensor::new(arange(&[3, 2])).require_grad(true);
+    let rows = x.index_mask(&Tensor::from(vec![0.0, 1.0, 1.0])).unwrap();
+    assert_eq!(rows.data(), array![[2.0, 3.0], [4.0, 5.0]].into_dyn());
+    rows.sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[0.0, 0.0], [1.0, 1.0], [1.0, 1.0]].into_dyn()
+    );
+    assert!(x.index_mask(&Tensor::from(vec![1.0, 0.0])).is_err());
+}
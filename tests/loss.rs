@@ -0,0 +1,280 @@
+use ndarray::{ArrayD, array};
+use torch_rs::functional::{self, Reduction};
+use torch_rs::nn::linear::Linear;
+use torch_rs::nn::loss::{
+    BCELoss, BCEWithLogitsLoss, CrossEntropyLoss, HuberLoss, L1Loss, Loss, MSELoss,
+    TripletMarginLoss,
+};
+use torch_rs::nn::relu::ReLU;
+use torch_rs::nn::sequential::Sequential;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{assert_close, grad_of, numeric_grad};
+
+#[test]
+fn test_l1_loss_reductions() {
+    let x = Tensor::new(array![1.0, -2.0, 3.0].into_dyn()).require_grad(true);
+    let y = Tensor::new(array![0.0, 0.0, 5.0].into_dyn());
+
+    let none = functional::l1_loss(&x, &y, Reduction::None);
+    assert_close(&none.data(), &array![1.0, 2.0, 2.0].into_dyn(), 1e-6);
+    let sum = functional::l1_loss(&x, &y, Reduction::Sum);
+    assert_close(&sum.data(), &ArrayD::from_elem(vec![], 5.0), 1e-6);
+
+    let mean = functional::l1_loss(&x, &y, Reduction::Mean);
+    assert_close(&mean.data(), &ArrayD::from_elem(vec![], 5.0 / 3.0), 1e-6);
+    mean.backward();
+    assert_close(
+        &grad_of(&x),
+        &array![1.0 / 3.0, -1.0 / 3.0, -1.0 / 3.0].into_dyn(),
+        1e-6,
+    );
+}
+
+#[test]
+fn test_huber_and_smooth_l1() {
+    let x = Tensor::new(array![0.5, -3.0, 1.0].into_dyn()).require_grad(true);
+    let y = Tensor::zeros(&[3]);
+
+    // delta = 1：0.5 * 0.25，1 * (3 - 0.5)，1 * (1 - 0.5)
+    let huber = functional::huber_loss(&x, &y, 1.0, Reduction::None);
+    assert_close(&huber.data(), &array![0.125, 2.5, 0.5].into_dyn(), 1e-6);
+    huber.sum().backward();
+    assert_close(&grad_of(&x), &array![0.5, -1.0, 1.0].into_dyn(), 1e-6);
+
+    // smooth_l1 = huber / beta
+    let x = Tensor::new(array![0.5, -3.0, 1.0].into_dyn());
+    let smooth = functional::smooth_l1_loss(&x, &y, 2.0, Reduction::None);
+    assert_close(&smooth.data(), &array![0.0625, 2.0, 0.25].into_dyn(), 1e-6);
+    let l1 = functional::smooth_l1_loss(&x, &y, 0.0, Reduction::None);
+    assert_close(&l1.data(), &array![0.5, 3.0, 1.0].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_binary_cross_entropy() {
+    let p = array![0.9, 0.2, 0.0].into_dyn();
+    let t = Tensor::new(array![1.0, 0.0, 1.0].into_dyn());
+    let x = Tensor::new(p.clone()).require_grad(true);
+    let loss = functional::binary_cross_entropy(&x, &t, None, Reduction::None);
+    // 概率为 0 而目标为 1 时，对数被截断为 -100
+    assert_close(
+        &loss.data(),
+        &array![-(0.9f32.ln()), -(0.8f32.ln()), 100.0].into_dyn(),
+        1e-5,
+    );
+
+    let w = Tensor::new(array![2.0, 1.0, 0.0].into_dyn());
+    let x = Tensor::new(array![0.9, 0.2, 0.5].into_dyn()).require_grad(true);
+    functional::binary_cross_entropy(&x, &t, Some(&w), Reduction::Sum).backward();
+    assert_close(
+        &grad_of(&x),
+        &array![-2.0 / 0.9, 1.0 / 0.8, 0.0].into_dyn(),
+        1e-4,
+    );
+}
+
+#[test]
+fn test_bce_with_logits_matches_bce() {
+    let logits = array![[2.0, -1.0, 0.0], [-30.0, 30.0, 0.5]].into_dyn();
+    let t = Tensor::new(array![[1.0, 0.0, 1.0], [0.0, 1.0, 0.0]].into_dyn());
+    let x = Tensor::new(logits.clone()).require_grad(true);
+    let loss = functional::binary_cross_entropy_with_logits(&x, &t, None, None, Reduction::Mean);
+    let reference =
+        functional::binary_cross_entropy(&Tensor::new(logits).sigmoid(), &t, None, Reduction::Mean);
+    assert_close(&loss.data(), &reference.data(), 1e-5);
+
+    // 梯度为 (sigmoid(x) - y) / N
+    loss.backward();
+    let expected = (&x.sigmoid() - &t).data() / 6.0;
+    assert_close(&grad_of(&x), &expected, 1e-6);
+}
+
+#[test]
+fn test_bce_with_logits_pos_weight() {
+    let x0 = array![[1.0, -2.0], [0.5, 3.0]].into_dyn();
+    let t = Tensor::new(array![[1.0, 0.0], [1.0, 1.0]].into_dyn());
+    let pw = Tensor::new(array![3.0, 0.5].into_dyn());
+    let x = Tensor::new(x0.clone()).require_grad(true);
+    let loss =
+        functional::binary_cross_entropy_with_logits(&x, &t, None, Some(&pw), Reduction::None);
+    // 正样本项乘以 pos_weight：-pw * y * ln σ(x) - (1 - y) * ln(1 - σ(x))
+    let s = x0.mapv(|v| 1.0 / (1.0 + (-v).exp()));
+    let expected = array![
+        [-3.0 * s[[0, 0]].ln(), -(1.0 - s[[0, 1]]).ln()],
+        [-3.0 * s[[1, 0]].ln(), -0.5 * s[[1, 1]].ln()]
+    ]
+    .into_dyn();
+    assert_close(&loss.data(), &expected, 1e-5);
+
+    loss.sum().backward();
+    let numeric = numeric_grad(&x0, |x| {
+        functional::binary_cross_entropy_with_logits(x, &t, None, Some(&pw), Reduction::None)
+    });
+    assert_close(&grad_of(&x), &numeric, 1e-2);
+}
+
+#[test]
+fn test_kl_div() {
+    let p = array![0.2, 0.3, 0.5].into_dyn();
+    let q = array![0.5, 0.0, 0.5].into_dyn();
+    let x = Tensor::new(p.mapv(f32::ln)).require_grad(true);
+    let target = Tensor::new(q.clone());
+    let loss = functional::kl_div(&x, &target, false, Reduction::Sum);
+    // 目标为 0 的项不贡献损失
+    let expected = 0.5 * (0.5f32 / 0.2).ln() + 0.5 * (0.5f32 / 0.5).ln();
+    assert_close(&loss.data(), &ArrayD::from_elem(vec![], expected), 1e-5);
+    loss.backward();
+    assert_close(&grad_of(&x), &(-&q), 1e-6);
+
+    let log_target = Tensor::new(array![0.5f32, 0.25, 0.25].mapv(f32::ln).into_dyn());
+    let loss = functional::kl_div(&x, &log_target, true, Reduction::Sum);
+    let expected =
+        0.5 * (0.5f32 / 0.2).ln() + 0.25 * (0.25f32 / 0.3).ln() + 0.25 * (0.25f32 / 0.5).ln();
+    assert_close(&loss.data(), &ArrayD::from_elem(vec![], expected), 1e-5);
+}
+
+#[test]
+fn test_cosine_embedding_loss() {
+    let x1 = Tensor::new(array![[1.0, 0.0], [1.0, 1.0], [0.0, 2.0]].into_dyn()).require_grad(true);
+    let x2 = Tensor::new(array![[1.0, 1.0], [-1.0, -1.0], [0.0, 1.0]].into_dyn());
+    let y = Tensor::new(array![1.0, -1.0, -1.0].into_dyn());
+    let loss = functional::cosine_embedding_loss(&x1, &x2, &y, 0.1, Reduction::None);
+    // cos = [1/√2, -1, 1]
+    let cos0 = 1.0 / 2f32.sqrt();
+    assert_close(&loss.data(), &array![1.0 - cos0, 0.0, 0.9].into_dyn(), 1e-5);
+
+    loss.sum().backward();
+    let numeric = numeric_grad(&x1.data(), |x| {
+        functional::cosine_embedding_loss(x, &x2, &y, 0.1, Reduction::None)
+    });
+    assert_close(&grad_of(&x1), &numeric, 1e-2);
+}
+
+#[test]
+fn test_margin_ranking_and_hinge_embedding() {
+    let x1 = Tensor::new(array![1.0, 2.0, 0.0].into_dyn()).require_grad(true);
+    let x2 = Tensor::new(array![2.0, 0.0, 0.0].into_dyn());
+    let y = Tensor::new(array![1.0, 1.0, -1.0].into_dyn());
+    let loss = functional::margin_ranking_loss(&x1, &x2, &y, 0.5, Reduction::None);
+    assert_close(&loss.data(), &array![1.5, 0.0, 0.5].into_dyn(), 1e-6);
+    loss.sum().backward();
+    assert_close(&grad_of(&x1), &array![-1.0, 0.0, 1.0].into_dyn(), 1e-6);
+
+    let d = Tensor::new(array![0.3, 0.3, 2.0].into_dyn()).require_grad(true);
+    let loss = functional::hinge_embedding_loss(&d, &y, 1.0, Reduction::None);
+    assert_close(&loss.data(), &array![0.3, 0.3, 0.0].into_dyn(), 1e-6);
+    loss.sum().backward();
+    assert_close(&grad_of(&d), &array![1.0, 1.0, 0.0].into_dyn(), 1e-6);
+}
+
+#[test]
+fn test_triplet_margin_loss() {
+    let a0 = array![[0.0, 0.0], [1.0, 1.0]].into_dyn();
+    let p = Tensor::new(array![[3.0, 4.0], [1.0, 2.0]].into_dyn());
+    let n = Tensor::new(array![[0.0, 1.0], [4.0, 5.0]].into_dyn());
+    let a = Tensor::new(a0.clone()).require_grad(true);
+    let loss = functional::triplet_margin_loss(&a, &p, &n, 1.0, 2.0, 0.0, false, Reduction::None);
+    // d(a, p) = [5, 1]，d(a, n) = [1, 5]
+    assert_close(&loss.data(), &array![5.0, 0.0].into_dyn(), 1e-5);
+    loss.sum().backward();
+    let numeric = numeric_grad(&a0, |a| {
+        functional::triplet_margin_loss(a, &p, &n, 1.0, 2.0, 0.0, false, Reduction::None)
+    });
+    assert_close(&grad_of(&a), &numeric, 1e-2);
+
+    // swap 时负样本距离取 min(d(a, n), d(p, n))
+    let a = Tensor::new(array![[0.0, 0.0]].into_dyn());
+    let p = Tensor::new(array![[0.0, 3.0]].into_dyn());
+    let n = Tensor::new(array![[0.0, 4.0]].into_dyn());
+    let loss = functional::triplet_margin_loss(&a, &p, &n, 1.0, 2.0, 0.0, true, Reduction::Sum);
+    assert_close(&loss.data(), &ArrayD::from_elem(vec![], 3.0), 1e-5);
+}
+
+#[test]
+fn test_loss_modules() {
+    let x = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+    let y = Tensor::new(array![[1.0, 0.0], [0.0, 4.0]].into_dyn());
+    let losses: Vec<Box<dyn Loss>> = vec![
+        Box::new(MSELoss::new(Reduction::Sum)),
+        Box::new(L1Loss::new(Reduction::Mean)),
+        Box::new(HuberLoss::default()),
+    ];
+    let values: Vec<f32> = losses
+        .iter()
+        .map(|l| l.forward(&[&x, &y]).data().sum())
+        .collect();
+    assert_close(
+        &ArrayD::from_shape_vec(vec![3], values).unwrap(),
+        &array![13.0, 1.25, 1.0].into_dyn(),
+        1e-6,
+    );
+
+    let mse = MSELoss::default().forward(&[&x, &y]);
+    assert_close(&mse.data(), &functional::mse_loss(&x, &y).data(), 1e-6);
+
+    let probs = Tensor::new(array![0.5, 0.5].into_dyn());
+    let target = Tensor::new(array![1.0, 0.0].into_dyn());
+    let bce = BCELoss::default().forward(&[&probs, &target]);
+    let bce_logits = BCEWithLogitsLoss::default().forward(&[&Tensor::zeros(&[2]), &target]);
+    assert_close(&bce.data(), &ArrayD::from_elem(vec![], 2f32.ln()), 1e-6);
+    assert_close(&bce_logits.data(), &bce.data(), 1e-6);
+
+    let logits = Tensor::new(array![[0.0, 0.0]].into_dyn());
+    let class = Tensor::new(array![1.0].into_dyn());
+    let ce = CrossEntropyLoss::default().forward(&[&logits, &class]);
+    assert_close(&ce.data(), &ArrayD::from_elem(vec![], 2f32.ln()), 1e-6);
+
+    let triplet = TripletMarginLoss::default();
+    let zeros = Tensor::zeros(&[1, 2]);
+    assert_close(
+        &triplet.forward(&[&zeros, &zeros, &zeros]).data(),
+        &ArrayD::from_elem(vec![], 1.0),
+        1e-5,
+    );
+}
+
+#[test]
+#[should_panic(expected = "expects 3 input tensors")]
+fn test_loss_module_input_count() {
+    let x = Tensor::zeros(&[2]);
+    TripletMarginLoss::default().forward(&[&x, &x]);
+}
+
+#[test]
+fn test_loss_as_module_in_sequential() {
+    use torch_rs::nn::Module;
+
+    let layer = Linear::new(3, 2);
+    let x = Tensor::new(array![[1.0, -2.0, 0.5], [0.3, 0.7, -1.1]].into_dyn());
+    let target = Tensor::new(array![[1.0, 0.0], [0.0, 1.0]].into_dyn());
+    let expected = functional::mse_loss(&layer.forward(&x), &target);
+
+    // 损失作为最后一层，整个网络的输出即损失
+    let model = Sequential::new(vec![
+        Box::new(layer),
+        Box::new(MSELoss::default().with_targets(&[&target])),
+    ]);
+    assert_eq!(model.parameters().len(), 2);
+    let loss = model.forward(&x);
+    assert_close(&loss.data(), &expected.data(), 1e-6);
+    loss.backward();
+    assert!(grad_of(&model.parameters()[0]).iter().any(|&g| g != 0.0));
+
+    // 三个输入的损失绑定其余两个输入
+    let zeros = Tensor::zeros(&[1, 2]);
+    let triplet: Box<dyn Module> =
+        Box::new(TripletMarginLoss::default().with_targets(&[&zeros, &zeros]));
+    let relu_then_triplet = Sequential::new(vec![Box::new(ReLU::new()), triplet]);
+    assert_close(
+        &relu_then_triplet.forward(&zeros).data(),
+        &ArrayD::from_elem(vec![], 1.0),
+        1e-5,
+    );
+}
+
+#[test]
+#[should_panic(expected = "MSELoss used as a Module needs its targets set with with_targets")]
+fn test_loss_module_without_targets() {
+    torch_rs::nn::Module::forward(&MSELoss::default(), &Tensor::zeros(&[2]));
+}
@@ -1,5 +1,10 @@
-use ndarray::array;
+use ndarray::{ArrayD, Ix2, array, s};
+use torch_rs::ops::Op;
+use torch_rs::ops::matmul::MatMul;
 use torch_rs::{ops::matmul::matmul, tensor::Tensor};
+
+mod common;
+use common::{assert_close, grad_of};
 #[test]
 fn test_tensor_matmul_2d() {
     let a = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
@@ -8,7 +13,7 @@ fn test_tensor_matmul_2d() {
     // 预期结果: A:[2,2], B:[2,2] -> Out:[2,2]
     // [[1*5+2*7, 1*6+2*8], [3*5+4*7, 3*6+4*8]] = [[19, 22], [43, 50]]
     let expected = array![[19.0, 22.0], [43.0, 50.0]].into_dyn();
-    assert_eq!(result.0.borrow().data, expected);
+    assert_eq!(result.data(), expected);
 }
 #[test]
 fn test_tensor_matmul_2d_grad() {
@@ -43,7 +48,7 @@ fn test_tensor_3d_matmul() {
         [[128.0, 152.0, 176.0, 200.0], [173.0, 206.0, 239.0, 272.0]]
     ]
     .into_dyn();
-    assert_eq!(result.0.borrow().data, expected);
+    assert_eq!(result.data(), expected);
 }
 #[test]
 fn test_tensor_3d_matmul_grad() {
@@ -76,3 +81,156 @@ fn test_vectors_matmul() {
     result.backward();
     println!("a:{:?}, b:{:?}, c:{:?}", a, b, c);
 }
+
+#[test]
+fn test_batched_matmul_broadcast() {
+    // a: [2, 1, 2, 3]，b: [3, 3, 2] -> [2, 3, 2, 2]
+    let a_data = ArrayD::from_shape_fn(vec![2, 1, 2, 3], |i| (i[0] * 6 + i[2] * 3 + i[3]) as f32);
+    let b_data = ArrayD::from_shape_fn(vec![3, 3, 2], |i| (i[0] + i[1]) as f32 - i[2] as f32);
+    let a = Tensor::new(a_data.clone()).require_grad(true);
+    let b = Tensor::new(b_data.clone()).require_grad(true);
+    let result = a.matmul(&b);
+    assert_eq!(result.shape(), vec![2, 3, 2, 2]);
+    for i in 0..2 {
+        for j in 0..3 {
+            let a_mat = a_data
+                .slice(s![i, 0, .., ..])
+                .into_dimensionality::<Ix2>()
+                .unwrap();
+            let b_mat = b_data
+                .slice(s![j, .., ..])
+                .into_dimensionality::<Ix2>()
+                .unwrap();
+            assert_eq!(
+                result.data().slice(s![i, j, .., ..]),
+                a_mat.dot(&b_mat).view()
+            );
+        }
+    }
+
+    // 梯度需要在广播的批量维度上求和
+    result.sum().backward();
+    let ones = ArrayD::<f32>::ones(vec![2, 2]);
+    let mut expected_a = ArrayD::zeros(vec![2, 1, 2, 3]);
+    let mut expected_b = ArrayD::zeros(vec![3, 3, 2]);
+    for i in 0..2 {
+        for j in 0..3 {
+            let a_mat = a_data
+                .slice(s![i, 0, .., ..])
+                .into_dimensionality::<Ix2>()
+                .unwrap();
+            let b_mat = b_data
+                .slice(s![j, .., ..])
+                .into_dimensionality::<Ix2>()
+                .unwrap();
+            let g = ones.view().into_dimensionality::<Ix2>().unwrap();
+            let mut ga = expected_a.slice_mut(s![i, 0, .., ..]);
+            ga += &g.dot(&b_mat.t());
+            let mut gb = expected_b.slice_mut(s![j, .., ..]);
+            gb += &a_mat.t().dot(&g);
+        }
+    }
+    assert_close(&grad_of(&a), &expected_a, 1e-4);
+    assert_close(&grad_of(&b), &expected_b, 1e-4);
+}
+
+#[test]
+fn test_matmul_vector_promotion() {
+    let m = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn()).require_grad(true);
+    let v = Tensor::new(array![1.0, 0.0, -1.0].into_dyn()).require_grad(true);
+    let mv = m.mv(&v);
+    assert_eq!(mv.data(), array![-2.0, -2.0].into_dyn());
+    mv.sum().backward();
+    assert_eq!(
+        grad_of(&m),
+        array![[1.0, 0.0, -1.0], [1.0, 0.0, -1.0]].into_dyn()
+    );
+    assert_eq!(grad_of(&v), array![5.0, 7.0, 9.0].into_dyn());
+
+    // 1 维左操作数：[2] @ [2, 3] -> [3]
+    let u = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let m = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn()).require_grad(true);
+    let vm = u.matmul(&m);
+    assert_eq!(vm.data(), array![9.0, 12.0, 15.0].into_dyn());
+    vm.sum().backward();
+    assert_eq!(grad_of(&u), array![6.0, 15.0].into_dyn());
+    assert_eq!(
+        grad_of(&m),
+        array![[1.0, 1.0, 1.0], [2.0, 2.0, 2.0]].into_dyn()
+    );
+
+    // 1 维向量与批量矩阵：[2] @ [4, 2, 3] -> [4, 3]
+    let batch = Tensor::ones(&[4, 2, 3]);
+    assert_eq!(u.matmul(&batch).shape(), vec![4, 3]);
+}
+
+#[test]
+fn test_dot_and_outer() {
+    let a = Tensor::new(array![1.0, 2.0, 3.0].into_dyn()).require_grad(true);
+    let b = Tensor::new(array![4.0, 5.0, 6.0].into_dyn()).require_grad(true);
+    let d = a.dot(&b);
+    assert_eq!(d.shape(), Vec::<usize>::new());
+    assert_eq!(d.data().sum(), 32.0);
+    d.backward();
+    assert_eq!(grad_of(&a), b.data());
+    assert_eq!(grad_of(&b), a.data());
+
+    let a = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let b = Tensor::new(array![3.0, 4.0, 5.0].into_dyn()).require_grad(true);
+    let o = a.outer(&b);
+    assert_eq!(
+        o.data(),
+        array![[3.0, 4.0, 5.0], [6.0, 8.0, 10.0]].into_dyn()
+    );
+    o.sum().backward();
+    assert_eq!(grad_of(&a), array![12.0, 12.0].into_dyn());
+    assert_eq!(grad_of(&b), array![3.0, 3.0, 3.0].into_dyn());
+}
+
+#[test]
+fn test_bmm_and_transposed_operands() {
+    let a_data = ArrayD::from_shape_fn(vec![2, 2, 3], |i| (i[0] + 2 * i[1] + i[2]) as f32);
+    let b_data = ArrayD::from_shape_fn(vec![2, 3, 2], |i| (i[0] * i[2]) as f32 + i[1] as f32);
+    let a = Tensor::new(a_data.clone()).require_grad(true);
+    let b = Tensor::new(b_data.clone()).require_grad(true);
+    let c = a.bmm(&b);
+    assert_eq!(c.shape(), vec![2, 2, 2]);
+
+    // A @ Bᵀ 与先转置再相乘的结果一致
+    let bt_data = b_data
+        .clone()
+        .permuted_axes(vec![0, 2, 1])
+        .as_standard_layout()
+        .into_owned();
+    let bt = Tensor::new(bt_data);
+    let c_t = MatMul::with_transpose(false, true).forward(&[&a, &bt]);
+    assert_close(&c_t.data(), &c.data(), 1e-6);
+
+    c.sum().backward();
+    let grad_a = grad_of(&a);
+    a.0.borrow_mut().grad = None;
+    let bt = bt.require_grad(true);
+    let c_t = MatMul::with_transpose(false, true).forward(&[&a, &bt]);
+    c_t.sum().backward();
+    assert_close(&grad_of(&a), &grad_a, 1e-6);
+    let grad_bt = grad_of(&bt)
+        .permuted_axes(vec![0, 2, 1])
+        .as_standard_layout()
+        .into_owned();
+    assert_close(&grad_bt, &grad_of(&b), 1e-6);
+}
+
+#[test]
+fn test_matmul_grad_only_second_operand() {
+    // 只有右操作数需要梯度时，梯度也应传给正确的张量
+    let a = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+    let b = Tensor::new(array![[5.0, 6.0], [7.0, 8.0]].into_dyn()).require_grad(true);
+    matmul(&a, &b).sum().backward();
+    assert_eq!(grad_of(&b), array![[4.0, 4.0], [6.0, 6.0]].into_dyn());
+}
+
+#[test]
+#[should_panic(expected = "Matrix dimensions do not match")]
+fn test_matmul_shape_mismatch() {
+    matmul(&Tensor::ones(&[2, 3]), &Tensor::ones(&[2, 3]));
+}
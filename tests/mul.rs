@@ -13,7 +13,7 @@ fn test_mul() {
         .unwrap();
 
     // 验证结果
-    assert_eq!(result.0.borrow().data, expected.0.borrow().data);
+    assert_eq!(result.data(), expected.data());
 }
 #[test]
 fn test_mul_scalar() {
@@ -26,13 +26,16 @@ fn test_mul_scalar() {
     let expected = Tensor::from(vec![2.0, 4.0, 6.0]).reshape(&[3, 1]).unwrap();
 
     // 验证结果
-    assert_eq!(result.0.borrow().data, expected.0.borrow().data);
+    assert_eq!(result.data(), expected.data());
 }
 
 #[test]
 fn test_mul_scalar_grad() {
     // 创建一个张量
-    let a = Tensor::from(vec![1.0, 2.0, 3.0]).reshape(&[3, 1]).unwrap();
+    let a = Tensor::from(vec![1.0, 2.0, 3.0])
+        .reshape(&[3, 1])
+        .unwrap()
+        .require_grad(true);
 
     // 乘以一个标量
     let result: Tensor = &a * 2.0;
@@ -44,5 +47,14 @@ fn test_mul_scalar_grad() {
     let grad = a.0.borrow().grad.clone().unwrap();
     let expected_grad = Tensor::from(vec![2.0, 2.0, 2.0]).reshape(&[3, 1]).unwrap();
 
-    assert_eq!(grad, expected_grad.0.borrow().data);
+    assert_eq!(grad, expected_grad.data());
+}
+
+#[test]
+fn test_mul_scalar_keeps_shape() {
+    // 标量按零维张量参与广播，零维张量乘以标量仍是零维
+    let a = Tensor::new(ndarray::arr0(3.0).into_dyn());
+    assert_eq!((&a * 2.0_f32).shape(), Vec::<usize>::new());
+    assert_eq!((2.0_f32 * &a).data(), ndarray::arr0(6.0).into_dyn());
+    assert_eq!((&a * 2.0_f64).shape(), Vec::<usize>::new());
 }
@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use torch_rs::tensor::Tensor;
+
+    #[test]
+    fn test_powf() {
+        let a = Tensor::from(vec![1.0, 2.0, 3.0]).require_grad(true);
+
+        let result = a.powf(2.0);
+        assert_eq!(result.data(), array![1.0, 4.0, 9.0].into_dyn());
+
+        result.backward();
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![2.0, 4.0, 6.0].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_pow_tensor_exponent_grad() {
+        let a = Tensor::from(vec![2.0, 3.0]).require_grad(true);
+        let b = Tensor::from(vec![3.0, 2.0]).require_grad(true);
+
+        let result = a.pow(&b);
+        assert_eq!(result.data(), array![8.0, 9.0].into_dyn());
+        result.backward();
+
+        // ∂(a^b)/∂a = b * a^(b-1)
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![12.0, 6.0].into_dyn()
+        );
+        // ∂(a^b)/∂b = a^b * ln(a)
+        let b_grad = b.0.borrow().grad.clone().unwrap();
+        assert!((b_grad[0] - 8.0 * 2.0f32.ln()).abs() < 1e-5);
+        assert!((b_grad[1] - 9.0 * 3.0f32.ln()).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_pow_broadcasting_grad() {
+        let a = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn()).require_grad(true);
+        let b = Tensor::new(array![[2.0], [1.0]].into_dyn());
+
+        let result = a.pow(&b);
+        assert_eq!(result.data(), array![[1.0, 4.0], [3.0, 4.0]].into_dyn());
+        result.backward();
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![[2.0, 4.0], [1.0, 1.0]].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_pow_zero_base_grad() {
+        // 底数为0时，对指数的梯度约定为0而不是NaN
+        let a = Tensor::from(vec![0.0, 1.0]);
+        let b = Tensor::from(vec![2.0, 2.0]).require_grad(true);
+
+        let result = a.pow(&b);
+        result.backward();
+        assert_eq!(
+            b.0.borrow().grad.clone().unwrap(),
+            array![0.0, 0.0].into_dyn()
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use ndarray::{ArrayD, array};
+use torch_rs::dtype::DType;
+use torch_rs::functional::{self, Reduction};
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{assert_close, grad_of};
+
+fn sample() -> Tensor {
+    Tensor::new(array![[1.0, 5.0, 3.0], [4.0, 2.0, 6.0]].into_dyn()).require_grad(true)
+}
+
+#[test]
+fn test_sum_dims() {
+    let x = sample();
+    assert_eq!(x.sum().data(), ndarray::arr0(21.0).into_dyn());
+    assert_eq!(
+        x.sum_dims(&[0], false).data(),
+        array![5.0, 7.0, 9.0].into_dyn()
+    );
+    assert_eq!(
+        x.sum_dims(&[1], true).data(),
+        array![[9.0], [12.0]].into_dyn()
+    );
+
+    let weights = Tensor::new(array![1.0, 2.0].into_dyn());
+    let y = &x.sum_dims(&[1], false) * &weights;
+    y.sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[1.0, 1.0, 1.0], [2.0, 2.0, 2.0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_sum_multiple_dims() {
+    let x = Tensor::ones(&[2, 3, 4]).require_grad(true);
+    let y = x.sum_dims(&[0, 2], true);
+    assert_eq!(y.shape(), vec![1, 3, 1]);
+    assert_eq!(y.data(), ArrayD::from_elem(vec![1, 3, 1], 8.0));
+    y.backward();
+    assert_eq!(grad_of(&x), ArrayD::from_elem(vec![2, 3, 4], 1.0));
+}
+
+#[test]
+#[should_panic]
+fn test_sum_dim_out_of_range() {
+    sample().sum_dims(&[2], false);
+}
+
+#[test]
+fn test_mean_dims() {
+    let x = sample();
+    let y = x.mean_dims(&[0], false);
+    assert_eq!(y.data(), array![2.5, 3.5, 4.5].into_dyn());
+    y.sum().backward();
+    assert_eq!(grad_of(&x), ArrayD::from_elem(vec![2, 3], 0.5));
+
+    let x = sample();
+    let y = x.mean_dims(&[1], true);
+    assert_eq!(y.data(), array![[3.0], [4.0]].into_dyn());
+}
+
+#[test]
+fn test_mean_as_intermediate() {
+    // mean 的输出作为中间结果时，梯度不应带有额外的初始值
+    let x = Tensor::from(vec![1.0, 2.0, 3.0]).require_grad(true);
+    let y: Tensor = &x.mean() * 2.0;
+    y.backward();
+    assert_close(&grad_of(&x), &ArrayD::from_elem(vec![3], 2.0 / 3.0), 1e-5);
+}
+
+#[test]
+fn test_max_min_dims() {
+    let x = sample();
+    let y = x.max_dims(&[1], false);
+    assert_eq!(y.data(), array![5.0, 6.0].into_dyn());
+    y.sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[0.0, 1.0, 0.0], [0.0, 0.0, 1.0]].into_dyn()
+    );
+
+    let x = sample();
+    let y = x.min_dims(&[0], true);
+    assert_eq!(y.data(), array![[1.0, 2.0, 3.0]].into_dyn());
+    y.sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[1.0, 0.0, 1.0], [0.0, 1.0, 0.0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_max_all_routes_to_single_element() {
+    let x = Tensor::new(array![[1.0, 7.0], [7.0, 2.0]].into_dyn()).require_grad(true);
+    let y = x.max();
+    assert_eq!(y.data(), ndarray::arr0(7.0).into_dyn());
+    y.backward();
+    // 并列时梯度只传给第一个最大值
+    assert_eq!(grad_of(&x), array![[0.0, 1.0], [0.0, 0.0]].into_dyn());
+    assert_eq!(x.min().data(), ndarray::arr0(1.0).into_dyn());
+}
+
+#[test]
+fn test_argmax_argmin() {
+    let x = sample();
+    assert_eq!(x.argmax(&[1], false).data(), array![1.0, 2.0].into_dyn());
+    assert_eq!(
+        x.argmin(&[0], true).data(),
+        array![[0.0, 1.0, 0.0]].into_dyn()
+    );
+    // 空列表：展平后的下标
+    assert_eq!(x.argmax(&[], false).data(), ndarray::arr0(5.0).into_dyn());
+    assert!(!x.argmax(&[1], false).0.borrow().requires_grad);
+
+    // 下标为 int64，可以直接用于索引与分类损失的目标
+    let index = x.argmax(&[1], true);
+    assert_eq!(index.dtype(), DType::I64);
+    assert_eq!(
+        x.gather(1, &index).unwrap().data(),
+        array![[5.0], [6.0]].into_dyn()
+    );
+    let target = x.argmax(&[1], false);
+    let loss = functional::nll_loss(&x, &target, None, None, Reduction::Sum);
+    assert_eq!(loss.data(), ndarray::arr0(-11.0).into_dyn());
+}
+
+#[test]
+fn test_prod_dims() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 0.0, 6.0]].into_dyn()).require_grad(true);
+    let y = x.prod_dims(&[1], false);
+    assert_eq!(y.data(), array![6.0, 0.0].into_dyn());
+    y.sum().backward();
+    // 含 0 的行：只有 0 所在位置的梯度非零
+    assert_eq!(
+        grad_of(&x),
+        array![[6.0, 3.0, 2.0], [0.0, 24.0, 0.0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_var_std() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0, 4.0]].into_dyn()).require_grad(true);
+    let var = x.var(&[1], true, false);
+    assert_close(&var.data(), &array![5.0 / 3.0].into_dyn(), 1e-5);
+    let var_biased = x.var(&[1], false, true);
+    assert_close(&var_biased.data(), &array![[1.25]].into_dyn(), 1e-5);
+
+    var.sum().backward();
+    // ∂var/∂x_i = 2 (x_i - mean) / (N - 1)
+    assert_close(
+        &grad_of(&x),
+        &array![[-1.0, -1.0 / 3.0, 1.0 / 3.0, 1.0]].into_dyn(),
+        1e-5,
+    );
+
+    let std = x.std(&[], false, false);
+    assert_close(&std.data(), &ndarray::arr0(1.25f32.sqrt()).into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_logsumexp() {
+    let x = Tensor::new(array![[1.0, 2.0, 3.0], [1000.0, 1000.0, 1000.0]].into_dyn())
+        .require_grad(true);
+    let y = x.logsumexp(&[1], false);
+    let expected0 = (1.0f32.exp() + 2.0f32.exp() + 3.0f32.exp()).ln();
+    // 大输入下不应溢出
+    assert_close(
+        &y.data(),
+        &array![expected0, 1000.0 + 3.0f32.ln()].into_dyn(),
+        1e-5,
+    );
+    y.sum().backward();
+    let grad = grad_of(&x);
+    // 梯度为 softmax，每行和为 1
+    for row in grad.outer_iter() {
+        assert!((row.sum() - 1.0).abs() < 1e-5);
+    }
+    assert_close(
+        &grad.index_axis(ndarray::Axis(0), 1).to_owned().into_dyn(),
+        &ArrayD::from_elem(vec![3], 1.0 / 3.0),
+        1e-5,
+    );
+}
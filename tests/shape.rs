@@ -0,0 +1,162 @@
+use ndarray::{ArrayD, array};
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{arange, grad_of};
+
+/// 用与输出同形状的权重加权求和后反向传播，梯度即为权重被放回输入位置的结果
+fn weighted_backward(output: &Tensor) -> ArrayD<f32> {
+    let weight = arange(&output.shape());
+    (output * &Tensor::new(weight.clone())).sum().backward();
+    weight
+}
+
+#[test]
+fn test_reshape_family_keeps_graph() {
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let y = x.view(&[3, 2]).unwrap();
+    assert!(!y.is_leaf());
+    let w = weighted_backward(&y);
+    assert_eq!(grad_of(&x), w.into_shape_with_order(vec![2, 3]).unwrap());
+
+    let x = Tensor::new(arange(&[2, 1, 3])).require_grad(true);
+    let y = x.squeeze(Some(1)).unwrap().unsqueeze(0).unwrap();
+    assert_eq!(y.shape(), vec![1, 2, 3]);
+    weighted_backward(&y);
+    assert_eq!(grad_of(&x), arange(&[2, 1, 3]));
+
+    let x = Tensor::new(arange(&[2, 3, 4])).require_grad(true);
+    let y = x.flatten(1, 2).unwrap();
+    assert_eq!(y.shape(), vec![2, 12]);
+    weighted_backward(&y);
+    assert_eq!(grad_of(&x), arange(&[2, 3, 4]));
+}
+
+#[test]
+fn test_transpose_and_permute() {
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let y = x.transpose(0, 1).unwrap();
+    assert_eq!(
+        y.data(),
+        array![[0.0, 3.0], [1.0, 4.0], [2.0, 5.0]].into_dyn()
+    );
+    weighted_backward(&y);
+    assert_eq!(
+        grad_of(&x),
+        array![[0.0, 2.0, 4.0], [1.0, 3.0, 5.0]].into_dyn()
+    );
+
+    let x = Tensor::new(arange(&[2, 3, 4])).require_grad(true);
+    let y = x.permute(&[2, 0, 1]).unwrap();
+    assert_eq!(y.shape(), vec![4, 2, 3]);
+    assert_eq!(y.data()[[3, 1, 2]], x.data()[[1, 2, 3]]);
+    let w = weighted_backward(&y);
+    assert_eq!(grad_of(&x)[[1, 2, 3]], w[[3, 1, 2]]);
+    assert!(x.permute(&[0, 0, 1]).is_err());
+}
+
+#[test]
+fn test_expand_and_repeat() {
+    let x = Tensor::new(array![[1.0], [2.0]].into_dyn()).require_grad(true);
+    let y = x.expand(&[3, 2, 4]).unwrap();
+    assert_eq!(y.shape(), vec![3, 2, 4]);
+    y.sum().backward();
+    assert_eq!(grad_of(&x), array![[12.0], [12.0]].into_dyn());
+    assert!(x.expand(&[3, 4]).is_err());
+
+    let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let y = x.repeat(&[2, 3]).unwrap();
+    assert_eq!(
+        y.data(),
+        array![
+            [1.0, 2.0, 1.0, 2.0, 1.0, 2.0],
+            [1.0, 2.0, 1.0, 2.0, 1.0, 2.0]
+        ]
+        .into_dyn()
+    );
+    weighted_backward(&y);
+    // 元素 0 出现在偶数列：0+2+4+6+8+10，元素 1 出现在奇数列
+    assert_eq!(grad_of(&x), array![30.0, 36.0].into_dyn());
+}
+
+#[test]
+fn test_cat_stack_split_chunk() {
+    let a = Tensor::new(arange(&[2, 2])).require_grad(true);
+    let b = Tensor::new(arange(&[2, 1])).require_grad(true);
+    let c = Tensor::cat(&[a.clone(), b.clone()], 1).unwrap();
+    assert_eq!(
+        c.data(),
+        array![[0.0, 1.0, 0.0], [2.0, 3.0, 1.0]].into_dyn()
+    );
+    weighted_backward(&c);
+    assert_eq!(grad_of(&a), array![[0.0, 1.0], [3.0, 4.0]].into_dyn());
+    assert_eq!(grad_of(&b), array![[2.0], [5.0]].into_dyn());
+    assert!(Tensor::cat(&[a.clone(), b.clone()], 0).is_err());
+
+    let a = Tensor::new(arange(&[3])).require_grad(true);
+    let b = Tensor::new(arange(&[3])).require_grad(true);
+    let s = Tensor::stack(&[a.clone(), b.clone()]).unwrap();
+    assert_eq!(s.shape(), vec![2, 3]);
+    weighted_backward(&s);
+    assert_eq!(grad_of(&a), array![0.0, 1.0, 2.0].into_dyn());
+    assert_eq!(grad_of(&b), array![3.0, 4.0, 5.0].into_dyn());
+
+    let x = Tensor::new(arange(&[5, 2])).require_grad(true);
+    let parts = x.split(2, 0).unwrap();
+    assert_eq!(
+        parts.iter().map(|p| p.shape()).collect::<Vec<_>>(),
+        vec![vec![2, 2], vec![2, 2], vec![1, 2]]
+    );
+    // 只对最后一块求导，其余位置梯度为 0
+    parts[2].sum().backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[0.0, 0.0], [0.0, 0.0], [0.0, 0.0], [0.0, 0.0], [1.0, 1.0]].into_dyn()
+    );
+
+    let chunks = Tensor::new(arange(&[5])).chunk(2, 0).unwrap();
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[1].data(), array![3.0, 4.0].into_dyn());
+}
+
+#[test]
+fn test_flip_roll_and_index() {
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let y = x.flip(&[1]).unwrap();
+    assert_eq!(
+        y.data(),
+        array![[2.0, 1.0, 0.0], [5.0, 4.0, 3.0]].into_dyn()
+    );
+    weighted_backward(&y);
+    assert_eq!(
+        grad_of(&x),
+        array![[2.0, 1.0, 0.0], [5.0, 4.0, 3.0]].into_dyn()
+    );
+
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let y = x.roll(&[1], &[1]).unwrap();
+    assert_eq!(
+        y.data(),
+        array![[2.0, 0.0, 1.0], [5.0, 3.0, 4.0]].into_dyn()
+    );
+    weighted_backward(&y);
+    assert_eq!(
+        grad_of(&x),
+        array![[1.0, 2.0, 0.0], [4.0, 5.0, 3.0]].into_dyn()
+    );
+    // 不指定维度时按展平后的顺序移动
+    let flat = Tensor::new(arange(&[2, 3])).roll(&[-1], &[]).unwrap();
+    assert_eq!(
+        flat.data(),
+        array![[1.0, 2.0, 3.0], [4.0, 5.0, 0.0]].into_dyn()
+    );
+
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let v = x.index(&[1, 2]).unwrap();
+    assert_eq!(v.data().sum(), 5.0);
+    v.backward();
+    assert_eq!(
+        grad_of(&x),
+        array![[0.0, 0.0, 0.0], [0.0, 0.0, 1.0]].into_dyn()
+    );
+}
@@ -0,0 +1,127 @@
+use ndarray::{ArrayD, IxDyn, array};
+use torch_rs::ops::index::TensorIndex;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::arange;
+
+#[test]
+fn test_views_share_storage() {
+    let x = Tensor::new(arange(&[2, 3]));
+    let v = x.view(&[3, 2]).unwrap();
+    let t = x.transpose(0, 1).unwrap();
+    let row = x.select(0, 1).unwrap();
+    let d = x.detach();
+    for view in [&v, &t, &row, &d] {
+        assert!(view.shares_storage(&x));
+    }
+
+    // 通过视图原地修改，原张量与其余视图都能看到
+    row.fill_(-1.0).unwrap();
+    assert_eq!(
+        x.data(),
+        array![[0.0, 1.0, 2.0], [-1.0, -1.0, -1.0]].into_dyn()
+    );
+    assert_eq!(
+        t.data(),
+        array![[0.0, -1.0], [1.0, -1.0], [2.0, -1.0]].into_dyn()
+    );
+    d.add_(&Tensor::ones(&[1])).unwrap();
+    assert_eq!(
+        v.data(),
+        array![[1.0, 2.0], [3.0, 0.0], [0.0, 0.0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_strides_and_offset() {
+    let x = Tensor::new(arange(&[3, 4]));
+    assert_eq!(x.stride(), vec![4, 1]);
+    assert!(x.is_contiguous());
+
+    let t = x.transpose(0, 1).unwrap();
+    assert_eq!(t.stride(), vec![1, 4]);
+    assert!(!t.is_contiguous());
+
+    let col = x.narrow(1, 1, 2).unwrap();
+    assert_eq!(col.storage_offset(), 1);
+    assert_eq!(col.stride(), vec![4, 1]);
+    assert!(!col.is_contiguous());
+
+    let stepped = x
+        .slice(&[(1..).into(), TensorIndex::slice(None, None, 2)])
+        .unwrap();
+    assert_eq!(stepped.storage_offset(), 4);
+    assert_eq!(stepped.stride(), vec![4, 2]);
+    assert_eq!(stepped.data(), array![[4.0, 6.0], [8.0, 10.0]].into_dyn());
+
+    let e = Tensor::ones(&[3, 1]).expand(&[2, 3, 4]).unwrap();
+    assert_eq!(e.stride(), vec![0, 1, 0]);
+    assert_eq!(e.data(), ArrayD::ones(IxDyn(&[2, 3, 4])));
+}
+
+#[test]
+fn test_view_and_contiguous() {
+    let x = Tensor::new(arange(&[2, 3])).require_grad(true);
+    let t = x.transpose(0, 1).unwrap();
+    // 转置后的内存布局无法合并维度，view 失败而 reshape 复制数据
+    assert!(t.view(&[6]).is_err());
+    let flat = t.reshape(&[6]).unwrap();
+    assert!(!flat.shares_storage(&x));
+    assert_eq!(flat.data(), array![0.0, 3.0, 1.0, 4.0, 2.0, 5.0].into_dyn());
+
+    let c = t.contiguous();
+    assert!(c.is_contiguous());
+    assert!(!c.shares_storage(&x));
+    assert_eq!(c.data(), t.data());
+    assert!(x.contiguous().shares_storage(&x));
+
+    // 只拆分、合并连续维度时不要求整体连续
+    let y = Tensor::new(arange(&[2, 3, 4])).permute(&[1, 0, 2]).unwrap();
+    assert!(y.view(&[3, 8]).is_err());
+    let split = y.view(&[3, 2, 2, 2]).unwrap();
+    assert!(split.shares_storage(&y));
+    assert_eq!(
+        split.data(),
+        y.data().into_shape_with_order(vec![3, 2, 2, 2]).unwrap()
+    );
+
+    (&c * &Tensor::from(vec![1.0, 2.0])).sum().backward();
+    assert_eq!(
+        x.0.borrow().grad.clone().unwrap(),
+        array![[1.0, 1.0, 1.0], [2.0, 2.0, 2.0]].into_dyn()
+    );
+}
+
+#[test]
+fn test_version_counter() {
+    let x = Tensor::zeros(&[2]);
+    assert_eq!(x.version(), 0);
+    x.fill_(1.0).unwrap();
+    x.view(&[1, 2]).unwrap().zero_().unwrap();
+    assert_eq!(x.version(), 2);
+    assert_eq!(x.data(), array![0.0, 0.0].into_dyn());
+
+    // 需要梯度的张量不允许原地修改
+    let w = Tensor::ones(&[2]).require_grad(true);
+    assert!(w.fill_(0.0).is_err());
+    assert!(w.view(&[2, 1]).unwrap().zero_().is_err());
+}
+
+#[test]
+#[should_panic(expected = "modified by an inplace operation")]
+fn test_inplace_modification_of_saved_tensor() {
+    let a = Tensor::new(array![1.0, 2.0].into_dyn());
+    let b = Tensor::new(array![3.0, 4.0].into_dyn()).require_grad(true);
+    let c = &a * &b;
+    // 乘法保存了 a 用于计算 b 的梯度
+    a.copy_(&Tensor::from(vec![5.0, 6.0])).unwrap();
+    c.sum().backward();
+}
+
+#[test]
+#[should_panic(expected = "refers to a single memory location")]
+fn test_inplace_write_to_expanded_view() {
+    let e = Tensor::zeros(&[1, 3]).expand(&[2, 3]).unwrap();
+    e.fill_(1.0).unwrap();
+}
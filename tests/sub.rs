@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use ndarray::array;
+    use torch_rs::tensor::Tensor;
+
+    #[test]
+    fn test_tensor_sub() {
+        let a = Tensor::new(array![[5.0, 6.0], [7.0, 8.0]].into_dyn());
+        let b = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn());
+
+        let result = &a - &b;
+
+        let expected = array![[4.0, 4.0], [4.0, 4.0]].into_dyn();
+        assert_eq!(result.data(), expected);
+    }
+
+    #[test]
+    fn test_tensor_sub_grad() {
+        let a = Tensor::new(array![[1.0, 2.0], [3.0, 4.0]].into_dyn()).require_grad(true);
+        let b = Tensor::new(array![[5.0, 6.0], [7.0, 8.0]].into_dyn()).require_grad(true);
+
+        let result = &a - &b;
+        result.backward();
+
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![[1.0, 1.0], [1.0, 1.0]].into_dyn()
+        );
+        assert_eq!(
+            b.0.borrow().grad.clone().unwrap(),
+            array![[-1.0, -1.0], [-1.0, -1.0]].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_tensor_sub_broadcasting_grad() {
+        // [2, 2, 2] - [2, 2]：第二个张量的梯度应在批次维度上求和
+        let batch_tensor =
+            Tensor::new(array![[[1.0, 2.0], [3.0, 4.0]], [[5.0, 6.0], [7.0, 8.0]]].into_dyn())
+                .require_grad(true);
+        let single_tensor =
+            Tensor::new(array![[0.5, 0.5], [0.5, 0.5]].into_dyn()).require_grad(true);
+
+        let result = &batch_tensor - &single_tensor;
+        assert_eq!(
+            result.data(),
+            array![[[0.5, 1.5], [2.5, 3.5]], [[4.5, 5.5], [6.5, 7.5]]].into_dyn()
+        );
+        result.backward();
+
+        assert_eq!(
+            batch_tensor.0.borrow().grad.clone().unwrap(),
+            array![[[1.0, 1.0], [1.0, 1.0]], [[1.0, 1.0], [1.0, 1.0]]].into_dyn()
+        );
+        assert_eq!(
+            single_tensor.0.borrow().grad.clone().unwrap(),
+            array![[-2.0, -2.0], [-2.0, -2.0]].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_tensor_sub_keepdim_broadcasting_grad() {
+        // [2, 3] - [2, 1]：大小为1的维度上的梯度需要求和
+        let a = Tensor::new(array![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]].into_dyn()).require_grad(true);
+        let b = Tensor::new(array![[1.0], [2.0]].into_dyn()).require_grad(true);
+
+        let result = &a - &b;
+        assert_eq!(
+            result.data(),
+            array![[0.0, 1.0, 2.0], [2.0, 3.0, 4.0]].into_dyn()
+        );
+        result.backward();
+
+        assert_eq!(
+            b.0.borrow().grad.clone().unwrap(),
+            array![[-3.0], [-3.0]].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_sub_only_second_requires_grad() {
+        let a = Tensor::new(array![1.0, 2.0, 3.0].into_dyn());
+        let b = Tensor::new(array![4.0, 5.0, 6.0].into_dyn()).require_grad(true);
+
+        let result = (&a - &b).mean();
+        result.backward();
+
+        let expected = ndarray::Array::from_elem(b.shape(), -1.0 / 3.0).into_dyn();
+        assert_eq!(b.0.borrow().grad.clone().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_sub_scalar() {
+        let a = Tensor::from(vec![1.0, 2.0, 3.0]).require_grad(true);
+
+        let result: Tensor = &a - 1.0;
+        assert_eq!(result.data(), array![0.0, 1.0, 2.0].into_dyn());
+
+        let result: Tensor = 1.0 - &a;
+        assert_eq!(result.data(), array![0.0, -1.0, -2.0].into_dyn());
+        result.backward();
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![-1.0, -1.0, -1.0].into_dyn()
+        );
+    }
+
+    #[test]
+    fn test_neg() {
+        let a = Tensor::new(array![[1.0, -2.0], [0.0, 4.0]].into_dyn()).require_grad(true);
+
+        let result = -&a;
+        assert_eq!(result.data(), array![[-1.0, 2.0], [0.0, -4.0]].into_dyn());
+        result.backward();
+        assert_eq!(
+            a.0.borrow().grad.clone().unwrap(),
+            array![[-1.0, -1.0], [-1.0, -1.0]].into_dyn()
+        );
+    }
+}
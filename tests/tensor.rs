@@ -91,7 +91,7 @@ fn test_tensor_shape_operations() {
 fn test_tensor_grad() {
     // 测试 requires_grad 默认值
     let tensor = Tensor::ones(&[2, 3]);
-    assert_eq!(tensor.is_leaf(), true); // 新创建的张量应该是叶节点
+    assert!(tensor.is_leaf()); // 新创建的张量应该是叶节点
 
     // 测试设置 requires_grad
     let tensor = tensor.require_grad(true);
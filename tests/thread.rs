@@ -0,0 +1,136 @@
+use ndarray::{ArrayD, array};
+use std::thread;
+use torch_rs::autograd::{is_grad_enabled, no_grad};
+use torch_rs::nn::Module;
+use torch_rs::nn::linear::Linear;
+use torch_rs::nn::relu::ReLU;
+use torch_rs::nn::sequential::Sequential;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::{assert_rel_close, grad_of};
+
+fn assert_send_sync<T: Send + Sync>() {}
+
+fn model() -> Sequential {
+    Sequential::new(vec![
+        Box::new(Linear::new(3, 4)),
+        Box::new(ReLU::new()),
+        Box::new(Linear::new(4, 2)),
+    ])
+}
+
+fn batch() -> (ArrayD<f32>, ArrayD<f32>) {
+    let x = array![
+        [1.0, -2.0, 0.5],
+        [0.3, 0.7, -1.1],
+        [2.0, 0.1, 0.4],
+        [-0.6, 1.5, 0.9]
+    ];
+    let y = array![[1.0, 0.0], [0.0, 1.0], [0.5, 0.5], [1.0, -1.0]];
+    (x.into_dyn(), y.into_dyn())
+}
+
+#[test]
+fn test_tensors_and_modules_are_send_sync() {
+    assert_send_sync::<Tensor>();
+    assert_send_sync::<Linear>();
+    assert_send_sync::<Sequential>();
+    assert_send_sync::<Box<dyn Module>>();
+}
+
+#[test]
+fn test_shared_model_inference() {
+    let model = model();
+    let (x, _) = batch();
+    let rows: Vec<Tensor> = (0..4)
+        .map(|i| {
+            Tensor::new(x.clone())
+                .select(0, i)
+                .unwrap()
+                .unsqueeze(0)
+                .unwrap()
+        })
+        .collect();
+    let expected: Vec<ArrayD<f32>> = rows.iter().map(|row| model.forward(row).data()).collect();
+
+    let outputs: Vec<ArrayD<f32>> = thread::scope(|scope| {
+        let handles: Vec<_> = rows
+            .iter()
+            .map(|row| {
+                let model = &model;
+                scope.spawn(move || {
+                    let _guard = no_grad();
+                    let output = model.forward(row);
+                    assert!(!output.0.borrow().requires_grad);
+                    output.data()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    for (output, expected) in outputs.iter().zip(&expected) {
+        assert_rel_close(output, expected, 1e-5);
+    }
+    // 梯度模式是线程局部的，其他线程的 no_grad 不影响当前线程
+    assert!(is_grad_enabled());
+}
+
+#[test]
+fn test_data_parallel_gradients() {
+    let model = model();
+    let (x, y) = batch();
+    let loss = |x: &Tensor, y: &Tensor| {
+        let diff = &model.forward(x) - y;
+        (&diff * &diff).sum()
+    };
+
+    loss(&Tensor::new(x.clone()), &Tensor::new(y.clone())).backward();
+    let expected: Vec<ArrayD<f32>> = model
+        .parameters()
+        .iter()
+        .map(|param| param.0.borrow_mut().grad.take().unwrap())
+        .collect();
+
+    // 两个线程各自处理一半数据，梯度累加到共享的参数上
+    thread::scope(|scope| {
+        for shard in 0..2 {
+            let (x, y) = (Tensor::new(x.clone()), Tensor::new(y.clone()));
+            let loss = &loss;
+            scope.spawn(move || {
+                let x = x.narrow(0, shard * 2, 2).unwrap();
+                let y = y.narrow(0, shard * 2, 2).unwrap();
+                loss(&x, &y).backward();
+            });
+        }
+    });
+    for (param, expected) in model.parameters().iter().zip(&expected) {
+        assert_rel_close(&grad_of(param), expected, 1e-5);
+    }
+}
+
+#[test]
+#[should_panic(expected = "already borrowed on this thread")]
+fn test_borrow_mut_while_borrowed_panics() {
+    let x = Tensor::from(vec![1.0, 2.0]);
+    let _data = x.0.borrow();
+    // 与 RefCell 一样报错，而不是等待当前线程自己释放读取
+    x.0.borrow_mut();
+}
+
+#[test]
+fn test_borrow_mut_waits_for_other_threads() {
+    let x = Tensor::from(vec![1.0, 2.0]);
+    let data = x.0.borrow();
+    thread::scope(|scope| {
+        let writer = scope.spawn(|| {
+            x.0.borrow_mut().grad = Some(array![1.0, 1.0].into_dyn());
+        });
+        // 其他线程的写入等待读取结束，不会 panic
+        thread::sleep(std::time::Duration::from_millis(20));
+        assert!(data.grad.is_none());
+        drop(data);
+        writer.join().unwrap();
+    });
+    assert_eq!(grad_of(&x), array![1.0, 1.0].into_dyn());
+}
@@ -0,0 +1,135 @@
+use ndarray::{ArrayD, array};
+use torch_rs::functional;
+use torch_rs::tensor::Tensor;
+
+mod common;
+use common::assert_close;
+
+/// 对输入应用 `f` 并反向传播，返回输出数据和输入梯度
+fn forward_backward(input: &[f32], f: impl Fn(&Tensor) -> Tensor) -> (ArrayD<f32>, ArrayD<f32>) {
+    let x = Tensor::from(input.to_vec()).require_grad(true);
+    let y = f(&x);
+    y.backward();
+    let grad = x.0.borrow().grad.clone().unwrap();
+    (y.data(), grad)
+}
+
+#[test]
+fn test_exp() {
+    let (y, grad) = forward_backward(&[0.0, 1.0, -1.0], |x| x.exp());
+    let expected = array![1.0, 1.0f32.exp(), (-1.0f32).exp()].into_dyn();
+    assert_close(&y, &expected, 1e-5);
+    assert_close(&grad, &expected, 1e-5);
+}
+
+#[test]
+fn test_log() {
+    let (y, grad) = forward_backward(&[1.0, 2.0, 4.0], |x| x.log());
+    assert_close(&y, &array![0.0, 2.0f32.ln(), 4.0f32.ln()].into_dyn(), 1e-5);
+    assert_close(&grad, &array![1.0, 0.5, 0.25].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_log1p() {
+    let (y, grad) = forward_backward(&[0.0, 1.0, 1e-8], functional::log1p);
+    assert_close(&y, &array![0.0, 2.0f32.ln(), 1e-8].into_dyn(), 1e-5);
+    assert_close(&grad, &array![1.0, 0.5, 1.0].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_sqrt_and_rsqrt() {
+    let (y, grad) = forward_backward(&[1.0, 4.0, 9.0], |x| x.sqrt());
+    assert_close(&y, &array![1.0, 2.0, 3.0].into_dyn(), 1e-5);
+    assert_close(&grad, &array![0.5, 0.25, 1.0 / 6.0].into_dyn(), 1e-5);
+
+    let (y, grad) = forward_backward(&[1.0, 4.0], |x| x.rsqrt());
+    assert_close(&y, &array![1.0, 0.5].into_dyn(), 1e-5);
+    // d(x^(-1/2))/dx = -1/2 * x^(-3/2)
+    assert_close(&grad, &array![-0.5, -0.0625].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_abs_and_sign() {
+    let (y, grad) = forward_backward(&[-2.0, 0.0, 3.0], |x| x.abs());
+    assert_close(&y, &array![2.0, 0.0, 3.0].into_dyn(), 1e-5);
+    assert_close(&grad, &array![-1.0, 0.0, 1.0].into_dyn(), 1e-5);
+
+    let (y, grad) = forward_backward(&[-2.0, 0.0, 3.0], functional::sign);
+    assert_close(&y, &array![-1.0, 0.0, 1.0].into_dyn(), 1e-5);
+    assert_close(&grad, &array![0.0, 0.0, 0.0].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_sin_and_cos() {
+    let input = [0.0, 1.0, -2.0];
+    let (y, grad) = forward_backward(&input, |x| x.sin());
+    assert_close(
+        &y,
+        &array![0.0, 1.0f32.sin(), (-2.0f32).sin()].into_dyn(),
+        1e-5,
+    );
+    assert_close(
+        &grad,
+        &array![1.0, 1.0f32.cos(), (-2.0f32).cos()].into_dyn(),
+        1e-5,
+    );
+
+    let (y, grad) = forward_backward(&input, |x| x.cos());
+    assert_close(
+        &y,
+        &array![1.0, 1.0f32.cos(), (-2.0f32).cos()].into_dyn(),
+        1e-5,
+    );
+    assert_close(
+        &grad,
+        &array![0.0, -(1.0f32.sin()), 2.0f32.sin()].into_dyn(),
+        1e-5,
+    );
+}
+
+#[test]
+fn test_tanh() {
+    let (y, grad) = forward_backward(&[0.0, 0.5, -3.0], functional::tanh);
+    let t: Vec<f32> = [0.0f32, 0.5, -3.0].iter().map(|x| x.tanh()).collect();
+    assert_close(
+        &y,
+        &ArrayD::from_shape_vec(vec![3], t.clone()).unwrap(),
+        1e-5,
+    );
+    let dt: Vec<f32> = t.iter().map(|t| 1.0 - t * t).collect();
+    assert_close(&grad, &ArrayD::from_shape_vec(vec![3], dt).unwrap(), 1e-5);
+}
+
+#[test]
+fn test_sigmoid() {
+    let (y, grad) = forward_backward(&[0.0, 2.0, -100.0], functional::sigmoid);
+    let s2 = 1.0 / (1.0 + (-2.0f32).exp());
+    assert_close(&y, &array![0.5, s2, 0.0].into_dyn(), 1e-5);
+    assert_close(&grad, &array![0.25, s2 * (1.0 - s2), 0.0].into_dyn(), 1e-5);
+    assert!(y.iter().all(|v| v.is_finite()));
+}
+
+#[test]
+fn test_softplus() {
+    let (y, grad) = forward_backward(&[0.0, 100.0, -100.0], functional::softplus);
+    // 大输入下不应溢出
+    assert_close(&y, &array![2.0f32.ln(), 100.0, 0.0].into_dyn(), 1e-5);
+    assert_close(&grad, &array![0.5, 1.0, 0.0].into_dyn(), 1e-5);
+}
+
+#[test]
+fn test_unary_chain() {
+    // d/dx exp(sin(x)) = exp(sin(x)) * cos(x)
+    let x = Tensor::from(vec![0.3, -1.2]).require_grad(true);
+    let y = x.sin().exp().mean();
+    y.backward();
+    let expected: Vec<f32> = [0.3f32, -1.2]
+        .iter()
+        .map(|x| x.sin().exp() * x.cos() / 2.0)
+        .collect();
+    assert_close(
+        &x.0.borrow().grad.clone().unwrap(),
+        &ArrayD::from_shape_vec(vec![2], expected).unwrap(),
+        1e-5,
+    );
+}
@@ -0,0 +1,70 @@
+use ndarray::array;
+use torch_rs::autograd::viz::{to_dot, to_dot_named};
+use torch_rs::autograd::{Function, FunctionCtx};
+use torch_rs::functional;
+use torch_rs::nn::Module;
+use torch_rs::nn::linear::Linear;
+use torch_rs::nn::relu::ReLU;
+use torch_rs::nn::sequential::Sequential;
+use torch_rs::tensor::Tensor;
+
+#[test]
+fn test_dot_labels_parameters_from_module_tree() {
+    let model = Sequential::new(vec![Box::new(Linear::new(3, 2)), Box::new(ReLU::new())]);
+    let x = Tensor::new(array![[1.0, -2.0, 0.5]].into_dyn());
+    let target = Tensor::new(array![[1.0, 0.0]].into_dyn());
+    let loss = functional::mse_loss(&model.forward(&x), &target);
+    let dot = to_dot_named(&loss, &model.named_parameters());
+
+    assert!(dot.starts_with("digraph {\n") && dot.ends_with("}\n"));
+    // 输出节点排在最前
+    assert!(
+        dot.contains("t0 [label=\"Mean\\n[]\\nrequires_grad=true\", fillcolor=darkolivegreen1];")
+    );
+    assert!(dot.contains("\"MatMul\\n[1, 2]\\nrequires_grad=true\""));
+    assert!(dot.contains("\"ReLU\\n[1, 2]\\nrequires_grad=true\""));
+    assert!(dot.contains("\"param: 0.w\\n[3, 2]\\nrequires_grad=true\", fillcolor=lightblue"));
+    assert!(dot.contains("\"param: 0.b\\n[2]\\nrequires_grad=true\""));
+    assert!(dot.contains("\"constant\\n[1, 3]\\nrequires_grad=false\", fillcolor=lightgray"));
+    // 同一个父节点作为两个输入时画两条边
+    let node_count = dot.matches(" [label=").count();
+    let edge_count = dot.matches(" -> ").count();
+    assert_eq!(node_count, 10);
+    assert_eq!(edge_count, 10);
+}
+
+struct Twice;
+
+impl Function for Twice {
+    fn forward(&self, _ctx: &mut FunctionCtx, inputs: &[&Tensor]) -> Tensor {
+        inputs[0] * 2.0_f32
+    }
+
+    fn backward(&self, _ctx: &FunctionCtx, grad_output: &Tensor) -> Vec<Option<Tensor>> {
+        vec![Some(grad_output * 2.0_f32)]
+    }
+}
+
+#[test]
+fn test_dot_shared_nodes_and_freed_graph() {
+    let x = Tensor::new(array![1.0, 2.0].into_dyn()).require_grad(true);
+    let h = Twice.apply(&[&x]);
+    let y = (&h.exp() + &h).sum();
+    let dot = to_dot(&y);
+
+    // 自定义函数显示为去掉模块路径的类型名，共享的节点只出现一次
+    assert!(dot.contains("\"Twice\\n[2]"));
+    assert_eq!(
+        dot.matches("\"leaf\\n[2]\\nrequires_grad=true\"").count(),
+        1
+    );
+    assert_eq!(dot.matches(" [label=").count(), 5);
+    assert!(dot.contains("t1 -> t0;"));
+
+    y.backward();
+    let freed = to_dot(&y);
+    assert_eq!(
+        freed,
+        "digraph {\n    node [shape=box, style=filled, fontname=\"monospace\"];\n    t0 [label=\"(graph freed)\\n[]\\nrequires_grad=true\", fillcolor=darkolivegreen1];\n}\n"
+    );
+}